@@ -34,7 +34,7 @@ async fn main() -> Result<()> {
     tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
 
     // Inject user prompts
-    let prompts = vec![
+    let prompts = [
         "Hello! Can you introduce yourself?",
         "What's 15 + 27?",
         "Thank you!",
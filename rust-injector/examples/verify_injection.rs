@@ -1,5 +1,4 @@
 use anyhow::Result;
-use claude_injector::*;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 use std::process::Stdio;
@@ -34,7 +33,7 @@ async fn main() -> Result<()> {
 
     use tokio::io::AsyncWriteExt;
 
-    let messages = vec![
+    let messages = [
         "Hello from Rust!",
         "This is message 2",
         "Final message",
@@ -42,7 +41,7 @@ async fn main() -> Result<()> {
 
     let mut stdin = stdin;
 
-    for (i, msg) in messages.iter().enumerate() {
+    for msg in messages.iter() {
         println!("📤 INJECTING: {}", msg);
 
         stdin.write_all(msg.as_bytes()).await?;
@@ -1,7 +1,8 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use claude_injector::TmuxSpawner;
 use std::env;
-use std::process::Command;
+use std::process::{Command, Stdio};
 
 /// Custom Claude launcher with automatic agent role setting
 #[derive(Parser)]
@@ -19,6 +20,22 @@ struct Cli {
     #[arg(short, long)]
     dir: Option<String>,
 
+    /// Project whose CLAUDE.md and context Claude loads, when it differs from --dir
+    #[arg(long, global = true)]
+    project_root: Option<String>,
+
+    /// Never pass --dangerously-skip-permissions to Claude (same as CLAUDE_INJECTOR_SAFE=1)
+    #[arg(long, global = true)]
+    safe: bool,
+
+    /// Only print warnings and errors
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Print debug output (repeat for trace output)
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
     /// Direct command to pass to Claude
     #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
     args: Vec<String>,
@@ -39,23 +56,34 @@ enum Commands {
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
+    claude_injector::init_cli_logging(cli.quiet, cli.verbose);
+    if cli.safe {
+        claude_injector::enable_safe_mode();
+    }
 
     match cli.command {
         Some(Commands::Launch { agent, prompt }) => {
-            launch_claude_with_agent(&agent, cli.dir, prompt.join(" ").as_str())?;
+            claude_injector::check_explicit_args(&prompt)?;
+            launch_claude_with_agent(&agent, cli.dir, cli.project_root.as_deref(), prompt.join(" ").as_str())?;
         }
         None => {
             // Default mode: use --agent flag or default to master-orchestrator-agent
             let agent = cli.agent.unwrap_or_else(|| "master-orchestrator-agent".to_string());
+            claude_injector::check_explicit_args(&cli.args)?;
             let prompt = cli.args.join(" ");
-            launch_claude_with_agent(&agent, cli.dir, &prompt)?;
+            launch_claude_with_agent(&agent, cli.dir, cli.project_root.as_deref(), &prompt)?;
         }
     }
 
     Ok(())
 }
 
-fn launch_claude_with_agent(agent: &str, working_dir: Option<String>, prompt: &str) -> Result<()> {
+fn launch_claude_with_agent(
+    agent: &str,
+    working_dir: Option<String>,
+    project_root: Option<&str>,
+    prompt: &str,
+) -> Result<()> {
     // Determine working directory
     let working_dir = working_dir.unwrap_or_else(|| {
         env::current_dir()
@@ -64,12 +92,14 @@ fn launch_claude_with_agent(agent: &str, working_dir: Option<String>, prompt: &s
             .to_string()
     });
 
-    println!("🤖 Agent: {}", agent);
-    println!("📁 Directory: {}", working_dir);
+    log::info!("🤖 Agent: {}", agent);
+    log::info!("📁 Directory: {}", working_dir);
+    if let Some(root) = project_root {
+        log::info!("🗂️  Project root: {}", root);
+    }
     if !prompt.is_empty() {
-        println!("📝 Prompt: {}", prompt);
+        log::info!("📝 Prompt: {}", prompt);
     }
-    println!();
 
     // Generate unique session name
     let session_name = format!("cclaude-{}", agent);
@@ -80,141 +110,135 @@ fn launch_claude_with_agent(agent: &str, working_dir: Option<String>, prompt: &s
         anyhow::bail!("tmux is not installed. Install with: sudo apt install tmux");
     }
 
-    println!("🚀 Creating tmux session: {}", session_name);
+    log::info!("🚀 Creating tmux session: {}", session_name);
 
     // Create tmux session with Claude running
-    // IMPORTANT: Use -e flag to pass CCLAUDE_AGENT environment variable INTO the tmux session
-    let env_var = format!("CCLAUDE_AGENT={}", agent);
-
-    let tmux_create = Command::new("tmux")
-        .args(&[
-            "new-session",
-            "-d",              // Detached
-            "-e", &env_var,    // Pass environment variable into session
-            "-s", &session_name,
-            "-c", &working_dir,
-            "claude",
-            "--dangerously-skip-permissions",
-        ])
-        .output()?;
+    // IMPORTANT: Pass CCLAUDE_AGENT INTO the tmux session (via -e, or an env wrapper on tmux < 3.2)
+    let mut env = vec![("CCLAUDE_AGENT", agent)];
+    let mut claude_args = Vec::new();
+    if let Some(root) = project_root {
+        claude_args = TmuxSpawner::project_root_args(root)?;
+        env.push(claude_injector::PROJECT_ROOT_ENV);
+    }
+
+    let command = claude_injector::claude_command(&[claude_injector::DANGEROUS_SKIP_PERMISSIONS], &claude_args)?;
+    let command: Vec<&str> = command.iter().map(String::as_str).collect();
+    let session_args = TmuxSpawner::new_session_args(&session_name, &working_dir, &env, &command)?;
+
+    let tmux_create = Command::new("tmux").args(&session_args).output()?;
 
     if !tmux_create.status.success() {
         // Session might already exist, kill it and retry
         let _ = Command::new("tmux")
-            .args(&["kill-session", "-t", &session_name])
+            .args(["kill-session", "-t", &session_name])
             .output();
 
         // Retry creation with environment variable
-        let retry = Command::new("tmux")
-            .args(&[
-                "new-session",
-                "-d",
-                "-e", &env_var,    // Pass environment variable into session
-                "-s", &session_name,
-                "-c", &working_dir,
-                "claude",
-                "--dangerously-skip-permissions",
-            ])
-            .output()?;
+        let retry = Command::new("tmux").args(&session_args).output()?;
 
         if !retry.status.success() {
             anyhow::bail!("Failed to create tmux session: {}", String::from_utf8_lossy(&retry.stderr));
         }
     }
 
-    println!("✅ Tmux session created: {}", session_name);
-    println!("🔧 Agent: {} (auto-detected via CCLAUDE_AGENT)", agent);
-    println!();
+    log::info!("✅ Tmux session created: {}", session_name);
+    log::info!("🔧 Agent: {} (auto-detected via CCLAUDE_AGENT)", agent);
 
     // Open new terminal and attach to session
     open_terminal_with_tmux(&session_name, agent, &working_dir)?;
 
     // Send initial prompt if provided (AFTER terminal opens)
     if !prompt.is_empty() {
-        println!("⏳ Waiting for Claude to initialize...");
+        log::info!("⏳ Waiting for Claude to initialize...");
         std::thread::sleep(std::time::Duration::from_secs(8));
 
-        println!("📝 Injecting initial prompt...");
+        log::info!("📝 Injecting initial prompt...");
 
-        // Format prompt
-        let formatted_prompt = if prompt.starts_with("task_id:") || prompt.starts_with("subtask_id:") {
-            format!("Call {} to do {}", agent, prompt)
-        } else {
-            prompt.to_string()
-        };
+        let formatted_prompt = format_prompt(agent, prompt);
 
-        // Send message with -l flag (literal)
+        // Send message literally, even if it starts with '-' or ends with ';'
         let send_result = Command::new("tmux")
-            .args(&["send-keys", "-l", "-t", &session_name, &formatted_prompt])
+            .args(TmuxSpawner::send_literal_args(&session_name, &formatted_prompt))
             .output()?;
 
         if !send_result.status.success() {
-            eprintln!("⚠️  Warning: Failed to inject message text");
+            log::warn!("⚠️  Failed to inject message text");
         }
 
         // Send Enter key
         let enter_result = Command::new("tmux")
-            .args(&["send-keys", "-t", &session_name, "Enter"])
+            .args(["send-keys", "-t", &session_name, "Enter"])
             .output()?;
 
         if !enter_result.status.success() {
-            eprintln!("⚠️  Warning: Failed to inject Enter key");
+            log::warn!("⚠️  Failed to inject Enter key");
         } else {
-            println!("✅ Prompt injected successfully");
+            log::info!("✅ Prompt injected successfully");
         }
     }
 
     Ok(())
 }
 
+/// Wrap task references (`task_id: ...`, `Subtask_ID = ...`) in an agent call
+fn format_prompt(agent: &str, prompt: &str) -> String {
+    let key = prompt
+        .trim_start()
+        .split([':', '='])
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+    let is_task_reference = prompt.contains([':', '=']) && (key == "task_id" || key == "subtask_id");
+
+    if is_task_reference {
+        format!("Call {} to do {}", agent, prompt.trim())
+    } else {
+        prompt.to_string()
+    }
+}
+
 fn open_terminal_with_tmux(session_name: &str, agent: &str, working_dir: &str) -> Result<()> {
     // Detect platform and open appropriate terminal
 
     // WSL2 with Windows Terminal
-    if Command::new("wt.exe").arg("--version").output().is_ok() {
-        println!("🪟 Opening Windows Terminal...");
+    if command_exists("wt.exe") {
+        log::info!("🪟 Opening Windows Terminal...");
 
         let attach_cmd = format!("cd '{}' && tmux attach -t {}", working_dir, session_name);
 
-        Command::new("wt.exe")
-            .args(&[
-                "new-tab",
-                "--title",
-                &format!("Claude [{}]", agent),
-                "bash",
-                "-c",
-                &attach_cmd,
-            ])
-            .stdin(std::process::Stdio::null())
-            .stdout(std::process::Stdio::null())
-            .stderr(std::process::Stdio::null())
-            .spawn()?;
-
-        println!("✅ Windows Terminal opened");
+        spawn_detached(Command::new("wt.exe").args([
+            "new-tab",
+            "--title",
+            &format!("Claude [{}]", agent),
+            "bash",
+            "-c",
+            &attach_cmd,
+        ]))?;
+
+        log::info!("✅ Windows Terminal opened");
         return Ok(());
     }
 
     // Linux with gnome-terminal
-    if Command::new("gnome-terminal").arg("--version").output().is_ok() {
-        println!("🐧 Opening GNOME Terminal...");
-
-        Command::new("gnome-terminal")
-            .args(&[
-                "--working-directory", working_dir,
-                "--title", &format!("Claude [{}]", agent),
-                "--",
-                "bash", "-c",
-                &format!("tmux attach -t {}; exec bash", session_name),
-            ])
-            .spawn()?;
-
-        println!("✅ GNOME Terminal opened");
+    if command_exists("gnome-terminal") {
+        log::info!("🐧 Opening GNOME Terminal...");
+
+        spawn_detached(Command::new("gnome-terminal").args([
+            "--working-directory", working_dir,
+            "--title", &format!("Claude [{}]", agent),
+            "--",
+            "bash", "-c",
+            &format!("tmux attach -t {}; exec bash", session_name),
+        ]))?;
+
+        log::info!("✅ GNOME Terminal opened");
         return Ok(());
     }
 
     // macOS with Terminal.app
     if cfg!(target_os = "macos") {
-        println!("🍎 Opening Terminal.app...");
+        log::info!("🍎 Opening Terminal.app...");
 
         let script = format!(
             r#"tell application "Terminal"
@@ -224,19 +248,57 @@ end tell"#,
             working_dir, session_name
         );
 
-        Command::new("osascript")
-            .arg("-e")
-            .arg(&script)
-            .spawn()?;
+        spawn_detached(Command::new("osascript").arg("-e").arg(&script))?;
 
-        println!("✅ Terminal.app opened");
+        log::info!("✅ Terminal.app opened");
         return Ok(());
     }
 
     // Fallback: Print attach command
-    println!("⚠️  No supported terminal found");
-    println!("📝 Manually attach with: tmux attach -t {}", session_name);
-    println!("💡 Or install: wt.exe (WSL2) | gnome-terminal (Linux) | Terminal.app (macOS)");
+    log::warn!("⚠️  No supported terminal found");
+    log::warn!("📝 Manually attach with: tmux attach -t {}", session_name);
+    log::info!("💡 Or install: wt.exe (WSL2) | gnome-terminal (Linux) | Terminal.app (macOS)");
 
     Ok(())
 }
+
+/// Check whether a program is on PATH without running it
+fn command_exists(program: &str) -> bool {
+    env::var_os("PATH")
+        .map(|paths| env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+/// Start a terminal process without waiting for it or sharing our stdio
+///
+/// The child gets its own process group so it is not tied to this command's
+/// terminal, and the launcher returns as soon as the process has started.
+fn spawn_detached(command: &mut Command) -> Result<()> {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+
+    command
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("Failed to start {:?}: {}", command.get_program(), e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+}
@@ -1,16 +1,59 @@
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 use claude_injector::*;
-use std::fs;
 use std::path::PathBuf;
 use serde::{Deserialize, Serialize};
 
 #[derive(Parser)]
 #[command(name = "claude-inject")]
 #[command(about = "CLI tool for injecting messages into Claude sessions", long_about = None)]
+#[command(after_help = "Exit codes: 0 success, 1 other error, 2 session not found, \
+3 tmux unavailable, 4 permission denied (e.g. TIOCSTI disabled), 5 timeout, 6 rate limited, 7 claude not installed")]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Never pass --dangerously-skip-permissions to Claude (same as CLAUDE_INJECTOR_SAFE=1)
+    #[arg(long, global = true)]
+    safe: bool,
+
+    /// Only print results, warnings and errors
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Print debug output (repeat for trace output)
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Only detect Claude processes owned by this user (default: the current user)
+    #[arg(long, global = true, conflicts_with = "all_users")]
+    user: Option<String>,
+
+    /// Detect Claude processes of every user, not just your own
+    #[arg(long, global = true)]
+    all_users: bool,
+
+    /// Print only a tab-separated result line on success (`injected`, `queued` or
+    /// `spawned`, then target and detail) and `error<TAB>code<TAB>message` on failure
+    #[arg(long, global = true)]
+    porcelain: bool,
+
+    /// Use the home-level worker registry even inside a project with .claude-workers.json
+    #[arg(long, global = true)]
+    global: bool,
+
+    /// Text put before every injected message [config: injection_prefix]
+    #[arg(long, global = true)]
+    prefix: Option<String>,
+
+    /// Text put after every injected message [config: injection_suffix]
+    #[arg(long, global = true)]
+    suffix: Option<String>,
+
+    /// End injections that carry a correlation id with a hidden
+    /// `<!-- correlation-id: ... -->` marker [config: correlation_markers]
+    #[arg(long, global = true)]
+    correlation_markers: bool,
 }
 
 #[derive(Subcommand)]
@@ -24,38 +67,144 @@ enum Commands {
         /// Initial prompt (optional)
         #[arg(short, long)]
         prompt: Option<String>,
+
+        /// Base the spawn on this project (project id or project path)
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Base the spawn on this detected session (id or id prefix)
+        #[arg(long)]
+        session: Option<String>,
+
+        /// Run Claude in the background and return immediately
+        #[arg(long)]
+        detach: bool,
     },
 
     /// Inject a message into a managed session (spawned by this tool)
+    ///
+    /// Foreground sessions are reached through the daemon socket their `spawn`
+    /// serves; when nothing is listening (the `spawn` has exited) the command
+    /// fails with exit code 2. Detached sessions are written to directly.
     Inject {
         /// Session ID to inject into
+        #[arg(short, long, required_unless_present = "project_path")]
+        id: Option<String>,
+
+        /// Select the managed session by its project directory instead of ID
+        #[arg(long, conflicts_with = "id")]
+        project_path: Option<String>,
+
+        /// Message to inject (will be sent as user input)
+        #[arg(short, long, required_unless_present_any = ["preset", "file"])]
+        message: Option<String>,
+
+        /// Inject a payload saved in ~/.config/claude-injector/presets/<NAME>.json instead
+        #[arg(long, value_name = "NAME", conflicts_with = "message")]
+        preset: Option<String>,
+
+        /// Inject the payload in this .json or .yaml file instead
+        #[arg(long, conflicts_with_all = ["message", "preset"])]
+        file: Option<PathBuf>,
+
+        /// Queue the message; the session delivers it after earlier responses
+        #[arg(long)]
+        queue: bool,
+
+        /// Drop the queued message instead of delivering it after this long (e.g. 10m)
+        #[arg(long, requires = "queue", value_parser = parse_duration)]
+        ttl: Option<std::time::Duration>,
+
+        /// Id tying this injection to a request elsewhere (logged and kept in metadata)
+        #[arg(long)]
+        correlation_id: Option<String>,
+    },
+
+    /// Show messages queued for a managed session that were not delivered yet
+    QueueList {
+        /// Session ID (managed ID or Claude session ID)
         #[arg(short, long)]
         id: String,
+    },
 
-        /// Message to inject (will be sent as user input)
+    /// Cancel messages queued for a managed session before they are delivered
+    QueueClear {
+        /// Session ID (managed ID or Claude session ID)
         #[arg(short, long)]
-        message: String,
+        id: String,
     },
 
     /// Inject into ANY existing Claude session via terminal device (PTY)
+    ///
+    /// Without --id, --project-path or --device, an interactive terminal gets a
+    /// fuzzy picker of running sessions.
     Pty {
         /// Session ID to inject into
         #[arg(short, long)]
-        id: String,
+        id: Option<String>,
+
+        /// Select the running session by its project directory instead of ID
+        #[arg(long, conflicts_with = "id")]
+        project_path: Option<String>,
+
+        /// Inject into this terminal device as shown by `find` (e.g. /dev/pts/3)
+        #[arg(long, conflicts_with_all = ["id", "project_path"])]
+        device: Option<String>,
+
+        /// Message to inject (will be sent as user input)
+        #[arg(short, long)]
+        message: String,
+
+        /// Refuse messages containing control characters (Ctrl-C, escape sequences, ...)
+        #[arg(long)]
+        sanitize: bool,
+
+        /// Send the message even when it is blank, pressing Enter on an empty prompt
+        #[arg(long)]
+        allow_empty: bool,
+    },
+
+    /// Inject directly into a known terminal device (e.g. /dev/pts/3)
+    PtyRaw {
+        /// Terminal device path
+        #[arg(short, long)]
+        device: String,
 
         /// Message to inject (will be sent as user input)
         #[arg(short, long)]
         message: String,
+
+        /// Write to the device as output instead of injecting keyboard input
+        #[arg(long)]
+        direct: bool,
+
+        /// Refuse messages containing control characters (Ctrl-C, escape sequences, ...)
+        #[arg(long)]
+        sanitize: bool,
+
+        /// Send the message even when it is blank, pressing Enter on an empty prompt
+        #[arg(long)]
+        allow_empty: bool,
     },
 
     /// List active managed sessions
     List,
 
     /// Stop a running session
+    ///
+    /// Without --id, stops every managed session matching all given filters.
     Stop {
         /// Session ID to stop
-        #[arg(short, long)]
-        id: String,
+        #[arg(short, long, required_unless_present_any = ["project_path_prefix", "older_than"])]
+        id: Option<String>,
+
+        /// Stop sessions whose project directory is this directory or below it
+        #[arg(long, conflicts_with = "id")]
+        project_path_prefix: Option<String>,
+
+        /// Stop sessions started longer ago than this (e.g. 1h)
+        #[arg(long, conflicts_with = "id", value_parser = parse_duration)]
+        older_than: Option<std::time::Duration>,
     },
 
     /// Find existing Claude sessions by ID
@@ -65,6 +214,31 @@ enum Commands {
         id: Option<String>,
     },
 
+    /// List everything that can be injected into, with the command that reaches it
+    ///
+    /// Merges managed sessions, running Claude processes reached through their
+    /// terminal, and tmux workers into one list.
+    Targets {
+        /// Output format (table, json)
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
+
+    /// Move a detached managed session into a visible tmux worker, continuing its conversation
+    Promote {
+        /// Managed session ID; becomes the worker name
+        #[arg(long)]
+        id: String,
+
+        /// Agent type recorded for the worker.
+        /// Defaults to $CLAUDE_DEFAULT_AGENT when omitted.
+        #[arg(short, long)]
+        agent: Option<String>,
+    },
+
+    /// Show session count and disk usage per project, largest first
+    Stats,
+
     /// Spawn Claude in a tmux session (visible + injectable)
     Tmux {
         /// Tmux session name
@@ -74,17 +248,53 @@ enum Commands {
         /// Working directory for Claude
         #[arg(short = 'd', long)]
         dir: Option<String>,
+
+        /// Project whose CLAUDE.md and context Claude loads, when it differs from --dir
+        #[arg(long)]
+        project_root: Option<String>,
+
+        /// Verify injection works by having Claude answer a test prompt
+        #[arg(long)]
+        self_test: bool,
     },
 
     /// Inject message into a tmux Claude session
     TmuxInject {
-        /// Tmux session name
-        #[arg(short = 'n', long)]
-        name: String,
+        /// Tmux session name (its Claude pane is found), or a session:window.pane target
+        #[arg(short = 'n', long, required_unless_present = "project_path")]
+        name: Option<String>,
+
+        /// Select the tmux session running Claude in this project directory
+        #[arg(long, conflicts_with = "name")]
+        project_path: Option<String>,
 
         /// Message to inject
         #[arg(short, long)]
         message: String,
+
+        /// Fail instead of leaving copy-mode when the pane is scrolled back
+        #[arg(long)]
+        fail_on_copy_mode: bool,
+
+        /// Wait up to this long for Claude to finish generating before injecting (e.g. 2m)
+        #[arg(long, value_parser = parse_duration)]
+        wait_idle: Option<std::time::Duration>,
+
+        /// Least time between messages to one worker (e.g. 5s) [config: min_inject_interval_secs]
+        #[arg(long, value_parser = parse_duration)]
+        min_interval: Option<std::time::Duration>,
+
+        /// Fail with exit code 6 instead of waiting when the worker was messaged too recently
+        #[arg(long)]
+        fail_on_rate_limit: bool,
+
+        /// Send the message even when it is blank, pressing Enter on an empty prompt
+        #[arg(long)]
+        allow_empty: bool,
+
+        /// Inject into the session on this host over ssh (no idle wait or rate limit)
+        #[arg(long, value_name = "USER@HOST", conflicts_with_all = ["project_path", "fail_on_copy_mode", "wait_idle", "min_interval", "fail_on_rate_limit"])]
+        ssh: Option<String>,
     },
 
     /// Spawn a worker with agent type (auto-registered)
@@ -93,9 +303,10 @@ enum Commands {
         #[arg(short, long)]
         name: String,
 
-        /// Agent type (e.g., coding-agent, test-orchestrator-agent)
+        /// Agent type (e.g., coding-agent, test-orchestrator-agent).
+        /// Defaults to $CLAUDE_DEFAULT_AGENT when omitted.
         #[arg(short, long)]
-        agent: String,
+        agent: Option<String>,
 
         /// Working directory
         #[arg(short, long)]
@@ -105,14 +316,30 @@ enum Commands {
         #[arg(short, long)]
         task_id: Option<String>,
 
-        /// Initial prompt to send after spawn
+        /// Initial prompt to send after spawn [config: agent_prompts.<agent>]
         #[arg(short = 'p', long)]
         prompt: Option<String>,
+
+        /// Verify injection works by having Claude answer a test prompt
+        #[arg(long)]
+        self_test: bool,
+
+        /// Stop and unregister the worker after this long (e.g. 2h); enforced by `daemon`
+        #[arg(long, value_parser = parse_duration)]
+        ttl: Option<std::time::Duration>,
+
+        /// Continue this Claude session (`claude --resume`) instead of starting fresh
+        #[arg(long)]
+        resume: Option<String>,
+
+        /// Project whose CLAUDE.md and context Claude loads, when it differs from --dir
+        #[arg(long)]
+        project_root: Option<String>,
     },
 
     /// List all registered workers
     ListWorkers {
-        /// Output format
+        /// Output format (table, json, csv)
         #[arg(short, long, default_value = "table")]
         format: String,
 
@@ -123,6 +350,14 @@ enum Commands {
         /// Filter by status
         #[arg(long)]
         status: Option<String>,
+
+        /// List the workers in the home-level registry of this host, read over ssh
+        #[arg(long, value_name = "USER@HOST")]
+        ssh: Option<String>,
+
+        /// Save workers whose tmux session is gone as stopped (otherwise only shown so)
+        #[arg(long, conflicts_with = "ssh")]
+        reconcile: bool,
     },
 
     /// Get worker status
@@ -130,6 +365,54 @@ enum Commands {
         /// Worker name
         #[arg(short, long)]
         name: String,
+
+        /// Look the worker up in the home-level registry of this host, over ssh
+        #[arg(long, value_name = "USER@HOST")]
+        ssh: Option<String>,
+    },
+
+    /// Block until a worker reaches a status (exit code 5 on timeout)
+    WorkerWait {
+        /// Worker name
+        #[arg(short, long)]
+        name: String,
+
+        /// Status to wait for (e.g. ready, idle)
+        #[arg(short, long, default_value = "idle", value_parser = WorkerStatus::parse)]
+        status: WorkerStatus,
+
+        /// Longest to wait (e.g. 60s, 5m)
+        #[arg(short, long, default_value = "60s", value_parser = parse_duration)]
+        timeout: std::time::Duration,
+    },
+
+    /// Save a worker's pane as a PNG image, colours included
+    WorkerSnapshot {
+        /// Worker name
+        #[arg(short, long)]
+        name: String,
+
+        /// PNG file to write (plain text goes to a .txt beside it when no font is found)
+        #[arg(short, long)]
+        out: PathBuf,
+    },
+
+    /// Print what a worker's tmux pane currently shows
+    WorkerOutput {
+        /// Worker name
+        #[arg(short, long)]
+        name: String,
+
+        /// Start this many lines back in the scrollback instead of at the visible screen
+        #[arg(short, long)]
+        lines: Option<usize>,
+    },
+
+    /// Re-resolve the Claude session a worker is running and update the registry
+    WorkerRefresh {
+        /// Worker name
+        #[arg(short, long)]
+        name: String,
     },
 
     /// Stop a worker
@@ -142,199 +425,1146 @@ enum Commands {
         #[arg(short, long)]
         force: bool,
     },
-}
 
-#[derive(Serialize, Deserialize)]
-struct SessionRegistry {
-    sessions: std::collections::HashMap<String, SessionInfo>,
-}
+    /// Feed a worker prompts from a file, one line each time it goes idle
+    WorkerFeed {
+        /// Worker name
+        #[arg(short, long)]
+        name: String,
 
-#[derive(Serialize, Deserialize, Clone)]
-struct SessionInfo {
-    custom_id: String,
-    claude_session_id: String,
-    project_path: String,
-    started_at: u64,
-}
+        /// File with one prompt per line; lines are removed once sent (append under `flock <file>.lock`)
+        #[arg(short = 'f', long)]
+        queue: PathBuf,
 
-fn get_registry_path() -> PathBuf {
-    let home = dirs::home_dir().expect("Cannot find home directory");
-    home.join(".claude-injector-registry.json")
-}
+        /// Longest to wait for the worker to go idle before each prompt (e.g. 30m)
+        #[arg(short, long, default_value = "30m", value_parser = parse_duration)]
+        timeout: std::time::Duration,
+    },
 
-fn load_registry() -> Result<SessionRegistry> {
-    let path = get_registry_path();
-    if !path.exists() {
-        return Ok(SessionRegistry {
-            sessions: std::collections::HashMap::new(),
-        });
-    }
+    /// Remove a worker from the registry but leave its tmux session running
+    ForgetWorker {
+        /// Worker name
+        #[arg(short, long)]
+        name: String,
+    },
 
-    let content = fs::read_to_string(&path)?;
-    let registry: SessionRegistry = serde_json::from_str(&content)?;
-    Ok(registry)
-}
+    /// List tmux sessions running Claude that are not registered as workers
+    DiscoverWorkers {
+        /// Register them as workers, with the agent type read from their transcripts
+        #[arg(long)]
+        adopt: bool,
+    },
 
-fn save_registry(registry: &SessionRegistry) -> Result<()> {
-    let path = get_registry_path();
-    let content = serde_json::to_string_pretty(registry)?;
-    fs::write(&path, content)?;
-    Ok(())
-}
+    /// Check the worker registry for damaged entries (fails if any are found)
+    RegistryCheck {
+        /// Save the repaired registry, removing unreadable entries, instead of failing
+        #[arg(long)]
+        repair: bool,
+    },
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
+    /// Follow the recent output of every live worker, prefixed with its name
+    WorkersTail {
+        /// Pane lines captured per worker on each refresh
+        #[arg(short, long, default_value_t = 10)]
+        lines: usize,
 
-    match cli.command {
-        Commands::Spawn { id, prompt } => {
-            println!("🚀 Spawning Claude session with ID: {}", id);
+        /// Time between refreshes (e.g. 2s, 500ms)
+        #[arg(short, long, default_value = "2s", value_parser = parse_duration)]
+        interval: std::time::Duration,
+    },
 
-            // Detect available sessions
-            let detector = SessionDetector::new()?;
-            let all_sessions = detector.get_all_sessions()?;
+    /// List workers that have been stopped, most recent first
+    WorkerHistory {
+        /// Output format (table, json)
+        #[arg(short, long, default_value = "table")]
+        format: String,
 
-            if all_sessions.is_empty() {
-                anyhow::bail!("No Claude sessions found. Create one first with: cd /some/project && claude");
-            }
+        /// Show at most this many workers
+        #[arg(short, long)]
+        limit: Option<usize>,
+    },
 
-            let session = all_sessions.values().next().unwrap()[0].clone();
-            println!("📁 Using base session: {}", session.project_path);
+    /// Run a shell command in a new window of a worker's tmux session
+    WorkerExec {
+        /// Worker name
+        #[arg(short, long)]
+        name: String,
 
-            // Start Claude process
-            let manager = ClaudeProcessManager::new();
+        /// Shell command to run in the worker's directory
+        #[arg(short, long)]
+        cmd: String,
 
-            let initial_prompt = prompt.unwrap_or_else(|| {
-                "I am ready to receive injected messages.".to_string()
-            });
+        /// Give up waiting for the command after this long (e.g. 30s, 5m)
+        #[arg(short, long, default_value = "60s", value_parser = parse_duration)]
+        timeout: std::time::Duration,
+    },
 
-            let claude_session_id = manager
-                .start_session(session.clone(), Some(initial_prompt))
-                .await
-                .context("Failed to start Claude session")?;
+    /// Relay the latest assistant output of one session into a worker as context
+    Relay {
+        /// Source Claude session ID (or tmux session name)
+        #[arg(long)]
+        from: String,
 
-            println!("✅ Claude process started: {}", claude_session_id);
+        /// Target worker name
+        #[arg(long)]
+        to: String,
+    },
 
-            // Save to registry
-            let mut registry = load_registry()?;
-            registry.sessions.insert(
-                id.clone(),
-                SessionInfo {
-                    custom_id: id.clone(),
-                    claude_session_id: claude_session_id.clone(),
-                    project_path: session.project_path,
-                    started_at: std::time::SystemTime::now()
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .unwrap()
-                        .as_secs(),
-                },
-            );
-            save_registry(&registry)?;
+    /// Re-send turns of a past session's transcript into a worker, one at a time
+    Replay {
+        /// Claude session ID whose transcript is replayed
+        #[arg(long)]
+        from: String,
 
-            println!("\n💡 Session registered! Now you can inject messages:");
-            println!("   claude-inject inject --id {} --message \"Your message here\"", id);
-            println!("\n⏳ Session will run in background. Stop with:");
-            println!("   claude-inject stop --id {}", id);
+        /// Target worker name (or tmux session name)
+        #[arg(long)]
+        to: String,
 
-            // Keep process alive
-            println!("\n🔄 Session running... Press Ctrl+C to stop");
-            tokio::signal::ctrl_c().await?;
+        /// Roles to replay; assistant turns are sent as context
+        #[arg(long, value_delimiter = ',', default_value = "user")]
+        roles: Vec<String>,
 
-            // Cleanup
-            manager.stop_session(&claude_session_id).await?;
-            let mut registry = load_registry()?;
-            registry.sessions.remove(&id);
-            save_registry(&registry)?;
+        /// Leave out the first selected turn (often a long paste)
+        #[arg(long)]
+        skip_first: bool,
 
-            println!("🛑 Session stopped");
-        }
+        /// Transcript positions to consider, 0-based and end-exclusive (e.g. 2:10, 5:, :4)
+        #[arg(long, value_parser = ReplayFilter::parse_range)]
+        range: Option<(Option<usize>, Option<usize>)>,
 
-        Commands::Inject { id, message } => {
-            println!("📤 Injecting message into MANAGED session: {}", id);
+        /// Longest to wait for Claude to settle after each turn (e.g. 90s, 5m)
+        #[arg(short, long, default_value = "5m", value_parser = parse_duration)]
+        timeout: std::time::Duration,
 
-            let registry = load_registry()?;
-            let session_info = registry
-                .sessions
-                .get(&id)
-                .context(format!("Session '{}' not found. Is it running?", id))?;
+        /// Only list the turns that would be sent
+        #[arg(long)]
+        dry_run: bool,
+    },
 
-            println!("📝 Message: {}", message);
+    /// Export a session's transcript
+    Export {
+        /// Claude session ID to export
+        #[arg(long)]
+        id: String,
 
-            let manager = ClaudeProcessManager::new();
+        /// Output format (markdown, json, jsonl, plain)
+        #[arg(short, long, default_value = "markdown", value_parser = TranscriptFormat::parse)]
+        format: TranscriptFormat,
 
-            let payload = InjectionPayload::user_prompt(message);
+        /// Roles to include
+        #[arg(long, value_delimiter = ',', default_value = "user,assistant")]
+        roles: Vec<String>,
 
-            manager
-                .inject(&session_info.claude_session_id, payload)
-                .await
-                .context("Failed to inject message")?;
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+    },
 
-            println!("✅ Message injected successfully!");
-        }
+    /// Notify a worker of CI test results from a JUnit XML or JSON file
+    CiNotify {
+        /// Target worker name (or tmux session name)
+        #[arg(long)]
+        to: String,
 
-        Commands::Pty { id, message } => {
-            println!("📤 Injecting into EXISTING Claude session via PTY: {}", id);
-            println!("📝 Message: {}", message);
-            println!();
+        /// Results file (JUnit XML, or JSON with `failed_tests`)
+        #[arg(short, long)]
+        file: PathBuf,
 
-            PtyInjector::inject_to_session(&id, &message)?;
-        }
+        /// Pipeline name shown to the worker
+        #[arg(short, long)]
+        pipeline: String,
 
-        Commands::List => {
-            let registry = load_registry()?;
+        /// Link to the full CI log
+        #[arg(long)]
+        log_url: String,
+    },
 
-            if registry.sessions.is_empty() {
-                println!("No active sessions");
-                return Ok(());
-            }
+    /// Tell a worker that a blocker raised earlier is resolved
+    BlockerCleared {
+        /// Target worker name (or tmux session name)
+        #[arg(long)]
+        to: String,
 
-            println!("Active sessions:");
-            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        /// The blocker being cleared (test name, pipeline or correlation id)
+        #[arg(short, long)]
+        blocker_ref: String,
 
-            for (id, info) in &registry.sessions {
-                let age = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs()
-                    - info.started_at;
+        /// What resolved it
+        #[arg(short, long)]
+        resolution: String,
+    },
 
-                println!("\n  ID: {}", id);
-                println!("  Claude Session: {}", info.claude_session_id);
-                println!("  Project: {}", info.project_path);
-                println!("  Running for: {}s", age);
+    /// Send one message to every live worker, optionally filtered by agent or status
+    BroadcastWorkers {
+        /// Message to send to every matching worker
+        #[arg(short, long)]
+        message: String,
+
+        /// Only workers running this agent type
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// Only workers with this status (e.g. idle, ready)
+        #[arg(long, value_parser = WorkerStatus::parse)]
+        status: Option<WorkerStatus>,
+
+        /// Send even when more workers match than allowed [config: max_broadcast_targets]
+        #[arg(long)]
+        confirm: bool,
+    },
+
+    /// Ask workers for a status report and summarize their answers
+    PollStatus {
+        /// Worker to poll
+        #[arg(short, long, required_unless_present = "all", conflicts_with = "all")]
+        name: Option<String>,
+
+        /// Poll every worker whose tmux session is running
+        #[arg(long)]
+        all: bool,
+
+        /// Poll even when there are more workers than allowed [config: max_broadcast_targets]
+        #[arg(long)]
+        confirm: bool,
+
+        /// Structure the workers should answer in
+        #[arg(long, default_value = DEFAULT_STATUS_FORMAT)]
+        format_hint: String,
+
+        /// How long to wait for each answer to settle (e.g. 90s, 5m)
+        #[arg(short, long, default_value = "120s", value_parser = parse_duration)]
+        timeout: std::time::Duration,
+
+        /// Output format (table, json)
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
+
+    /// Ask a worker to call an MCP tool
+    McpCall {
+        /// Target worker name (or tmux session name)
+        #[arg(long)]
+        to: String,
+
+        /// Full tool name (e.g. mcp__agenthub_http__call_agent)
+        #[arg(short, long)]
+        tool: String,
+
+        /// Tool arguments as JSON (an object or a string)
+        #[arg(short, long)]
+        args: Option<String>,
+    },
+
+    /// Run continuous worker housekeeping until interrupted
+    Daemon {
+        /// Time between housekeeping passes (e.g. 10s, 1m) [config: idle_poll_interval_secs]
+        #[arg(short, long, value_parser = parse_duration)]
+        interval: Option<std::time::Duration>,
+
+        /// Trailing pane lines compared for idle detection [config: idle_lines]
+        #[arg(long)]
+        idle_lines: Option<usize>,
+
+        /// Unchanged passes before a worker is marked idle [config: idle_stable_cycles]
+        #[arg(long)]
+        idle_stable_cycles: Option<u32>,
+
+        /// Unix socket path to stream worker events on (JSON lines)
+        #[arg(long)]
+        events: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct SessionRegistry {
+    sessions: std::collections::HashMap<String, SessionInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct SessionInfo {
+    custom_id: String,
+    claude_session_id: String,
+    project_path: String,
+    started_at: u64,
+    /// Set when the session was spawned with --detach
+    #[serde(default)]
+    detached: Option<DetachedSession>,
+}
+
+impl SessionInfo {
+    /// The session as [`SessionFilter`] sees it, created when it was started
+    fn as_session(&self) -> ClaudeSession {
+        ClaudeSession {
+            session_id: self.claude_session_id.clone(),
+            project_id: encode_project_path(&self.project_path),
+            project_path: self.project_path.clone(),
+            created_at: self.started_at,
+            first_message: None,
+            model: None,
+            jsonl_path: PathBuf::new(),
+        }
+    }
+}
+
+/// Answer structure `poll-status` asks for unless --format-hint is given
+const DEFAULT_STATUS_FORMAT: &str = "STATUS: <one line>\nBLOCKERS: <none, or one per line>\nNEXT: <one line>";
+
+/// Widest response column in the `poll-status` table
+const STATUS_COLUMN_WIDTH: usize = 100;
+
+/// Quiet time after which a queued message's response counts as finished
+const QUEUE_IDLE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// Pane lines `worker-status` shows as the worker's last output
+const STATUS_OUTPUT_LINES: usize = 5;
+
+/// Messages from a worker's history shown by `worker-status`
+const STATUS_HISTORY_MESSAGES: usize = 3;
+
+/// Characters of each history message shown by `worker-status`
+const STATUS_HISTORY_PREVIEW: usize = 60;
+
+/// Longest a queued message may keep Claude answering
+const QUEUE_RESPONSE_LIMIT: std::time::Duration = std::time::Duration::from_secs(600);
+
+/// Claude session id behind a managed session id, or the id itself if it is not managed
+fn claude_session_id(registry: &SessionRegistry, id: &str) -> String {
+    registry
+        .sessions
+        .get(id)
+        .map(|info| info.claude_session_id.clone())
+        .unwrap_or_else(|| id.to_string())
+}
+
+/// Environment variable providing the agent type when `--agent` is omitted
+const DEFAULT_AGENT_ENV: &str = "CLAUDE_DEFAULT_AGENT";
+
+/// Agent type recorded for promoted sessions when none is given or configured
+const UNASSIGNED_AGENT: &str = "unassigned";
+
+/// Use the explicit agent, falling back to $CLAUDE_DEFAULT_AGENT
+fn resolve_agent(agent: Option<String>) -> Result<String> {
+    agent
+        .or_else(|| {
+            std::env::var(DEFAULT_AGENT_ENV)
+                .ok()
+                .filter(|a| !a.trim().is_empty())
+        })
+        .context(format!(
+            "No agent specified. Pass --agent or set {}",
+            DEFAULT_AGENT_ENV
+        ))
+}
+
+/// Find the single managed session registered for a project directory
+fn resolve_managed_by_project_path(registry: &SessionRegistry, path: &str) -> Result<String> {
+    let target = normalize_path(path);
+
+    let matches: Vec<&String> = registry
+        .sessions
+        .iter()
+        .filter(|(_, info)| normalize_path(&info.project_path) == target)
+        .map(|(id, _)| id)
+        .collect();
+
+    match matches.as_slice() {
+        [] => anyhow::bail!("No managed session found in {}", target),
+        [id] => Ok((*id).clone()),
+        ids => anyhow::bail!(
+            "{} managed sessions found in {}: {:?}. Use --id to pick one",
+            ids.len(),
+            target,
+            ids
+        ),
+    }
+}
+
+/// Choose the detected session a spawn is based on
+///
+/// Explicit `--session`/`--project` flags win. Otherwise an interactive
+/// terminal gets a fuzzy picker, and non-interactive callers get the most
+/// recent session.
+fn select_base_session(
+    all_sessions: &std::collections::HashMap<String, Vec<ClaudeSession>>,
+    project: Option<&str>,
+    session: Option<&str>,
+) -> Result<ClaudeSession> {
+    let mut candidates: Vec<&ClaudeSession> = all_sessions.values().flatten().collect();
+
+    if let Some(project) = project {
+        let project = project.trim_end_matches('/');
+        candidates.retain(|s| s.project_id == project || s.project_path.trim_end_matches('/') == project);
+        if candidates.is_empty() {
+            anyhow::bail!("No sessions found for project '{}'", project);
+        }
+    }
+
+    if let Some(session_id) = session {
+        candidates.retain(|s| s.session_id.starts_with(session_id));
+        match candidates.len() {
+            0 => anyhow::bail!("No session matches '{}'", session_id),
+            1 => {}
+            n => anyhow::bail!("Session '{}' is ambiguous ({} matches)", session_id, n),
+        }
+    }
+
+    // Newest first so the default choice is deterministic
+    candidates.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+
+    if candidates.len() > 1 && project.is_none() && session.is_none() {
+        use std::io::IsTerminal;
+
+        if std::io::stdin().is_terminal() && std::io::stdout().is_terminal() {
+            return pick_session(&candidates);
+        }
+    }
+
+    candidates
+        .first()
+        .map(|s| (*s).clone())
+        .context("No Claude sessions found")
+}
+
+/// Rows the interactive pickers show at once
+const PICKER_ROWS: usize = 20;
+
+/// Let the user fuzzy-search the candidate sessions and pick a base session
+fn pick_session(candidates: &[&ClaudeSession]) -> Result<ClaudeSession> {
+    let labels: Vec<String> = candidates
+        .iter()
+        .map(|session| {
+            let preview = session
+                .first_message
+                .as_deref()
+                .map(|m| m.chars().take(50).collect::<String>())
+                .unwrap_or_default();
+            format!("{}  {}  {}", session.session_id, session.project_path, preview)
+        })
+        .collect();
+
+    let choice = dialoguer::FuzzySelect::new()
+        .with_prompt("Base session (type to filter)")
+        .items(&labels)
+        .default(0)
+        .max_length(PICKER_ROWS)
+        .interact_opt()?
+        .context("No session picked")?;
+
+    Ok(candidates[choice].clone())
+}
+
+/// A running Claude chosen in the `pty` picker
+enum PtyTarget {
+    /// A process mapped to its session
+    Session(String),
+    /// A process whose session is unknown, reached through its terminal
+    Device(String),
+}
+
+/// Something `claude-inject` can inject into, as listed by `targets`
+#[derive(Serialize)]
+struct InjectTarget {
+    /// How the target is reached: `managed`, `pty` or `tmux`
+    method: &'static str,
+    /// Id, device or worker name to pass to the command
+    target: String,
+    project: String,
+    /// Command line that injects into the target
+    command: String,
+}
+
+/// Managed sessions, live tmux workers and the other running Claude processes
+///
+/// A running process that is already listed as a managed session or worker
+/// is not listed again; Claude Desktop, which has no terminal, is left out.
+fn injectable_targets() -> Result<Vec<InjectTarget>> {
+    let mut targets = Vec::new();
+    let mut listed_sessions = std::collections::HashSet::new();
+
+    let registry = load_registry()?;
+    let mut managed: Vec<(&String, &SessionInfo)> = registry.sessions.iter().collect();
+    managed.sort_by_key(|(id, _)| id.as_str());
+    for (id, info) in managed {
+        listed_sessions.insert(info.claude_session_id.clone());
+        targets.push(InjectTarget {
+            method: "managed",
+            target: id.clone(),
+            project: info.project_path.clone(),
+            command: format!("claude-inject inject --id {} -m \"...\"", id),
+        });
+    }
+
+    let mut workers: Vec<WorkerInfo> = WorkerRegistry::load()?
+        .live_view()
+        .into_iter()
+        .filter(|w| w.status != WorkerStatus::Stopped)
+        .collect();
+    workers.sort_by(|a, b| a.name.cmp(&b.name));
+    for worker in workers {
+        if let Some(ref session_id) = worker.session_id {
+            listed_sessions.insert(session_id.clone());
+        }
+        targets.push(InjectTarget {
+            method: "tmux",
+            command: format!("claude-inject tmux-inject -n {} -m \"...\"", worker.name),
+            target: worker.name,
+            project: worker.working_dir,
+        });
+    }
+
+    for entry in SessionMapper::map_processes(true)? {
+        if entry.kind() == ProcessKind::Desktop {
+            continue;
+        }
+        let project = entry.cwd.clone().unwrap_or_else(|| "unknown directory".to_string());
+
+        match entry.session_id {
+            Some(id) if listed_sessions.contains(&id) => {}
+            Some(id) => targets.push(InjectTarget {
+                method: "pty",
+                command: format!("claude-inject pty --id {} -m \"...\"", id),
+                target: id,
+                project,
+            }),
+            None => {
+                let Ok(device) = PtyInjector::get_controlling_terminal(entry.pid) else {
+                    continue;
+                };
+                targets.push(InjectTarget {
+                    method: "pty",
+                    command: format!("claude-inject pty --device {} -m \"...\"", device.display()),
+                    target: device.display().to_string(),
+                    project,
+                });
             }
+        }
+    }
 
-            println!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    Ok(targets)
+}
+
+/// Let the user fuzzy-search running Claude processes and pick one to inject into
+///
+/// Only offered when stdin and stdout are terminals; scripts must name the target.
+fn pick_running_target() -> Result<PtyTarget> {
+    use std::io::IsTerminal;
+
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        anyhow::bail!("--id, --project-path or --device is required when not running interactively");
+    }
+
+    let detector = SessionDetector::new()?;
+    let mut labels = Vec::new();
+    let mut targets = Vec::new();
+
+    for entry in SessionMapper::map_processes(true)? {
+        if entry.kind() == ProcessKind::Desktop {
+            continue;
+        }
+        let project = entry.cwd.clone().unwrap_or_else(|| "unknown directory".to_string());
+
+        match entry.session_id {
+            Some(id) => {
+                let preview = detector
+                    .session_summary(&id)
+                    .ok()
+                    .flatten()
+                    .and_then(|summary| summary.first_message_preview)
+                    .unwrap_or_default();
+                labels.push(format!("{}  {}  {}", id, project, preview));
+                targets.push(PtyTarget::Session(id));
+            }
+            None => {
+                let Ok(device) = PtyInjector::get_controlling_terminal(entry.pid) else {
+                    continue;
+                };
+                labels.push(format!("PID {}  {}  (session unknown, {})", entry.pid, project, device.display()));
+                targets.push(PtyTarget::Device(device.to_string_lossy().to_string()));
+            }
+        }
+    }
+
+    if targets.is_empty() {
+        anyhow::bail!("No running Claude sessions to pick from");
+    }
+
+    let choice = dialoguer::FuzzySelect::new()
+        .with_prompt("Inject into (type to filter)")
+        .items(&labels)
+        .default(0)
+        .max_length(PICKER_ROWS)
+        .interact_opt()?
+        .context("No session picked")?;
+
+    Ok(targets.swap_remove(choice))
+}
+
+/// Quote a CSV field if it contains a delimiter, quote, or newline
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Format a byte count for display (e.g. 1.5 MB)
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+fn registry_file() -> StateFile {
+    StateFile::in_home(".claude-injector-registry.json")
+}
+
+fn load_registry() -> Result<SessionRegistry> {
+    match registry_file().read()? {
+        Some(content) => Ok(serde_json::from_str(&content)?),
+        None => Ok(SessionRegistry {
+            sessions: std::collections::HashMap::new(),
+        }),
+    }
+}
+
+fn save_registry(registry: &SessionRegistry) -> Result<()> {
+    let content = serde_json::to_string_pretty(registry)?;
+    registry_file().write(&content)
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    let porcelain = cli.porcelain;
+
+    if let Err(e) = run(cli).await {
+        let code = InjectorError::exit_code_of(&e);
+        if porcelain {
+            eprintln!("error\t{}\t{}", code, format!("{:#}", e).replace(['\n', '\t'], " "));
+        } else {
+            eprintln!("Error: {:?}", e);
         }
+        std::process::exit(code);
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    init_cli_logging(cli.quiet, cli.verbose);
+    let porcelain = cli.porcelain;
+    if porcelain {
+        log::set_max_level(log::LevelFilter::Off);
+    }
+    if cli.safe {
+        enable_safe_mode();
+    }
+    if cli.global {
+        force_global_registry();
+    }
+    if cli.all_users {
+        set_process_user_filter(ProcessUserFilter::All);
+    } else if let Some(user) = cli.user {
+        set_process_user_filter(ProcessUserFilter::Named(user));
+    }
+
+    let config = InjectorConfig::load()?;
+    set_injection_wrapper(InjectionWrapper {
+        prefix: cli.prefix.or(config.injection_prefix.clone()),
+        suffix: cli.suffix.or(config.injection_suffix.clone()),
+    });
+    set_correlation_markers(cli.correlation_markers || config.correlation_markers);
+    set_message_history_len(config.message_history_len);
+
+    match cli.command {
+        Commands::Spawn { id, prompt, project, session, detach } => {
+            log::info!("🚀 Spawning Claude session with ID: {}", id);
+
+            // Detect available sessions
+            let detector = SessionDetector::new()?;
+            let all_sessions = detector.get_all_sessions()?;
+
+            if all_sessions.is_empty() {
+                anyhow::bail!("No Claude sessions found. Create one first with: cd /some/project && claude");
+            }
+
+            let session = select_base_session(&all_sessions, project.as_deref(), session.as_deref())?;
+            log::info!("📁 Using base session: {} ({})", session.session_id, session.project_path);
+
+            let initial_prompt = prompt.unwrap_or_else(|| {
+                "I am ready to receive injected messages.".to_string()
+            });
 
-        Commands::Stop { id } => {
-            println!("🛑 Stopping session: {}", id);
+            if detach {
+                let detached = DetachedSession::spawn(&id, &session, Some(initial_prompt))?;
+                log::info!("✅ Detached Claude process started (PID {})", detached.pid);
+                log::info!("📄 Output log: {}", detached.log_path.display());
+
+                let mut registry = load_registry()?;
+                registry.sessions.insert(
+                    id.clone(),
+                    SessionInfo {
+                        custom_id: id.clone(),
+                        claude_session_id: detached
+                            .conversation_id
+                            .clone()
+                            .unwrap_or_else(|| session.session_id.clone()),
+                        project_path: session.project_path,
+                        started_at: std::time::SystemTime::now()
+                            .duration_since(std::time::UNIX_EPOCH)
+                            .unwrap()
+                            .as_secs(),
+                        detached: Some(detached),
+                    },
+                );
+                save_registry(&registry)?;
+
+                log::info!("💡 Inject messages with:");
+                log::info!("   claude-inject inject --id {} --message \"Your message here\"", id);
+                log::info!("🛑 Stop with:");
+                log::info!("   claude-inject stop --id {}", id);
+                return Ok(());
+            }
+
+            // Start Claude process
+            let manager = ClaudeProcessManager::new();
+
+            let claude_session_id = manager
+                .start_session(session.clone(), Some(initial_prompt))
+                .await
+                .context("Failed to start Claude session")?;
+
+            log::info!("✅ Claude process started: {}", claude_session_id);
 
+            // Save to registry
             let mut registry = load_registry()?;
+            registry.sessions.insert(
+                id.clone(),
+                SessionInfo {
+                    custom_id: id.clone(),
+                    claude_session_id: claude_session_id.clone(),
+                    project_path: session.project_path,
+                    started_at: std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap()
+                        .as_secs(),
+                    detached: None,
+                },
+            );
+            save_registry(&registry)?;
+
+            log::info!("💡 Session registered! Now you can inject messages:");
+            log::info!("   claude-inject inject --id {} --message \"Your message here\"", id);
+            log::info!("⏳ Session will run in background. Stop with:");
+            log::info!("   claude-inject stop --id {}", id);
+
+            // Keep process alive, serving injections and delivering queued messages
+            log::info!("🔄 Session running... Press Ctrl+C to stop");
+            let shutdown = tokio::signal::ctrl_c();
+            let socket = daemon_socket_path(&id);
+            let daemon = manager.serve_daemon(&socket);
+            tokio::pin!(shutdown, daemon);
+            // A failed daemon only loses socket injections; the session and its queue keep going
+            let mut daemon_running = true;
+            loop {
+                tokio::select! {
+                    result = &mut shutdown => {
+                        result?;
+                        break;
+                    }
+                    result = &mut daemon, if daemon_running => {
+                        daemon_running = false;
+                        match result {
+                            Ok(()) => log::warn!("⚠️  Daemon stopped; continuing without socket injection"),
+                            Err(e) => log::warn!("⚠️  Daemon stopped: {:#}; continuing without socket injection", e),
+                        }
+                    }
+                    delivered = manager.deliver_pending(&claude_session_id, QUEUE_IDLE_TIMEOUT, QUEUE_RESPONSE_LIMIT) => {
+                        match delivered {
+                            Ok(0) => {}
+                            Ok(n) => log::info!("📬 Delivered {} queued message(s)", n),
+                            Err(e) => log::warn!("⚠️  {:#}", e),
+                        }
+                        if !manager.is_session_active(&claude_session_id).await {
+                            log::warn!("⚠️  Claude exited");
+                            break;
+                        }
+                        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                    }
+                }
+            }
+
+            // Cleanup
+            manager.stop_session(&claude_session_id).await?;
+            let mut registry = load_registry()?;
+            registry.sessions.remove(&id);
+            save_registry(&registry)?;
+
+            log::info!("🛑 Session stopped");
+        }
+
+        Commands::Inject { id, project_path, message, preset, file, queue, ttl, correlation_id } => {
+            let registry = load_registry()?;
+
+            let id = match (id, project_path) {
+                (Some(id), _) => id,
+                (None, Some(path)) => resolve_managed_by_project_path(&registry, &path)?,
+                (None, None) => unreachable!("clap requires --id or --project-path"),
+            };
+
+            log::info!("📤 Injecting message into MANAGED session: {}", id);
+
             let session_info = registry
                 .sessions
                 .get(&id)
-                .context(format!("Session '{}' not found", id))?
-                .clone();
+                .ok_or_else(|| InjectorError::SessionNotFound(id.clone()))?;
 
-            let manager = ClaudeProcessManager::new();
-            manager
-                .stop_session(&session_info.claude_session_id)
+            let mut payload = match (message, preset, file) {
+                (Some(message), _, _) => {
+                    log::info!("📝 Message: {}", message);
+                    InjectionPayload::user_prompt(message)
+                }
+                (None, Some(name), _) => {
+                    log::info!("📝 Preset: {}", name);
+                    InjectionPayload::load_preset(&name, &presets_dir())?
+                }
+                (None, None, Some(path)) => {
+                    log::info!("📝 Payload file: {}", path.display());
+                    InjectionPayload::from_file(&path)?
+                }
+                (None, None, None) => unreachable!("clap requires --message, --preset or --file"),
+            };
+            if let Some(correlation_id) = correlation_id {
+                payload = payload.with_correlation_id(correlation_id);
+            }
+
+            if queue {
+                if session_info.detached.is_some() {
+                    anyhow::bail!("Session '{}' is detached; queued delivery needs a foreground `spawn`", id);
+                }
+
+                let manager = ClaudeProcessManager::new();
+                manager.enqueue(&session_info.claude_session_id, payload, ttl)?;
+
+                let pending = manager.pending(&session_info.claude_session_id).len();
+                log::info!("📥 Message queued ({} pending)", pending);
+                if porcelain {
+                    println!("queued\t{}\t{}", id, pending);
+                }
+                return Ok(());
+            }
+
+            // Detached sessions are reached through their stdin FIFO
+            if let Some(ref detached) = session_info.detached {
+                detached
+                    .inject(&payload)
+                    .context(format!("Failed to inject into detached session '{}'", id))?;
+
+                log::info!("✅ Message injected successfully!");
+                if porcelain {
+                    println!("injected\t{}\t{}", id, payload.to_injection_string().len() + 1);
+                }
+                return Ok(());
+            }
+
+            // Foreground sessions are owned by their `spawn`, which serves them on a socket
+            let socket = daemon_socket_path(&id);
+            let Some(mut client) = DaemonClient::connect(&socket).await? else {
+                return Err(anyhow::Error::new(InjectorError::SessionNotFound(id.clone())).context(format!(
+                    "No daemon is listening on {}; the `spawn` for this session is no longer running",
+                    socket.display()
+                )));
+            };
+
+            let bytes = client
+                .inject(&session_info.claude_session_id, &payload)
                 .await
-                .context("Failed to stop session")?;
+                .context("Failed to inject message")?;
+
+            log::info!("✅ Message injected successfully! ({} bytes)", bytes);
+            if porcelain {
+                println!("injected\t{}\t{}", id, bytes);
+            }
+        }
+
+        Commands::QueueList { id } => {
+            let registry = load_registry()?;
+            let session_id = claude_session_id(&registry, &id);
+            let pending = ClaudeProcessManager::new().pending(&session_id);
+
+            if pending.is_empty() {
+                println!("No queued messages for {}", id);
+                return Ok(());
+            }
+
+            println!("Queued messages for {} ({}):", id, pending.len());
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_secs();
+            for entry in &pending {
+                let expiry = match entry.expires_at {
+                    Some(expires_at) if expires_at <= now => " (expired)".to_string(),
+                    Some(expires_at) => format!(" (expires in {}s)", expires_at - now),
+                    None => String::new(),
+                };
+                println!("  {:>3}. [{:?}] {}{}", entry.position, entry.payload_type, entry.preview, expiry);
+            }
+        }
+
+        Commands::QueueClear { id } => {
+            let registry = load_registry()?;
+            let session_id = claude_session_id(&registry, &id);
+            let cleared = ClaudeProcessManager::new().clear_pending(&session_id)?;
+
+            log::info!("🗑️  Cancelled {} queued message(s) for {}", cleared, id);
+        }
+
+        Commands::Pty { id, project_path, device, message, sanitize, allow_empty } => {
+            if let Some(device) = device {
+                log::info!("📤 Injecting into terminal device: {}", device);
+                log::info!("📝 Message: {}", message);
+
+                PtyInjector::inject_to_device(&device, &wrap_injection(&message), PtyWriteMode::Tiocsti, sanitize, allow_empty)?;
+
+                log::info!("✅ Message injected to terminal!");
+                return Ok(());
+            }
+
+            let id = match (id, project_path) {
+                (Some(id), _) => id,
+                (None, Some(path)) => SessionMapper::find_session_by_project_path(&path)?.session_id,
+                (None, None) => match pick_running_target()? {
+                    PtyTarget::Session(id) => id,
+                    PtyTarget::Device(device) => {
+                        log::info!("📤 Injecting into terminal device: {}", device);
+                        PtyInjector::inject_to_device(&device, &wrap_injection(&message), PtyWriteMode::Tiocsti, sanitize, allow_empty)?;
+                        log::info!("✅ Message injected to terminal!");
+                        return Ok(());
+                    }
+                },
+            };
+
+            log::info!("📤 Injecting into EXISTING Claude session via PTY: {}", id);
+            log::info!("📝 Message: {}", message);
+
+            PtyInjector::inject_to_session(&id, &wrap_injection(&message), sanitize, allow_empty)?;
+
+            log::info!("✅ Message injected to terminal!");
+        }
+
+        Commands::PtyRaw { device, message, direct, sanitize, allow_empty } => {
+            log::info!("📤 Injecting into terminal device: {}", device);
+            log::info!("📝 Message: {}", message);
+
+            let mode = if direct {
+                PtyWriteMode::Direct
+            } else {
+                PtyWriteMode::Tiocsti
+            };
+            PtyInjector::inject_to_device(&device, &wrap_injection(&message), mode, sanitize, allow_empty)?;
+
+            log::info!("✅ Message injected to terminal!");
+        }
+
+        Commands::List => {
+            let registry = load_registry()?;
+
+            if registry.sessions.is_empty() {
+                println!("No active sessions");
+                return Ok(());
+            }
+
+            println!("Active sessions:");
+            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+            for (id, info) in &registry.sessions {
+                let age = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs()
+                    - info.started_at;
+
+                println!("\n  ID: {}", id);
+                println!("  Claude Session: {}", info.claude_session_id);
+                println!("  Project: {}", info.project_path);
+                println!("  Running for: {}s", age);
+            }
+
+            println!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        }
+
+        Commands::Stop { id, project_path_prefix, older_than } => {
+            let mut registry = load_registry()?;
+
+            let ids = match id {
+                Some(id) => {
+                    if !registry.sessions.contains_key(&id) {
+                        return Err(InjectorError::SessionNotFound(id).into());
+                    }
+                    vec![id]
+                }
+                None => {
+                    let filter = SessionFilter {
+                        project_path_prefix: project_path_prefix.map(PathBuf::from),
+                        older_than,
+                    };
+                    let mut ids: Vec<String> = registry
+                        .sessions
+                        .values()
+                        .filter(|info| filter.matches(&info.as_session()))
+                        .map(|info| info.custom_id.clone())
+                        .collect();
+                    ids.sort();
+                    ids
+                }
+            };
+
+            if ids.is_empty() {
+                log::info!("No managed sessions match");
+                return Ok(());
+            }
+
+            let foreground: Vec<String> = ids
+                .iter()
+                .map(|id| &registry.sessions[id])
+                .filter(|info| info.detached.is_none())
+                .map(|info| info.claude_session_id.clone())
+                .collect();
+            ClaudeProcessManager::new()
+                .stop_matching(|session| foreground.contains(&session.session_id))
+                .await?;
+
+            for id in &ids {
+                log::info!("🛑 Stopping session: {}", id);
+                if let Some(ref detached) = registry.sessions[id].detached {
+                    detached.stop().context(format!("Failed to stop detached session '{}'", id))?;
+                }
+
+                registry.sessions.remove(id);
+                save_registry(&registry)?;
+            }
+
+            log::info!("✅ Stopped {} session(s)", ids.len());
+        }
+
+        Commands::Promote { id, agent } => {
+            let mut registry = load_registry()?;
+            let info = registry
+                .sessions
+                .get(&id)
+                .cloned()
+                .ok_or_else(|| InjectorError::SessionNotFound(id.clone()))?;
+
+            let Some(ref detached) = info.detached else {
+                anyhow::bail!(
+                    "Session '{}' is held by a foreground `spawn`; stop it there (Ctrl+C), then continue it with: \
+                     claude-inject spawn-worker --name {} --resume {}",
+                    id,
+                    id,
+                    info.claude_session_id
+                );
+            };
+            if WorkerRegistry::load()?.exists(&id) {
+                anyhow::bail!("A worker named '{}' already exists", id);
+            }
+
+            // Registries written before --session-id was passed only know the process
+            let conversation = match detached.conversation_id {
+                Some(ref conversation) => conversation.clone(),
+                None => SessionMapper::map_processes(false)?
+                    .into_iter()
+                    .find(|entry| entry.pid == detached.pid)
+                    .and_then(|entry| entry.session_id)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Cannot tell which conversation session '{}' (PID {}) is writing",
+                            id,
+                            detached.pid
+                        )
+                    })?,
+            };
+            let agent = resolve_agent(agent).unwrap_or_else(|_| UNASSIGNED_AGENT.to_string());
+
+            log::info!("⏫ Promoting session {} (conversation {}) to a tmux worker", id, conversation);
+            detached.stop().context(format!("Failed to stop detached session '{}'", id))?;
+
+            let worker = TmuxSpawner::spawn_worker(&id, &agent, &info.project_path, None, None, Some(conversation.clone()), None)
+                .context(format!(
+                    "Session '{}' was stopped but could not be resumed; retry with: claude-inject spawn-worker --name {} --resume {}",
+                    id, id, conversation
+                ))?;
 
             registry.sessions.remove(&id);
             save_registry(&registry)?;
 
-            println!("✅ Session stopped");
+            log::info!("✅ Session {} is now worker {}", id, worker.name);
+            log::info!("📺 View session: tmux attach -t {}", worker.tmux_session);
         }
 
-        Commands::Find { id } => {
-            println!("🔍 Finding existing Claude sessions...\n");
+        Commands::Stats => {
+            let stats = SessionDetector::new()?.project_stats();
+
+            if stats.is_empty() {
+                println!("No projects found");
+                return Ok(());
+            }
 
-            let sessions = SessionMapper::map_sessions_to_processes()?;
+            println!("\n{:>10} {:>9}  PROJECT", "SIZE", "SESSIONS");
+            println!("{}", "=".repeat(80));
 
-            if sessions.is_empty() {
+            for stat in &stats {
+                println!("{:>10} {:>9}  {}", format_bytes(stat.total_bytes), stat.session_count, stat.decoded_path);
+            }
+
+            let total: u64 = stats.iter().map(|stat| stat.total_bytes).sum();
+            println!("\nTotal: {} across {} project(s)\n", format_bytes(total), stats.len());
+        }
+
+        Commands::Targets { format } => {
+            let targets = injectable_targets()?;
+
+            if format == "json" {
+                println!("{}", serde_json::to_string_pretty(&targets)?);
+                return Ok(());
+            }
+
+            if targets.is_empty() {
+                println!("Nothing to inject into: no managed sessions, workers or running Claude sessions");
+                return Ok(());
+            }
+
+            println!("\n{:<8} {:<38} {:<40}", "METHOD", "TARGET", "PROJECT");
+            println!("{}", "=".repeat(88));
+            for target in &targets {
+                println!("{:<8} {:<38} {:<40}", target.method, target.target, target.project);
+                println!("         ↳ {}", target.command);
+            }
+            println!("\nTotal: {} target(s)\n", targets.len());
+        }
+
+        Commands::Find { id } => {
+            log::info!("🔍 Finding existing Claude sessions...");
+
+            let mut sessions = Vec::new();
+            let mut unmapped = Vec::new();
+            for entry in SessionMapper::map_processes(true)? {
+                match entry.clone().into_running() {
+                    Some(session) => sessions.push(session),
+                    None => unmapped.push(entry),
+                }
+            }
+
+            if sessions.is_empty() && unmapped.is_empty() {
                 println!("No running Claude sessions found");
                 return Ok(());
             }
@@ -342,30 +1572,44 @@ async fn main() -> Result<()> {
             if let Some(target_id) = id {
                 // Find specific session
                 if let Some(session) = sessions.iter().find(|s| s.session_id.starts_with(&target_id)) {
-                    println!("✅ Found session!");
+                    log::info!("✅ Found session!");
                     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
                     println!("  Session ID: {}", session.session_id);
                     println!("  Process PID: {}", session.pid);
+                    println!("  Kind: {}", session.kind());
                     println!("  Project: {}", session.project_path);
 
+                    if session.kind() == ProcessKind::Desktop {
+                        log::warn!("⚠️  Claude Desktop has no terminal: injection is not possible");
+                        return Ok(());
+                    }
+
+                    let device = PtyInjector::get_controlling_terminal(session.pid).ok();
+                    if let Some(ref device) = device {
+                        println!("  TTY: {}", device.display());
+                    }
+
                     if let Some(ref term) = session.terminal_info {
                         println!("\n  Terminal Info:");
                         println!("    Type: {}", term.terminal_name);
                         println!("    PID: {}", term.terminal_pid);
                         println!("    Command: {}", term.terminal_cmd);
 
-                        println!("\n💡 Injection Options:");
-                        println!("  ⚠️  Direct stdin injection: NOT POSSIBLE (process not spawned by us)");
-                        println!("  ✅ Terminal automation: Use tools like:");
-                        println!("     - xdotool (X11): xdotool type --window <WID> \"message\"");
-                        println!("     - tmux (if in tmux): tmux send-keys -t <session> \"message\" Enter");
-                        println!("     - expect scripts: Automate terminal input");
+                        log::info!("💡 Injection Options:");
+                        if let Some(ref device) = device {
+                            log::info!("  ✅ Terminal device: claude-inject pty --device {} --message \"message\"", device.display());
+                        }
+                        log::info!("  ⚠️  Direct stdin injection: NOT POSSIBLE (process not spawned by us)");
+                        log::info!("  ✅ Terminal automation: Use tools like:");
+                        log::info!("     - xdotool (X11): xdotool type --window <WID> \"message\"");
+                        log::info!("     - tmux (if in tmux): tmux send-keys -t <session> \"message\" Enter");
+                        log::info!("     - expect scripts: Automate terminal input");
                     } else {
                         println!("\n  Terminal: Unknown");
-                        println!("\n⚠️  Cannot inject: Terminal information not available");
+                        log::warn!("⚠️  Cannot inject: Terminal information not available");
                     }
                 } else {
-                    println!("❌ Session '{}' not found", target_id);
+                    log::error!("❌ Session '{}' not found", target_id);
                 }
             } else {
                 // List all sessions
@@ -375,7 +1619,11 @@ async fn main() -> Result<()> {
                 for session in &sessions {
                     println!("  Session ID: {}", session.session_id);
                     println!("  Process PID: {}", session.pid);
+                    println!("  Kind: {}", session.kind());
                     println!("  Project: {}", session.project_path);
+                    if let Ok(device) = PtyInjector::get_controlling_terminal(session.pid) {
+                        println!("  TTY: {}", device.display());
+                    }
 
                     if let Some(ref term) = session.terminal_info {
                         println!("  Terminal: {} (PID: {})", term.terminal_name, term.terminal_pid);
@@ -385,17 +1633,29 @@ async fn main() -> Result<()> {
                     println!();
                 }
 
+                for entry in &unmapped {
+                    println!(
+                        "  Running Claude (PID {}, {}) in {}, session unknown",
+                        entry.pid,
+                        entry.kind(),
+                        entry.cwd.as_deref().unwrap_or("unknown directory")
+                    );
+                }
+                if !unmapped.is_empty() {
+                    println!();
+                }
+
                 println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-                println!("\n💡 To find a specific session:");
-                println!("   claude-inject find --id <session-id>");
+                log::info!("💡 To find a specific session:");
+                log::info!("   claude-inject find --id <session-id>");
             }
         }
 
-        Commands::Tmux { name, dir } => {
-            println!("🚀 Spawning Claude in tmux session: {}", name);
+        Commands::Tmux { name, dir, project_root, self_test } => {
+            log::info!("🚀 Spawning Claude in tmux session: {}", name);
 
             if !TmuxSpawner::is_available() {
-                anyhow::bail!("tmux is not installed. Install with: sudo apt install tmux");
+                return Err(InjectorError::TmuxUnavailable.into());
             }
 
             if TmuxSpawner::session_exists(&name) {
@@ -409,39 +1669,104 @@ async fn main() -> Result<()> {
                     .to_string()
             });
 
-            TmuxSpawner::spawn_session(&name, &working_dir)?;
+            TmuxSpawner::spawn_session(&name, &working_dir, &[], project_root.as_deref())?;
 
-            println!("✅ Claude started in tmux session!");
-            println!("\n📺 To view the session, run:");
-            println!("   {}", TmuxSpawner::attach_command(&name));
-            println!("\n💡 To inject messages:");
-            println!("   claude-inject tmux-inject --name {} --message \"Your message\"", name);
-            println!("\n🛑 To stop:");
-            println!("   tmux kill-session -t {}", name);
+            if self_test {
+                WorkerManager::run_self_test(&name)?;
+            }
+
+            log::info!("✅ Claude started in tmux session!");
+            log::info!("📺 To view the session, run:");
+            log::info!("   {}", TmuxSpawner::attach_command(&name));
+            log::info!("💡 To inject messages:");
+            log::info!("   claude-inject tmux-inject --name {} --message \"Your message\"", name);
+            log::info!("🛑 To stop:");
+            log::info!("   tmux kill-session -t {}", name);
         }
 
-        Commands::TmuxInject { name, message } => {
-            println!("📤 Injecting into tmux session: {}", name);
-            println!("📝 Message: {}", message);
+        Commands::TmuxInject { name, project_path, message, fail_on_copy_mode, wait_idle, min_interval, fail_on_rate_limit, allow_empty, ssh } => {
+            let name = match (name, project_path) {
+                (Some(name), _) => name,
+                (None, Some(path)) => {
+                    let session = SessionMapper::find_session_by_project_path(&path)?;
+                    session.ensure_injectable()?;
+                    TmuxSpawner::find_session_for_pid(session.pid)?.context(format!(
+                        "Claude session {} (PID {}) is not running inside tmux",
+                        session.session_id, session.pid
+                    ))?
+                }
+                (None, None) => unreachable!("clap requires --name or --project-path"),
+            };
+
+            log::info!("📤 Injecting into tmux session: {}", name);
+            log::info!("📝 Message: {}", message);
+
+            if let Some(destination) = ssh {
+                if !allow_empty {
+                    check_not_empty(&message)?;
+                }
+                let text = wrap_injection(&message);
+                SshHost::new(&destination).inject_message(&name, &text)?;
+
+                log::info!("✅ Message injected on {}!", destination);
+                if porcelain {
+                    println!("injected\t{}\t{}", name, text.len());
+                }
+                log::info!("💡 View the session with:");
+                log::info!("   ssh -t {} {}", destination, TmuxSpawner::attach_command(&name));
+                return Ok(());
+            }
 
             if !TmuxSpawner::session_exists(&name) {
-                anyhow::bail!("Tmux session '{}' not found", name);
+                return Err(InjectorError::TmuxSessionNotFound(name).into());
             }
 
-            TmuxSpawner::inject_message(&name, &message)?;
+            let options = TmuxInjectOptions {
+                copy_mode: if fail_on_copy_mode {
+                    CopyModePolicy::Error
+                } else {
+                    CopyModePolicy::Exit
+                },
+                wait_idle,
+                rate_limit: RateLimit::configured(
+                    &config,
+                    min_interval,
+                    if fail_on_rate_limit {
+                        RateLimitPolicy::Reject
+                    } else {
+                        RateLimitPolicy::Delay
+                    },
+                ),
+                allow_empty,
+            };
+            let text = wrap_injection(&message);
+            TmuxSpawner::inject_message_with_options(&name, &text, &options).map_err(|e| {
+                match InjectorError::find_in(&e) {
+                    Some(InjectorError::SessionGone(_)) => e.context(format!(
+                        "Worker '{}' is gone; reconcile the registry with `claude-inject list-workers --reconcile`",
+                        name
+                    )),
+                    _ => e,
+                }
+            })?;
 
-            // Update message counter
+            // Update message counter and history
             let mut registry = WorkerRegistry::load()?;
-            registry.increment_messages(&name).ok();
+            registry.record_message(&name, &text, PayloadType::UserPrompt).ok();
 
-            println!("✅ Message injected!");
-            println!("\n💡 View the session with:");
-            println!("   {}", TmuxSpawner::attach_command(&name));
+            log::info!("✅ Message injected!");
+            if porcelain {
+                println!("injected\t{}\t{}", name, text.len());
+            }
+            log::info!("💡 View the session with:");
+            log::info!("   {}", TmuxSpawner::attach_command(&name));
         }
 
-        Commands::SpawnWorker { name, agent, dir, task_id, prompt } => {
-            println!("🚀 Spawning worker: {}", name);
-            println!("🤖 Agent: {}", agent);
+        Commands::SpawnWorker { name, agent, dir, task_id, prompt, self_test, ttl, resume, project_root } => {
+            let agent = resolve_agent(agent)?;
+
+            log::info!("🚀 Spawning worker: {}", name);
+            log::info!("🤖 Agent: {}", agent);
 
             let working_dir = dir.unwrap_or_else(|| {
                 std::env::current_dir()
@@ -450,75 +1775,100 @@ async fn main() -> Result<()> {
                     .to_string()
             });
 
-            println!("📁 Directory: {}", working_dir);
+            log::info!("📁 Directory: {}", working_dir);
             if let Some(ref tid) = task_id {
-                println!("📋 Task ID: {}", tid);
+                log::info!("📋 Task ID: {}", tid);
             }
 
             // Spawn and register worker
-            let worker = TmuxSpawner::spawn_worker(&name, &agent, &working_dir, task_id)?;
-
-            println!("✅ Worker spawned and registered!");
-            println!("\n📺 View session: tmux attach -t {}", worker.name);
-            println!("📤 Inject message: claude-inject tmux-inject --name {} --message \"...\"", worker.name);
-
-            // Wait for session to initialize
-            tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+            let ttl_secs = ttl.map(|ttl| ttl.as_secs().max(1));
+            if let Some(secs) = ttl_secs {
+                log::info!("⏱️  TTL: {}s", secs);
+            }
 
-            // Always load the specified agent first
-            println!("\n🔧 Loading agent: {}...", agent);
-            let load_agent_cmd = format!(
-                "mcp__agenthub_http__call_agent(\"{}\")",
-                agent
-            );
-            TmuxSpawner::inject_message(&name, &load_agent_cmd)?;
+            if let Some(ref session_id) = resume {
+                log::info!("🔄 Resuming session: {}", session_id);
+            }
 
-            // Wait for agent to load
-            tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+            let options = WorkerSpawnOptions {
+                task_id,
+                ttl_secs,
+                resume,
+                project_root,
+                self_test,
+                ..Default::default()
+            };
+            let worker = WorkerManager::spawn_with_prompt(&name, &agent, &working_dir, prompt.as_deref(), &options, &config).await?;
 
-            // Send initial prompt if provided
-            if let Some(initial_prompt) = prompt {
-                println!("📝 Sending initial prompt...");
-                TmuxSpawner::inject_message(&name, &initial_prompt)?;
+            log::info!("✅ Worker spawned and registered!");
+            log::info!("📺 View session: tmux attach -t {}", worker.name);
+            log::info!("📤 Inject message: claude-inject tmux-inject --name {} --message \"...\"", worker.name);
+            if worker.status == WorkerStatus::Working {
+                log::info!("✅ Initial prompt sent!");
+            }
 
-                let mut registry = WorkerRegistry::load()?;
-                registry.update_status(&name, WorkerStatus::Working)?;
-                println!("✅ Initial prompt sent!");
-            } else {
-                let mut registry = WorkerRegistry::load()?;
-                registry.update_status(&name, WorkerStatus::Ready)?;
+            if porcelain {
+                println!("spawned\t{}\t{}", worker.name, worker.tmux_session);
             }
         }
 
-        Commands::ListWorkers { format, agent, status } => {
-            let registry = WorkerRegistry::load()?;
-
-            let mut workers: Vec<&WorkerInfo> = if let Some(ref agent_filter) = agent {
-                registry.list_by_agent(agent_filter)
-            } else {
-                registry.list_all()
+        Commands::ListWorkers { format, agent, status, ssh, reconcile } => {
+            let mut workers: Vec<WorkerInfo> = match ssh {
+                Some(destination) => {
+                    log::info!("🌐 Reading worker registry on {}", destination);
+                    SshHost::new(destination).load_registry()?.list_all().into_iter().cloned().collect()
+                }
+                None => {
+                    let config = InjectorConfig::load()?;
+                    let mut registry = WorkerRegistry::load()?;
+                    if let Some(path) = registry.project_path() {
+                        log::info!("📒 Project registry: {}", path.display());
+                    }
+                    registry.sweep_stuck_starting(config.starting_timeout_secs)?;
+                    if reconcile {
+                        for name in registry.reconcile()? {
+                            log::info!("🧹 Worker {} has no tmux session, marked stopped", name);
+                        }
+                    }
+                    registry.live_view()
+                }
             };
 
+            if let Some(ref agent_filter) = agent {
+                workers.retain(|w| w.agent_type == *agent_filter);
+            }
+
             if let Some(ref status_filter) = status {
-                let status_enum = match status_filter.as_str() {
-                    "starting" => WorkerStatus::Starting,
-                    "ready" => WorkerStatus::Ready,
-                    "working" => WorkerStatus::Working,
-                    "idle" => WorkerStatus::Idle,
-                    "error" => WorkerStatus::Error,
-                    "stopped" => WorkerStatus::Stopped,
-                    _ => anyhow::bail!("Invalid status: {}", status_filter),
-                };
+                let status_enum = WorkerStatus::parse(status_filter)?;
                 workers.retain(|w| w.status == status_enum);
             }
 
-            if workers.is_empty() {
+            // CSV consumers always get a header row, even with no workers
+            if workers.is_empty() && format != "csv" {
                 println!("No workers found");
                 return Ok(());
             }
 
             if format == "json" {
                 println!("{}", serde_json::to_string_pretty(&workers)?);
+            } else if format == "csv" {
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs();
+
+                println!("name,agent,task_id,status,messages_sent,uptime");
+                for worker in &workers {
+                    println!(
+                        "{},{},{},{},{},{}",
+                        csv_escape(&worker.name),
+                        csv_escape(&worker.agent_type),
+                        csv_escape(worker.task_id.as_deref().unwrap_or("")),
+                        worker.status,
+                        worker.messages_sent,
+                        now.saturating_sub(worker.spawned_at)
+                    );
+                }
             } else {
                 // Table format
                 println!("\n{:<20} {:<20} {:<15} {:<10} {:<8}", "NAME", "AGENT", "TASK_ID", "STATUS", "MESSAGES");
@@ -545,8 +1895,46 @@ async fn main() -> Result<()> {
             }
         }
 
-        Commands::WorkerStatus { name } => {
+        Commands::WorkerWait { name, status, timeout } => {
+            log::info!("⏳ Waiting up to {:?} for worker {} to be {}", timeout, name, status);
+            WorkerManager::wait_for_status(&name, &status, timeout)?;
+            log::info!("✅ Worker {} is {}", name, status);
+        }
+
+        Commands::WorkerSnapshot { name, out } => {
+            let registry = WorkerRegistry::load()?;
+            let session = registry
+                .get(&name)
+                .map(|w| w.tmux_session.clone())
+                .unwrap_or_else(|| name.clone());
+
+            let captured = TmuxSpawner::capture_pane_styled(&TmuxSpawner::resolve_target(&session)?)?;
+            let written = PaneSnapshot::parse(&captured).save(&out)?;
+            log::info!("📸 Saved snapshot of worker {} to {}", name, written.display());
+        }
+
+        Commands::WorkerOutput { name, lines } => {
             let registry = WorkerRegistry::load()?;
+            let session = registry
+                .get(&name)
+                .map(|w| w.tmux_session.clone())
+                .unwrap_or_else(|| name.clone());
+
+            let output = TmuxSpawner::capture_pane(&session, lines).map_err(|e| match InjectorError::find_in(&e) {
+                Some(InjectorError::TmuxSessionNotFound(_)) => {
+                    e.context(format!("Worker '{}' has no running tmux session", name))
+                }
+                _ => e,
+            })?;
+            println!("{}", output);
+        }
+
+        Commands::WorkerStatus { name, ssh } => {
+            let host = ssh.map(SshHost::new);
+            let registry = match host {
+                Some(ref host) => host.load_registry()?,
+                None => WorkerRegistry::load()?,
+            };
 
             match registry.get(&name) {
                 Some(worker) => {
@@ -558,6 +1946,13 @@ async fn main() -> Result<()> {
                     println!("Directory:    {}", worker.working_dir);
                     println!("Messages:     {}", worker.messages_sent);
                     println!("Tmux Session: {}", worker.tmux_session);
+                    println!("Session ID:   {}", worker.session_id.as_deref().unwrap_or("-"));
+                    if let Some(ref resumed) = worker.resumed_from {
+                        println!("Resumed From: {}", resumed);
+                    }
+                    if let Some(ttl) = worker.ttl_secs {
+                        println!("TTL:          {}s", ttl);
+                    }
 
                     let uptime = std::time::SystemTime::now()
                         .duration_since(std::time::UNIX_EPOCH)
@@ -566,44 +1961,690 @@ async fn main() -> Result<()> {
                         - worker.spawned_at;
                     println!("Uptime:       {}s", uptime);
 
-                    let session_exists = TmuxSpawner::session_exists(&worker.tmux_session);
+                    let session_exists = match host {
+                        Some(ref host) => host.session_exists(&worker.tmux_session)?,
+                        None => TmuxSpawner::session_exists(&worker.tmux_session),
+                    };
                     println!("Running:      {}", if session_exists { "yes" } else { "no" });
 
-                    println!("\n💡 Attach: tmux attach -t {}", worker.tmux_session);
+                    if !worker.history.is_empty() {
+                        let now = std::time::SystemTime::now()
+                            .duration_since(std::time::UNIX_EPOCH)
+                            .unwrap()
+                            .as_secs();
+                        println!("Recent messages:");
+                        for record in worker.history.iter().rev().take(STATUS_HISTORY_MESSAGES).rev() {
+                            let line = record.preview.split_whitespace().collect::<Vec<_>>().join(" ");
+                            let preview: String = line.chars().take(STATUS_HISTORY_PREVIEW).collect();
+                            println!(
+                                "  {:>6}s ago  [{}] {}{}",
+                                now.saturating_sub(record.timestamp),
+                                record.kind,
+                                preview,
+                                if line.chars().count() > STATUS_HISTORY_PREVIEW || record.is_truncated() { "…" } else { "" }
+                            );
+                        }
+                    }
+
+                    if session_exists && host.is_none() {
+                        if let Ok(pane) = TmuxSpawner::capture_pane(&worker.tmux_session, None) {
+                            let lines: Vec<&str> = pane.lines().collect();
+                            println!("Last output:");
+                            for line in &lines[lines.len().saturating_sub(STATUS_OUTPUT_LINES)..] {
+                                println!("  {}", line);
+                            }
+                        }
+                    }
+
+                    match host {
+                        Some(ref host) => {
+                            log::info!("💡 Attach: ssh -t {} tmux attach -t {}", host.destination(), worker.tmux_session)
+                        }
+                        None => log::info!("💡 Attach: tmux attach -t {}", worker.tmux_session),
+                    }
+                }
+                None => {
+                    log::error!("❌ Worker '{}' not found in registry", name);
                 }
+            }
+        }
+
+        Commands::WorkerRefresh { name } => {
+            let mut registry = WorkerRegistry::load()?;
+            let worker = registry
+                .get(&name)
+                .context(format!("Worker '{}' not found in registry", name))?
+                .clone();
+
+            if !TmuxSpawner::session_exists(&worker.tmux_session) {
+                return Err(InjectorError::TmuxSessionNotFound(worker.tmux_session).into());
+            }
+
+            let session_id = TmuxSpawner::resolve_session_id(&worker.tmux_session)?;
+            registry.set_session_id(&name, session_id.clone())?;
+
+            let old = worker.session_id.as_deref().unwrap_or("-");
+            match session_id {
+                Some(ref new) if new == old => println!("{}: {} (unchanged)", name, new),
+                Some(ref new) => println!("{}: {} -> {}", name, old, new),
                 None => {
-                    println!("❌ Worker '{}' not found in registry", name);
+                    println!("{}: {} -> -", name, old);
+                    log::warn!("⚠️  No running Claude session found in {}'s pane", name);
                 }
             }
         }
 
         Commands::StopWorker { name, force } => {
-            println!("🛑 Stopping worker: {}", name);
+            log::info!("🛑 Stopping worker: {}", name);
 
             let mut registry = WorkerRegistry::load()?;
 
-            if !registry.exists(&name) {
-                println!("⚠️  Worker not found in registry");
-            }
+            let tmux_session = match registry.get(&name) {
+                Some(worker) => worker.tmux_session.clone(),
+                None => {
+                    log::warn!("⚠️  Worker not found in registry");
+                    name.clone()
+                }
+            };
 
-            if TmuxSpawner::session_exists(&name) {
+            if TmuxSpawner::session_exists(&tmux_session) {
                 if force {
-                    TmuxSpawner::kill_session(&name)?;
-                    println!("✅ Worker killed");
+                    TmuxSpawner::kill_session(&tmux_session)?;
+                    log::info!("✅ Worker killed");
                 } else {
-                    TmuxSpawner::send_interrupt(&name)?;
+                    TmuxSpawner::send_interrupt(&tmux_session)?;
                     tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-                    TmuxSpawner::kill_session(&name)?;
-                    println!("✅ Worker stopped");
+                    TmuxSpawner::kill_session(&tmux_session)?;
+                    log::info!("✅ Worker stopped");
                 }
             }
 
-            registry.update_status(&name, WorkerStatus::Stopped)?;
+            if registry.archive(&name, WorkerStatus::Stopped)?.is_some() {
+                log::info!("✅ Worker unregistered (kept in worker-history)");
+            }
+        }
+
+        Commands::WorkerFeed { name, queue, timeout } => {
+            log::info!("📥 Feeding {} from {}", name, queue.display());
+            let sent = WorkerManager::feed_from_file(&name, &queue, timeout, &TmuxInjectOptions::for_worker(&config))?;
+            log::info!("✅ Queue empty after {} prompt(s)", sent);
+        }
+
+        Commands::ForgetWorker { name } => {
+            let mut registry = WorkerRegistry::load()?;
+            let tmux_session = registry
+                .get(&name)
+                .map(|w| w.tmux_session.clone())
+                .context(format!("Worker '{}' not found in registry", name))?;
+
             registry.unregister(&name)?;
+            log::info!("✅ Worker {} forgotten", name);
+
+            if TmuxSpawner::session_exists(&tmux_session) {
+                log::info!("📺 Tmux session '{}' is still running: tmux attach -t {}", tmux_session, tmux_session);
+            } else {
+                log::warn!("⚠️  Tmux session '{}' is not running", tmux_session);
+            }
+        }
+
+        Commands::DiscoverWorkers { adopt } => {
+            let sessions = WorkerManager::discover_unregistered()?;
+            if sessions.is_empty() {
+                println!("No unregistered tmux sessions running Claude");
+                return Ok(());
+            }
+
+            for session in &sessions {
+                if !adopt {
+                    println!("{}", session);
+                    continue;
+                }
+
+                match WorkerManager::adopt(session) {
+                    Ok(worker) => println!("{}\tadopted as {} ({})", session, worker.name, worker.agent_type),
+                    Err(e) => log::warn!("⚠️  Could not adopt {}: {:#}", session, e),
+                }
+            }
+
+            if !adopt {
+                log::info!("💡 Register them with: claude-inject discover-workers --adopt");
+            }
+        }
+
+        Commands::RegistryCheck { repair } => {
+            let (mut registry, issues) = WorkerRegistry::load_checked()?;
+            let location = registry
+                .project_path()
+                .map_or_else(|| "global registry".to_string(), |path| path.display().to_string());
+
+            if issues.is_empty() {
+                log::info!("✅ {} worker(s) in {} look fine", registry.count(), location);
+            } else {
+                println!("\n🩺 Registry issues in {}", location);
+                println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+                for issue in &issues {
+                    println!("{} {}", if issue.dropped { "❌" } else { "🔧" }, issue);
+                }
+                println!();
+
+                if !repair {
+                    anyhow::bail!("Worker registry has {} issue(s); rerun with --repair to save the fixes", issues.len());
+                }
+                registry.discard_unreadable();
+                registry.save()?;
+                log::info!("✅ Saved repaired registry with {} worker(s)", registry.count());
+            }
+        }
+
+        Commands::WorkerHistory { format, limit } => {
+            let history = WorkerRegistry::load()?.load_history()?;
+            let mut workers = history.list();
+            if let Some(limit) = limit {
+                workers.truncate(limit);
+            }
+
+            if workers.is_empty() {
+                println!("No past workers found");
+                return Ok(());
+            }
+
+            if format == "json" {
+                println!("{}", serde_json::to_string_pretty(&workers)?);
+            } else {
+                println!("\n{:<20} {:<20} {:<15} {:<10} {:<8} {:<10}", "NAME", "AGENT", "TASK_ID", "STATUS", "MESSAGES", "RAN FOR");
+                println!("{}", "=".repeat(88));
+
+                for worker in &workers {
+                    let info = &worker.info;
+                    println!(
+                        "{:<20} {:<20} {:<15} {:<10} {:<8} {:<10}",
+                        info.name,
+                        info.agent_type,
+                        info.task_id.as_deref().unwrap_or("-"),
+                        info.status.to_string(),
+                        info.messages_sent,
+                        format!("{}s", worker.stopped_at.saturating_sub(info.spawned_at))
+                    );
+                }
+
+                println!("\nTotal: {} past worker(s)\n", workers.len());
+            }
+        }
+
+        Commands::WorkersTail { lines, interval } => {
+            log::info!("📜 Tailing worker output every {:?}... Press Ctrl+C to stop", interval);
+
+            let mut tail = PaneTail::new();
+            let mut ticker = tokio::time::interval(interval.max(tokio::time::Duration::from_millis(100)));
+
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {
+                        let registry = WorkerRegistry::load()?;
+                        let workers = registry.list_all();
+                        let width = workers.iter().map(|w| w.name.len()).max().unwrap_or(0);
+
+                        for worker in workers {
+                            if !TmuxSpawner::session_exists(&worker.tmux_session) {
+                                tail.forget(&worker.name);
+                                continue;
+                            }
+
+                            let pane = match TmuxSpawner::capture_pane(&worker.tmux_session, Some(lines)) {
+                                Ok(pane) => pane,
+                                Err(e) => {
+                                    log::debug!("Failed to capture {}: {}", worker.name, e);
+                                    continue;
+                                }
+                            };
+
+                            // Only the last `lines` lines; the capture also includes the visible screen
+                            let recent: Vec<&str> = pane.lines().collect();
+                            let recent = recent[recent.len().saturating_sub(lines)..].join("\n");
+
+                            for line in tail.update(&worker.name, &recent) {
+                                println!("[{:<width$}] {}", worker.name, line, width = width);
+                            }
+                        }
+                    }
+                    _ = tokio::signal::ctrl_c() => break,
+                }
+            }
+        }
+
+        Commands::WorkerExec { name, cmd, timeout } => {
+            let registry = WorkerRegistry::load()?;
+            let worker = registry
+                .get(&name)
+                .context(format!("Worker '{}' not found in registry", name))?;
+
+            log::info!("🐚 Running in {}: {}", worker.working_dir, cmd);
+
+            let result = TmuxSpawner::exec_in_new_window(&worker.tmux_session, &worker.working_dir, &cmd, timeout)?;
+            print!("{}", result.output);
+
+            if result.exit_code != 0 {
+                log::warn!("⚠️  Command exited with status {}", result.exit_code);
+                std::process::exit(result.exit_code);
+            }
+        }
+
+        Commands::Relay { from, to } => {
+            log::info!("🔁 Relaying latest output from {} to {}", from, to);
+
+            // Prefer the transcript; fall back to the pane of a tmux session
+            let output = match TranscriptReader::last_assistant_message(&from) {
+                Ok(message) => message,
+                Err(_) if TmuxSpawner::session_exists(&from) => {
+                    Some(TmuxSpawner::capture_pane(&from, None)?).filter(|pane| !pane.trim().is_empty())
+                }
+                Err(e) => return Err(e),
+            };
+
+            let output = output.context(format!(
+                "Session '{}' has not produced any assistant output yet",
+                from
+            ))?;
+
+            let mut registry = WorkerRegistry::load()?;
+            let tmux_session = registry
+                .get(&to)
+                .map(|w| w.tmux_session.clone())
+                .unwrap_or_else(|| to.clone());
+
+            if !TmuxSpawner::session_exists(&tmux_session) {
+                return Err(InjectorError::TmuxSessionNotFound(tmux_session).into());
+            }
+
+            let payload = InjectionPayload::context(format!("Latest output from session {}:\n\n{}", from, output))
+                .with_metadata("relayed_from", &from);
+            let text = payload.to_injection_string();
+            TmuxSpawner::inject_message_with_options(&tmux_session, &text, &TmuxInjectOptions::for_worker(&config))?;
+            registry.record_message(&to, &text, payload.payload_type.clone()).ok();
+
+            log::info!("✅ Relayed {} characters into {}", output.chars().count(), to);
+        }
+
+        Commands::Replay { from, to, roles, skip_first, range, timeout, dry_run } => {
+            if let Some(role) = roles.iter().find(|r| *r != "user" && *r != "assistant") {
+                anyhow::bail!("Invalid role: {} (expected user or assistant)", role);
+            }
+
+            let filter = ReplayFilter {
+                roles,
+                skip_first,
+                range: range.unwrap_or((None, None)),
+            };
+            let turns = filter.apply(TranscriptReader::load_transcript(&from)?);
+
+            if turns.is_empty() {
+                println!("No transcript turns match the filter");
+                return Ok(());
+            }
+
+            if dry_run {
+                for (i, turn) in turns.iter().enumerate() {
+                    let preview: String = turn.text.lines().next().unwrap_or_default().chars().take(70).collect();
+                    println!("{:>3}. [{}] {}", i + 1, turn.role, preview);
+                }
+                return Ok(());
+            }
+
+            let mut registry = WorkerRegistry::load()?;
+            let tmux_session = registry
+                .get(&to)
+                .map(|w| w.tmux_session.clone())
+                .unwrap_or_else(|| to.clone());
+
+            if !TmuxSpawner::session_exists(&tmux_session) {
+                return Err(InjectorError::TmuxSessionNotFound(tmux_session).into());
+            }
+
+            log::info!("⏪ Replaying {} turn(s) from {} into {}", turns.len(), from, to);
+
+            for (i, turn) in turns.iter().enumerate() {
+                let payload = if turn.role == "assistant" {
+                    InjectionPayload::context(turn.text.clone()).with_metadata("replayed_from", &from)
+                } else {
+                    InjectionPayload::user_prompt(turn.text.clone())
+                };
+
+                log::info!("📤 Turn {}/{} ({})", i + 1, turns.len(), turn.role);
+                let text = payload.to_injection_string();
+                TmuxSpawner::inject_message_with_options(&tmux_session, &text, &TmuxInjectOptions::for_worker(&config))?;
+                registry.record_message(&to, &text, payload.payload_type.clone()).ok();
+
+                // Let Claude pick the turn up before waiting for it to settle
+                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                WorkerManager::wait_until_ready(&tmux_session, timeout)
+                    .context(format!("Claude did not settle after turn {}", i + 1))?;
+            }
+
+            log::info!("✅ Replayed {} turn(s)", turns.len());
+        }
+
+        Commands::Export { id, format, roles, output } => {
+            let entries: Vec<TranscriptEntry> = TranscriptReader::load_transcript(&id)?
+                .into_iter()
+                .filter(|entry| roles.contains(&entry.role))
+                .collect();
+            let rendered = render(&entries, format)?;
+
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, rendered).context(format!("Failed to write {}", path.display()))?;
+                    log::info!("✅ Exported {} entries to {}", entries.len(), path.display());
+                }
+                None => print!("{}", rendered),
+            }
+        }
+
+        Commands::CiNotify { to, file, pipeline, log_url } => {
+            let results = CiResults::from_file(&file)?;
+
+            log::info!(
+                "🧪 Pipeline {}: {}",
+                pipeline,
+                if results.passed {
+                    "passed".to_string()
+                } else {
+                    format!("{} failing test(s)", results.failed_tests.len())
+                }
+            );
+
+            let mut registry = WorkerRegistry::load()?;
+            let tmux_session = registry
+                .get(&to)
+                .map(|w| w.tmux_session.clone())
+                .unwrap_or_else(|| to.clone());
+
+            if !TmuxSpawner::session_exists(&tmux_session) {
+                return Err(InjectorError::TmuxSessionNotFound(tmux_session).into());
+            }
+
+            let payload = presets::ci_result(&pipeline, results.passed, results.failed_tests, &log_url);
+            let text = payload.to_injection_string();
+            TmuxSpawner::inject_message_with_options(&tmux_session, &text, &TmuxInjectOptions::for_worker(&config))?;
+            registry.record_message(&to, &text, payload.payload_type.clone()).ok();
+
+            log::info!("✅ Notified {}", to);
+        }
+
+        Commands::BlockerCleared { to, blocker_ref, resolution } => {
+            let mut registry = WorkerRegistry::load()?;
+            let tmux_session = registry
+                .get(&to)
+                .map(|w| w.tmux_session.clone())
+                .unwrap_or_else(|| to.clone());
+
+            if !TmuxSpawner::session_exists(&tmux_session) {
+                return Err(InjectorError::TmuxSessionNotFound(tmux_session).into());
+            }
+
+            let payload = presets::blocker_cleared(&blocker_ref, &resolution);
+            let text = payload.to_injection_string();
+            TmuxSpawner::inject_message_with_options(&tmux_session, &text, &TmuxInjectOptions::for_worker(&config))?;
+            registry.record_message(&to, &text, payload.payload_type.clone()).ok();
+
+            log::info!("✅ Told {} that blocker '{}' is cleared", to, blocker_ref);
+        }
+
+        Commands::BroadcastWorkers { message, agent, status, confirm } => {
+            check_not_empty(&message)?;
+
+            let mut registry = WorkerRegistry::load()?;
+            let mut matching: Vec<WorkerInfo> = registry
+                .list_all()
+                .into_iter()
+                .filter(|w| w.status != WorkerStatus::Stopped)
+                .filter(|w| agent.as_ref().is_none_or(|agent| w.agent_type == *agent))
+                .filter(|w| status.as_ref().is_none_or(|status| w.status == *status))
+                .cloned()
+                .collect();
+            matching.sort_by(|a, b| a.name.cmp(&b.name));
+
+            let (targets, gone): (Vec<WorkerInfo>, Vec<WorkerInfo>) =
+                matching.into_iter().partition(|w| TmuxSpawner::session_exists(&w.tmux_session));
+            for worker in &gone {
+                log::warn!("⏭️  Skipping {}: tmux session '{}' no longer exists", worker.name, worker.tmux_session);
+            }
+
+            if targets.is_empty() {
+                println!("No running workers match");
+                return Ok(());
+            }
 
-            println!("✅ Worker unregistered");
+            if !confirm {
+                let names: Vec<String> = targets.iter().map(|w| w.name.clone()).collect();
+                check_broadcast_targets(&names, config.max_broadcast_targets)?;
+            }
+
+            log::info!("📣 Broadcasting to {} worker(s)...", targets.len());
+            let text = wrap_injection(&message);
+            let options = TmuxInjectOptions::for_worker(&config);
+            let mut failed = 0;
+            for worker in &targets {
+                match TmuxSpawner::inject_message_with_options(&worker.tmux_session, &text, &options) {
+                    Ok(()) => {
+                        registry.record_message(&worker.name, &text, PayloadType::UserPrompt).ok();
+                        log::info!("  ✅ {}", worker.name);
+                    }
+                    Err(e) => {
+                        failed += 1;
+                        log::error!("  ❌ {}: {:#}", worker.name, e);
+                    }
+                }
+            }
+
+            println!(
+                "Broadcast: {} sent, {} failed, {} skipped (session gone)",
+                targets.len() - failed,
+                failed,
+                gone.len()
+            );
+            if failed > 0 {
+                anyhow::bail!("Broadcast failed for {} of {} worker(s)", failed, targets.len());
+            }
+        }
+
+        Commands::PollStatus { name, all, confirm, format_hint, timeout, format } => {
+            let mut registry = WorkerRegistry::load()?;
+
+            let targets: Vec<WorkerInfo> = if all {
+                registry
+                    .list_all()
+                    .into_iter()
+                    .filter(|w| w.status != WorkerStatus::Stopped && TmuxSpawner::session_exists(&w.tmux_session))
+                    .cloned()
+                    .collect()
+            } else {
+                let name = name.expect("clap requires --name or --all");
+                let worker = registry
+                    .get(&name)
+                    .context(format!("Worker '{}' not found in registry", name))?;
+                vec![worker.clone()]
+            };
+
+            if targets.is_empty() {
+                println!("No running workers to poll");
+                return Ok(());
+            }
+
+            if !confirm {
+                let names: Vec<String> = targets.iter().map(|w| w.name.clone()).collect();
+                check_broadcast_targets(&names, config.max_broadcast_targets)?;
+            }
+
+            log::info!("📋 Asking {} worker(s) for a status report...", targets.len());
+            let request = presets::status_request(&format_hint);
+            let message = request.to_injection_string();
+            let options = &TmuxInjectOptions::for_worker(&config);
+
+            // Each worker answers at its own pace, so poll them side by side
+            let reports: Vec<(String, Result<String>)> = std::thread::scope(|scope| {
+                let handles: Vec<_> = targets
+                    .iter()
+                    .map(|worker| {
+                        let message = &message;
+                        scope.spawn(move || {
+                            TmuxSpawner::inject_and_capture_delta(&worker.tmux_session, message, options, timeout)
+                        })
+                    })
+                    .collect();
+
+                targets
+                    .iter()
+                    .zip(handles)
+                    .map(|(worker, handle)| {
+                        let report = handle.join().expect("status poll thread panicked");
+                        (worker.name.clone(), report)
+                    })
+                    .collect()
+            });
+
+            for (name, report) in &reports {
+                if report.is_ok() {
+                    registry.record_message(name, &message, request.payload_type.clone()).ok();
+                }
+            }
+
+            if format == "json" {
+                let summary: serde_json::Map<String, serde_json::Value> = reports
+                    .iter()
+                    .map(|(name, report)| {
+                        let value = match report {
+                            Ok(response) => serde_json::json!({ "response": response }),
+                            Err(e) => serde_json::json!({ "error": format!("{:#}", e) }),
+                        };
+                        (name.clone(), value)
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&summary)?);
+            } else {
+                println!("\n{:<20} REPORT", "NAME");
+                println!("{}", "=".repeat(80));
+
+                for (name, report) in &reports {
+                    let summary = match report {
+                        Ok(response) if response.trim().is_empty() => "(no answer)".to_string(),
+                        Ok(response) => response
+                            .lines()
+                            .map(str::trim)
+                            .filter(|line| !line.is_empty())
+                            .collect::<Vec<_>>()
+                            .join(" | "),
+                        Err(e) => format!("error: {:#}", e),
+                    };
+                    let summary: String = if summary.chars().count() > STATUS_COLUMN_WIDTH {
+                        format!("{}...", summary.chars().take(STATUS_COLUMN_WIDTH - 3).collect::<String>())
+                    } else {
+                        summary
+                    };
+
+                    println!("{:<20} {}", name, summary);
+                }
+                println!();
+            }
+        }
+
+        Commands::McpCall { to, tool, args } => {
+            let args = match args {
+                Some(args) => serde_json::from_str(&args).context("--args is not valid JSON")?,
+                None => serde_json::Value::Null,
+            };
+            let payload = InjectionPayload::mcp_call(&tool, args)?;
+
+            let mut registry = WorkerRegistry::load()?;
+            let tmux_session = registry
+                .get(&to)
+                .map(|w| w.tmux_session.clone())
+                .unwrap_or_else(|| to.clone());
+
+            if !TmuxSpawner::session_exists(&tmux_session) {
+                return Err(InjectorError::TmuxSessionNotFound(tmux_session).into());
+            }
+
+            log::info!("🔧 Calling {} in {}", tool, to);
+            let text = payload.to_injection_string();
+            TmuxSpawner::inject_message_with_options(&tmux_session, &text, &TmuxInjectOptions::for_worker(&config))?;
+            registry.record_message(&to, &text, payload.payload_type.clone()).ok();
+
+            log::info!("✅ Tool call injected");
+        }
+
+        Commands::Daemon { interval, idle_lines, idle_stable_cycles, events } => {
+            let mut config = InjectorConfig::load()?;
+            if let Some(lines) = idle_lines {
+                config.idle_lines = lines;
+            }
+            if let Some(cycles) = idle_stable_cycles {
+                config.idle_stable_cycles = cycles;
+            }
+            let interval = interval.unwrap_or(std::time::Duration::from_secs(config.idle_poll_interval_secs));
+
+            log::info!("🔄 Worker daemon running every {:?}... Press Ctrl+C to stop", interval);
+            log::debug!(
+                "Idle detection: {} lines, {} stable passes",
+                config.idle_lines,
+                config.idle_stable_cycles
+            );
+
+            let mut supervisor = WorkerSupervisor::new(&config);
+
+            let event_socket = match events {
+                Some(path) => {
+                    let socket = EventSocket::bind(&path).await?;
+                    log::info!("📡 Streaming events on {}", socket.path().display());
+                    Some(socket)
+                }
+                None => None,
+            };
+
+            let mut ticker = tokio::time::interval(interval.max(tokio::time::Duration::from_millis(100)));
+            let mut injected = claude_injector::events::subscribe();
+
+            loop {
+                tokio::select! {
+                    event = injected.recv() => match event {
+                        Ok(event) => {
+                            println!("  {}", serde_json::to_string(&event)?);
+                            if let Some(ref socket) = event_socket {
+                                socket.publish(&event).await?;
+                            }
+                        }
+                        Err(e) => log::warn!("⚠️  Missed injection events: {}", e),
+                    },
+                    _ = ticker.tick() => {
+                        match supervisor.tick() {
+                            Ok(worker_events) => {
+                                for event in worker_events {
+                                    println!("  {}", serde_json::to_string(&event)?);
+                                    if let Some(ref socket) = event_socket {
+                                        socket.publish(&event).await?;
+                                    }
+                                }
+                            }
+                            Err(e) => log::warn!("⚠️  Housekeeping pass failed: {}", e),
+                        }
+                    }
+                    _ = tokio::signal::ctrl_c() => break,
+                }
+            }
+
+            log::info!("🛑 Worker daemon stopped");
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+}
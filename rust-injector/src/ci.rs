@@ -0,0 +1,126 @@
+use anyhow::{Context, Result};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, XmlVersion};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+/// Outcome of a CI test run, as read from a results file
+#[derive(Debug, Clone, PartialEq)]
+pub struct CiResults {
+    pub passed: bool,
+    pub failed_tests: Vec<String>,
+}
+
+/// JSON results: `{"passed": false, "failed_tests": ["a", "b"]}`
+///
+/// `passed` may be omitted, in which case the run passed if no test failed.
+#[derive(Deserialize)]
+struct JsonResults {
+    passed: Option<bool>,
+    #[serde(default)]
+    failed_tests: Vec<String>,
+}
+
+impl CiResults {
+    /// Read results from a JUnit XML or JSON file, chosen by its content
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).context(format!("Failed to read {}", path.display()))?;
+
+        if content.trim_start().starts_with('<') {
+            Self::from_junit_xml(&content).context(format!("Invalid JUnit XML in {}", path.display()))
+        } else {
+            Self::from_json(&content).context(format!("Invalid JSON results in {}", path.display()))
+        }
+    }
+
+    /// Parse JSON results
+    pub fn from_json(json: &str) -> Result<Self> {
+        let results: JsonResults = serde_json::from_str(json)?;
+
+        Ok(Self {
+            passed: results.passed.unwrap_or(results.failed_tests.is_empty()),
+            failed_tests: results.failed_tests,
+        })
+    }
+
+    /// Parse a JUnit XML report; a test case with a `<failure>` or `<error>` failed
+    pub fn from_junit_xml(xml: &str) -> Result<Self> {
+        let mut reader = Reader::from_str(xml);
+        let mut current: Option<String> = None;
+        let mut failed_tests = Vec::new();
+
+        loop {
+            match reader.read_event()? {
+                Event::Start(tag) if tag.name().as_ref() == "testcase" => current = Some(test_name(&tag)?),
+                Event::End(tag) if tag.name().as_ref() == "testcase" => current = None,
+                Event::Start(tag) | Event::Empty(tag)
+                    if matches!(tag.name().as_ref(), "failure" | "error") =>
+                {
+                    // Report each test case once, even with several failure elements
+                    if let Some(name) = current.take() {
+                        failed_tests.push(name);
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            passed: failed_tests.is_empty(),
+            failed_tests,
+        })
+    }
+}
+
+/// `classname.name` of a test case, or just its name without a class
+fn test_name(tag: &BytesStart) -> Result<String> {
+    let mut name = String::new();
+    let mut classname = String::new();
+
+    for attr in tag.attributes() {
+        let attr = attr?;
+        let value = attr.normalized_value(XmlVersion::Implicit1_0)?.into_owned();
+        match attr.key.as_ref() {
+            "name" => name = value,
+            "classname" => classname = value,
+            _ => {}
+        }
+    }
+
+    Ok(if classname.is_empty() {
+        name
+    } else {
+        format!("{}.{}", classname, name)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_junit_and_json_results() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites>
+  <testsuite name="auth" tests="3" failures="1" errors="1">
+    <testcase classname="auth" name="login_ok"/>
+    <testcase classname="auth" name="jwt_expiry">
+      <failure message="expected 3600">assertion failed</failure>
+    </testcase>
+    <testcase name="db_connect"><error message="refused"/></testcase>
+  </testsuite>
+</testsuites>"#;
+
+        let results = CiResults::from_junit_xml(xml).unwrap();
+        assert!(!results.passed);
+        assert_eq!(results.failed_tests, ["auth.jwt_expiry", "db_connect"]);
+
+        let results = CiResults::from_json(r#"{"failed_tests": []}"#).unwrap();
+        assert_eq!(results, CiResults { passed: true, failed_tests: vec![] });
+
+        let results = CiResults::from_json(r#"{"passed": false, "failed_tests": ["lint"]}"#).unwrap();
+        assert!(!results.passed);
+    }
+}
@@ -0,0 +1,114 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// User configuration for the injector tools
+///
+/// Read from `~/.config/claude-injector/config.json`; every field is optional
+/// and falls back to its default when missing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InjectorConfig {
+    /// Seconds a worker may stay `Starting` before it is considered stuck
+    pub starting_timeout_secs: u64,
+    /// Trailing pane lines compared between polls for idle detection
+    pub idle_lines: usize,
+    /// Seconds between worker polls in `daemon`
+    pub idle_poll_interval_secs: u64,
+    /// Unchanged polls in a row before a worker is marked idle
+    pub idle_stable_cycles: u32,
+    /// Text put before every injected message (overridden by `--prefix`)
+    pub injection_prefix: Option<String>,
+    /// Text put after every injected message (overridden by `--suffix`)
+    pub injection_suffix: Option<String>,
+    /// Most sessions a broadcast may reach without `--confirm`
+    pub max_broadcast_targets: usize,
+    /// Append correlation ids to injections as hidden markers (or `--correlation-markers`)
+    pub correlation_markers: bool,
+    /// Least seconds between messages to one worker (0 disables the limit)
+    pub min_inject_interval_secs: u64,
+    /// Initial prompt per agent type, sent by `spawn-worker` when `--prompt` is omitted
+    pub agent_prompts: HashMap<String, String>,
+    /// Messages kept in each worker's history in the registry
+    pub message_history_len: usize,
+    /// Times `spawn-worker` re-sends the agent-load command before giving up
+    pub agent_load_retries: u32,
+    /// Pane text showing the agent loaded, `{agent}` standing for its type (empty skips the check)
+    pub agent_ready_marker: String,
+}
+
+impl Default for InjectorConfig {
+    fn default() -> Self {
+        Self {
+            starting_timeout_secs: 120,
+            idle_lines: crate::supervisor::DEFAULT_IDLE_LINES,
+            idle_poll_interval_secs: crate::supervisor::DEFAULT_IDLE_POLL_INTERVAL_SECS,
+            idle_stable_cycles: crate::supervisor::DEFAULT_IDLE_STABLE_CYCLES,
+            injection_prefix: None,
+            injection_suffix: None,
+            max_broadcast_targets: crate::safety::DEFAULT_MAX_BROADCAST_TARGETS,
+            correlation_markers: false,
+            min_inject_interval_secs: 0,
+            agent_prompts: HashMap::new(),
+            message_history_len: crate::worker_registry::DEFAULT_MESSAGE_HISTORY_LEN,
+            agent_load_retries: crate::worker_manager::DEFAULT_AGENT_LOAD_RETRIES,
+            agent_ready_marker: crate::worker_manager::DEFAULT_AGENT_READY_MARKER.to_string(),
+        }
+    }
+}
+
+impl InjectorConfig {
+    /// Load configuration from disk, using defaults when the file is absent
+    pub fn load() -> Result<Self> {
+        let path = Self::get_config_path();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(&path)?;
+        let config: InjectorConfig = serde_json::from_str(&content)
+            .context(format!("Invalid config file: {}", path.display()))?;
+        Ok(config)
+    }
+
+    /// Get config file path
+    pub fn get_config_path() -> PathBuf {
+        Self::config_dir().join("config.json")
+    }
+
+    /// Directory holding injector configuration files
+    pub fn config_dir() -> PathBuf {
+        dirs::config_dir()
+            .or_else(|| dirs::home_dir().map(|home| home.join(".config")))
+            .expect("Cannot find config directory")
+            .join("claude-injector")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_config_uses_defaults() {
+        let config: InjectorConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config.starting_timeout_secs, 120);
+
+        let config: InjectorConfig = serde_json::from_str(r#"{"starting_timeout_secs": 30, "idle_lines": 5}"#).unwrap();
+        assert_eq!(config.starting_timeout_secs, 30);
+        assert_eq!(config.idle_lines, 5);
+        assert_eq!(config.idle_stable_cycles, 2);
+        assert!(config.agent_prompts.is_empty());
+
+        let config: InjectorConfig = serde_json::from_str(
+            r#"{"agent_prompts": {"test-orchestrator-agent": "List the test suites and their current status."}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.agent_prompts.get("test-orchestrator-agent").map(String::as_str),
+            Some("List the test suites and their current status.")
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{UnixListener, UnixStream};
+
+use crate::injector::ClaudeProcessManager;
+use crate::payload::InjectionPayload;
+
+/// How long the daemon waits for the next frame before dropping a client
+const CLIENT_IDLE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Injection request sent to a daemon, one JSON object per line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonFrame {
+    pub session_id: String,
+    pub payload: InjectionPayload,
+}
+
+/// Daemon's answer to a [`DaemonFrame`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum DaemonReply {
+    Injected { bytes: usize },
+    Failed { error: String },
+}
+
+/// Socket a foreground `claude-inject spawn --id <id>` serves its session on
+pub fn daemon_socket_path(id: &str) -> PathBuf {
+    let home = dirs::home_dir().expect("Cannot find home directory");
+    home.join(".claude-injector").join("daemon").join(format!("{}.sock", id))
+}
+
+/// Removes the socket file once the daemon stops serving
+struct SocketGuard(PathBuf);
+
+impl Drop for SocketGuard {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+impl ClaudeProcessManager {
+    /// Accept injections into this manager's processes on a Unix socket
+    ///
+    /// Lets a separate process reach the stdin pipes owned by this one through
+    /// [`DaemonClient`]. Clients are served one at a time; each frame is
+    /// answered with a [`DaemonReply`]. Runs until an error occurs or the
+    /// future is dropped, which removes the socket. A socket left behind by a
+    /// daemon that died is replaced; a live one is not.
+    pub async fn serve_daemon(&self, socket_path: &Path) -> Result<()> {
+        if let Some(dir) = socket_path.parent() {
+            std::fs::create_dir_all(dir).context(format!("Failed to create {}", dir.display()))?;
+        }
+
+        if socket_path.exists() {
+            if UnixStream::connect(socket_path).await.is_ok() {
+                anyhow::bail!("A daemon is already listening on {}", socket_path.display());
+            }
+            std::fs::remove_file(socket_path)
+                .context(format!("Failed to remove stale socket {}", socket_path.display()))?;
+        }
+
+        let listener = UnixListener::bind(socket_path)
+            .context(format!("Failed to bind daemon socket {}", socket_path.display()))?;
+        let _guard = SocketGuard(socket_path.to_path_buf());
+
+        // Only the owner may inject through the socket
+        std::fs::set_permissions(socket_path, std::fs::Permissions::from_mode(0o600))?;
+
+        log::info!("Daemon listening on {}", socket_path.display());
+        loop {
+            let (stream, _) = listener.accept().await.context("Failed to accept daemon client")?;
+            if let Err(e) = self.serve_client(stream).await {
+                log::warn!("Daemon client dropped: {:#}", e);
+            }
+        }
+    }
+
+    async fn serve_client(&self, stream: UnixStream) -> Result<()> {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+
+        while let Some(line) = tokio::time::timeout(CLIENT_IDLE_TIMEOUT, lines.next_line())
+            .await
+            .context("Client sent nothing")??
+        {
+            let reply = match serde_json::from_str::<DaemonFrame>(&line) {
+                Ok(frame) => match self.inject(&frame.session_id, frame.payload).await {
+                    Ok(stats) => DaemonReply::Injected { bytes: stats.bytes },
+                    Err(e) => DaemonReply::Failed { error: format!("{:#}", e) },
+                },
+                Err(e) => DaemonReply::Failed { error: format!("Malformed frame: {}", e) },
+            };
+
+            let mut reply = serde_json::to_string(&reply)?;
+            reply.push('\n');
+            writer.write_all(reply.as_bytes()).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Connection to a [`ClaudeProcessManager::serve_daemon`] socket
+pub struct DaemonClient {
+    lines: Lines<BufReader<OwnedReadHalf>>,
+    writer: OwnedWriteHalf,
+}
+
+impl DaemonClient {
+    /// Connect to a daemon, or `None` when nothing is listening on the socket
+    pub async fn connect(socket_path: &Path) -> Result<Option<Self>> {
+        match UnixStream::connect(socket_path).await {
+            Ok(stream) => {
+                let (reader, writer) = stream.into_split();
+                Ok(Some(Self {
+                    lines: BufReader::new(reader).lines(),
+                    writer,
+                }))
+            }
+            Err(e) if matches!(e.kind(), std::io::ErrorKind::NotFound | std::io::ErrorKind::ConnectionRefused) => {
+                Ok(None)
+            }
+            Err(e) => Err(e).context(format!("Failed to connect to daemon at {}", socket_path.display())),
+        }
+    }
+
+    /// Have the daemon inject a payload, returning the bytes written to stdin
+    pub async fn inject(&mut self, session_id: &str, payload: &InjectionPayload) -> Result<usize> {
+        let frame = DaemonFrame {
+            session_id: session_id.to_string(),
+            payload: payload.clone(),
+        };
+        let mut line = serde_json::to_string(&frame)?;
+        line.push('\n');
+        self.writer.write_all(line.as_bytes()).await.context("Failed to send frame to daemon")?;
+
+        let reply = self
+            .lines
+            .next_line()
+            .await
+            .context("Failed to read daemon reply")?
+            .context("Daemon closed the connection")?;
+
+        match serde_json::from_str(&reply).context("Malformed daemon reply")? {
+            DaemonReply::Injected { bytes } => Ok(bytes),
+            DaemonReply::Failed { error } => anyhow::bail!("Daemon failed to inject: {}", error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+
+    #[tokio::test]
+    async fn test_daemon_forwards_frames() {
+        let session = test_session("daemon-test");
+        let socket = std::env::temp_dir().join(format!("claude-daemon-test-{}.sock", std::process::id()));
+        assert!(DaemonClient::connect(&socket).await.unwrap().is_none());
+
+        let manager = ClaudeProcessManager::new();
+        let session_id = manager.spawn_process(session, "cat", &[]).await.unwrap();
+
+        let client = async {
+            let mut client = loop {
+                if let Some(client) = DaemonClient::connect(&socket).await.unwrap() {
+                    break client;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            };
+
+            let bytes = client.inject(&session_id, &InjectionPayload::user_prompt("ping")).await.unwrap();
+            let err = client.inject("unknown", &InjectionPayload::user_prompt("ping")).await.unwrap_err();
+            (bytes, err.to_string())
+        };
+
+        let (bytes, err) = tokio::select! {
+            result = manager.serve_daemon(&socket) => panic!("daemon stopped: {:?}", result),
+            result = client => result,
+        };
+        assert_eq!(bytes, 5);
+        assert!(err.contains("not found"), "{}", err);
+        assert!(!socket.exists());
+
+        let echoed = manager
+            .wait_for_response(&session_id, std::time::Duration::from_millis(200), std::time::Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(echoed, "ping\n");
+
+        manager.stop_session(&session_id).await.unwrap();
+    }
+}
@@ -0,0 +1,229 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::payload::InjectionPayload;
+use crate::session::ClaudeSession;
+
+/// A Claude process that outlives the command that spawned it
+///
+/// Its stdin is a named pipe (FIFO), so any later invocation can inject by
+/// writing to the pipe. The child holds the FIFO open read-write itself, so it
+/// never sees EOF when an injecting writer disconnects.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetachedSession {
+    pub pid: u32,
+    pub fifo_path: PathBuf,
+    pub log_path: PathBuf,
+    /// Conversation the process was started with (`--session-id`); absent in older registries
+    #[serde(default)]
+    pub conversation_id: Option<String>,
+}
+
+impl DetachedSession {
+    /// Spawn `claude` for a session in the background with a FIFO for stdin
+    ///
+    /// Claude is started on a fresh conversation whose id is chosen here, so
+    /// the conversation it writes is known without searching the project.
+    pub fn spawn(id: &str, session: &ClaudeSession, initial_prompt: Option<String>) -> Result<Self> {
+        let conversation_id = new_conversation_id()?;
+        let mut args = vec!["--session-id".to_string(), conversation_id.clone()];
+        if let Some(prompt) = initial_prompt {
+            args.push(prompt);
+        }
+
+        let command = crate::claude_command(&[], &args)?;
+        let spawned =
+            Self::spawn_program(id, &command[0], &command[1..], &session.project_path, &Self::get_detached_dir())?;
+        Ok(Self {
+            conversation_id: Some(conversation_id),
+            ..spawned
+        })
+    }
+
+    /// Directory holding FIFOs and output logs of detached sessions
+    fn get_detached_dir() -> PathBuf {
+        let home = dirs::home_dir().expect("Cannot find home directory");
+        home.join(".claude-injector").join("detached")
+    }
+
+    #[cfg(unix)]
+    fn spawn_program(id: &str, program: &str, args: &[String], working_dir: &str, dir: &Path) -> Result<Self> {
+        use std::os::unix::fs::OpenOptionsExt;
+        use std::os::unix::process::CommandExt;
+        use std::process::{Command, Stdio};
+
+        fs::create_dir_all(dir).context(format!("Failed to create {}", dir.display()))?;
+
+        let fifo_path = dir.join(format!("{}.fifo", id));
+        let log_path = dir.join(format!("{}.log", id));
+
+        if fifo_path.exists() {
+            fs::remove_file(&fifo_path)?;
+        }
+
+        let c_path = std::ffi::CString::new(fifo_path.to_string_lossy().as_bytes())?;
+        if unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) } != 0 {
+            return Err(std::io::Error::last_os_error())
+                .context(format!("Failed to create FIFO {}", fifo_path.display()));
+        }
+
+        // Read-write open never blocks on a FIFO and keeps a writer attached
+        let stdin = fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_CLOEXEC)
+            .open(&fifo_path)
+            .context("Failed to open FIFO for the child process")?;
+
+        let log = fs::File::create(&log_path).context("Failed to create session log")?;
+
+        let child = Command::new(program)
+            .args(args)
+            .current_dir(working_dir)
+            .stdin(Stdio::from(stdin))
+            .stdout(Stdio::from(log.try_clone()?))
+            .stderr(Stdio::from(log))
+            .process_group(0) // Survive the spawning terminal going away
+            .spawn()
+            .context(format!("Failed to spawn {} process", program))?;
+
+        log::info!("Spawned detached {} with PID {} (stdin: {})", program, child.id(), fifo_path.display());
+
+        Ok(Self {
+            pid: child.id(),
+            fifo_path,
+            log_path,
+            conversation_id: None,
+        })
+    }
+
+    #[cfg(not(unix))]
+    fn spawn_program(_id: &str, _program: &str, _args: &[String], _working_dir: &str, _dir: &Path) -> Result<Self> {
+        anyhow::bail!("Detached sessions are only supported on Unix");
+    }
+
+    /// Write a payload to the session's stdin FIFO
+    #[cfg(unix)]
+    pub fn inject(&self, payload: &InjectionPayload) -> Result<()> {
+        use std::io::Write;
+        use std::os::unix::fs::OpenOptionsExt;
+        use std::os::unix::io::AsRawFd;
+
+        if !self.fifo_path.exists() {
+            anyhow::bail!("Session stdin FIFO is missing: {}", self.fifo_path.display());
+        }
+
+        // Non-blocking open fails with ENXIO instead of hanging when no reader is left
+        let mut fifo = match fs::OpenOptions::new()
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(&self.fifo_path)
+        {
+            Ok(fifo) => fifo,
+            Err(e) if e.raw_os_error() == Some(libc::ENXIO) => {
+                anyhow::bail!(
+                    "Claude process {} has exited: nothing is reading {}",
+                    self.pid,
+                    self.fifo_path.display()
+                );
+            }
+            Err(e) => return Err(e).context(format!("Failed to open {}", self.fifo_path.display())),
+        };
+
+        // Block on writes so large payloads are not cut short by a full pipe
+        unsafe {
+            let fd = fifo.as_raw_fd();
+            let flags = libc::fcntl(fd, libc::F_GETFL);
+            libc::fcntl(fd, libc::F_SETFL, flags & !libc::O_NONBLOCK);
+        }
+
+        let message = payload.to_injection_string();
+        let result = fifo
+            .write_all(message.as_bytes())
+            .and_then(|_| fifo.write_all(b"\n"))
+            .and_then(|_| fifo.flush());
+
+        match result {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => anyhow::bail!(
+                "Claude process {} exited while the payload was being written",
+                self.pid
+            ),
+            Err(e) => Err(e).context("Failed to write to session FIFO"),
+        }
+    }
+
+    #[cfg(not(unix))]
+    pub fn inject(&self, _payload: &InjectionPayload) -> Result<()> {
+        anyhow::bail!("Detached sessions are only supported on Unix");
+    }
+
+    /// Check if the detached process is still running
+    pub fn is_running(&self) -> bool {
+        crate::ProcessDetector::is_process_running(self.pid)
+    }
+
+    /// Terminate the process and remove its FIFO
+    pub fn stop(&self) -> Result<()> {
+        if self.is_running() {
+            crate::ProcessDetector::kill_process(self.pid)?;
+        }
+
+        if self.fifo_path.exists() {
+            fs::remove_file(&self.fifo_path)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A random (version 4) UUID, the form Claude expects for `--session-id`
+fn new_conversation_id() -> Result<String> {
+    use std::io::Read;
+
+    let mut bytes = [0u8; 16];
+    fs::File::open("/dev/urandom")
+        .and_then(|mut random| random.read_exact(&mut bytes))
+        .context("Failed to read /dev/urandom")?;
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    Ok(format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..]))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detached_round_trip() {
+        let dir = std::env::temp_dir().join(format!("claude-detached-test-{}", std::process::id()));
+        let session = DetachedSession::spawn_program("echo", "cat", &[], "/tmp", &dir).unwrap();
+
+        session.inject(&InjectionPayload::user_prompt("hello detached")).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        assert!(fs::read_to_string(&session.log_path).unwrap().contains("hello detached"));
+
+        // Once the reader is gone, injection fails with a precise error
+        crate::ProcessDetector::kill_process(session.pid).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        let err = session.inject(&InjectionPayload::user_prompt("too late")).unwrap_err();
+        assert!(err.to_string().contains("has exited"), "{}", err);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_new_conversation_id() {
+        let id = new_conversation_id().unwrap();
+        let groups: Vec<usize> = id.split('-').map(str::len).collect();
+        assert_eq!(groups, [8, 4, 4, 4, 12]);
+        assert!(id.chars().all(|c| c == '-' || c.is_ascii_hexdigit()));
+        assert_eq!(&id[14..15], "4");
+        assert!("89ab".contains(&id[19..20]));
+        assert_ne!(id, new_conversation_id().unwrap());
+    }
+}
@@ -1,20 +1,211 @@
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 use std::process::Command;
+use std::sync::RwLock;
 
 /// Information about a running Claude process
 #[derive(Debug, Clone)]
 pub struct RunningProcess {
     pub pid: u32,
+    /// Owner's uid; `None` where the platform does not report it
+    pub uid: Option<u32>,
+    /// Owner's user name, when the uid resolves to one
+    pub user: Option<String>,
     pub command: String,
     pub working_dir: Option<String>,
 }
 
+/// Whose processes [`ProcessDetector::find_running_claude_processes`] reports
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum ProcessUserFilter {
+    /// Processes of the user running this tool
+    #[default]
+    Current,
+    /// Processes of the named user
+    Named(String),
+    /// Every user's processes
+    All,
+}
+
+impl ProcessUserFilter {
+    /// The uid processes must be owned by, or `None` for every user
+    ///
+    /// A named user may also be given as a numeric uid. Fails for a name
+    /// that is not a user on this system.
+    pub fn wanted_uid(&self) -> Result<Option<u32>> {
+        match self {
+            ProcessUserFilter::All => Ok(None),
+            ProcessUserFilter::Current => Ok(current_uid()),
+            ProcessUserFilter::Named(name) => match name.parse::<u32>() {
+                Ok(uid) => Ok(Some(uid)),
+                Err(_) => uid_of(name).map(Some).context(format!("Unknown user '{}'", name)),
+            },
+        }
+    }
+
+    /// Whether a process owned by `uid` passes; processes with no known owner always do
+    pub fn matches(&self, uid: Option<u32>) -> bool {
+        match (uid, self.wanted_uid()) {
+            (None, _) | (_, Ok(None)) => true,
+            (Some(uid), Ok(Some(wanted))) => uid == wanted,
+            (Some(_), Err(_)) => false,
+        }
+    }
+}
+
+/// Set by `--user` / `--all-users` on the command line tools
+static USER_FILTER: RwLock<ProcessUserFilter> = RwLock::new(ProcessUserFilter::Current);
+
+/// Restrict process detection to `filter` for the rest of the process
+pub fn set_process_user_filter(filter: ProcessUserFilter) {
+    *USER_FILTER.write().unwrap_or_else(|e| e.into_inner()) = filter;
+}
+
+/// Size of the buffer `getpwuid_r`/`getpwnam_r` fill with a passwd entry's strings
+#[cfg(unix)]
+const PASSWD_BUFFER_LEN: usize = 4096;
+
+/// Uid of the user running this tool
+#[cfg(unix)]
+fn current_uid() -> Option<u32> {
+    Some(unsafe { libc::getuid() })
+}
+
+#[cfg(not(unix))]
+fn current_uid() -> Option<u32> {
+    None
+}
+
+/// Look up a passwd entry with `getpwuid_r` or `getpwnam_r`, passed in as `lookup`
+#[cfg(unix)]
+fn with_passwd<T>(
+    lookup: impl FnOnce(*mut libc::passwd, *mut libc::c_char, usize, *mut *mut libc::passwd) -> libc::c_int,
+    read: impl FnOnce(&libc::passwd) -> T,
+) -> Option<T> {
+    let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut buffer = vec![0 as libc::c_char; PASSWD_BUFFER_LEN];
+    let mut result = std::ptr::null_mut();
+
+    // SAFETY: the entry's strings point into `buffer`, which outlives `read`
+    let status = lookup(&mut passwd, buffer.as_mut_ptr(), buffer.len(), &mut result);
+    if status != 0 || result.is_null() {
+        return None;
+    }
+    Some(read(&passwd))
+}
+
+/// Name of the user with `uid`
+#[cfg(unix)]
+pub fn user_name(uid: u32) -> Option<String> {
+    with_passwd(
+        |passwd, buffer, len, result| unsafe { libc::getpwuid_r(uid, passwd, buffer, len, result) },
+        |passwd| unsafe { std::ffi::CStr::from_ptr(passwd.pw_name) }.to_string_lossy().into_owned(),
+    )
+}
+
+#[cfg(not(unix))]
+pub fn user_name(_uid: u32) -> Option<String> {
+    None
+}
+
+/// Uid of the user called `name`
+#[cfg(unix)]
+fn uid_of(name: &str) -> Option<u32> {
+    let name = std::ffi::CString::new(name).ok()?;
+    with_passwd(
+        |passwd, buffer, len, result| unsafe { libc::getpwnam_r(name.as_ptr(), passwd, buffer, len, result) },
+        |passwd| passwd.pw_uid,
+    )
+}
+
+#[cfg(not(unix))]
+fn uid_of(_name: &str) -> Option<u32> {
+    None
+}
+
+impl RunningProcess {
+    /// What kind of Claude this process is
+    pub fn kind(&self) -> ProcessKind {
+        ProcessKind::classify(&self.command)
+    }
+}
+
+/// Which Claude application a detected process belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessKind {
+    /// The `claude` CLI, which runs in a terminal and can be injected into
+    Cli,
+    /// The Claude Desktop app, which has no terminal to inject into
+    Desktop,
+    /// Something else that mentions claude in its command line
+    Unknown,
+}
+
+impl ProcessKind {
+    /// Classify a process by its command line
+    pub fn classify(command: &str) -> Self {
+        const DESKTOP_MARKERS: [&str; 5] = [
+            "Claude.app/",
+            "/Applications/Claude",
+            "AnthropicClaude",
+            "claude-desktop",
+            "Claude.exe",
+        ];
+        if DESKTOP_MARKERS.iter().any(|marker| command.contains(marker)) {
+            return ProcessKind::Desktop;
+        }
+
+        let mut tokens = command.split_whitespace();
+        let program = tokens.next().unwrap_or_default();
+        let is_named = |token: &str, name: &str| token.rsplit(['/', '\\']).next() == Some(name);
+
+        if is_named(program, "claude") {
+            return ProcessKind::Cli;
+        }
+
+        // npm installs run the CLI as a node script
+        if is_named(program, "node") {
+            if let Some(script) = tokens.next() {
+                if is_named(script, "claude") || script.contains("@anthropic-ai/claude-code") {
+                    return ProcessKind::Cli;
+                }
+            }
+        }
+
+        ProcessKind::Unknown
+    }
+}
+
+impl std::fmt::Display for ProcessKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            ProcessKind::Cli => "cli",
+            ProcessKind::Desktop => "desktop",
+            ProcessKind::Unknown => "unknown",
+        };
+        f.write_str(name)
+    }
+}
+
 /// Detector for finding running Claude processes on the system
 pub struct ProcessDetector;
 
 impl ProcessDetector {
-    /// Find all running Claude processes
+    /// Find running Claude processes of the users selected by [`set_process_user_filter`]
+    ///
+    /// Only the current user's processes are reported unless another filter
+    /// was set. Owners are not known on Windows, so nothing is filtered there.
     pub fn find_running_claude_processes() -> Result<Vec<RunningProcess>> {
+        let filter = USER_FILTER.read().unwrap_or_else(|e| e.into_inner()).clone();
+        let wanted = filter.wanted_uid()?;
+        Ok(Self::find_all_claude_processes()?
+            .into_iter()
+            .filter(|process| wanted.is_none() || process.uid.is_none() || process.uid == wanted)
+            .collect())
+    }
+
+    fn find_all_claude_processes() -> Result<Vec<RunningProcess>> {
         #[cfg(target_os = "linux")]
         {
             Self::find_linux()
@@ -36,19 +227,28 @@ impl ProcessDetector {
         }
     }
 
-    #[cfg(target_os = "linux")]
+    /// Claude processes from `ps -eo uid=,pid=,args=`, which Linux and macOS both understand
+    ///
+    /// Owners are read as numeric uids: `ps aux` shortens long user names.
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
     fn find_linux() -> Result<Vec<RunningProcess>> {
         let output = Command::new("ps")
-            .args(["aux"])
+            .args(["-eo", "uid=,pid=,args="])
             .output()
             .context("Failed to execute ps command")?;
 
         let stdout = String::from_utf8_lossy(&output.stdout);
         let mut processes = Vec::new();
 
+        let own_pid = std::process::id();
+
         for line in stdout.lines() {
             if line.contains("claude") && !line.contains("grep") {
                 if let Some(process) = Self::parse_ps_line(line) {
+                    // Skip this tool and anything it spawned (e.g. managed claude children)
+                    if Self::is_self_or_descendant(process.pid, own_pid) {
+                        continue;
+                    }
                     processes.push(process);
                 }
             }
@@ -57,9 +257,46 @@ impl ProcessDetector {
         Ok(processes)
     }
 
+    /// Get the parent PID of a process
+    #[cfg(target_os = "linux")]
+    pub fn get_parent_pid(pid: u32) -> Option<u32> {
+        let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+
+        status
+            .lines()
+            .find(|line| line.starts_with("PPid:"))?
+            .split_whitespace()
+            .nth(1)?
+            .parse::<u32>()
+            .ok()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn get_parent_pid(_pid: u32) -> Option<u32> {
+        None
+    }
+
+    /// Check whether `pid` is `ancestor` or one of its descendants
+    pub fn is_self_or_descendant(pid: u32, ancestor: u32) -> bool {
+        let mut current = pid;
+
+        // Bound the walk in case of a ppid cycle from a racing /proc read
+        for _ in 0..64 {
+            if current == ancestor {
+                return true;
+            }
+            match Self::get_parent_pid(current) {
+                Some(parent) if parent > 1 && parent != current => current = parent,
+                _ => return false,
+            }
+        }
+
+        false
+    }
+
     #[cfg(target_os = "macos")]
     fn find_macos() -> Result<Vec<RunningProcess>> {
-        // Similar to Linux
+        // Only the working directory lookup differs, see `get_process_cwd`
         Self::find_linux()
     }
 
@@ -84,22 +321,25 @@ impl ProcessDetector {
     }
 
     fn parse_ps_line(line: &str) -> Option<RunningProcess> {
-        let parts: Vec<&str> = line.split_whitespace().collect();
+        let mut parts = line.split_whitespace();
+        let uid = parts.next()?.parse::<u32>().ok()?;
+        let pid = parts.next()?.parse::<u32>().ok()?;
+        let command = parts.collect::<Vec<_>>().join(" ");
 
-        if parts.len() < 11 {
+        if command.is_empty() {
             return None;
         }
 
-        let pid = parts[1].parse::<u32>().ok()?;
-        let command = parts[10..].join(" ");
-
         Some(RunningProcess {
             pid,
+            uid: Some(uid),
+            user: user_name(uid),
             command,
             working_dir: None,
         })
     }
 
+    #[cfg(target_os = "windows")]
     fn parse_tasklist_line(line: &str) -> Option<RunningProcess> {
         let parts: Vec<&str> = line.split(',').collect();
 
@@ -112,12 +352,14 @@ impl ProcessDetector {
 
         Some(RunningProcess {
             pid,
+            uid: None,
+            user: None,
             command,
             working_dir: None,
         })
     }
 
-    /// Get working directory for a process (Linux only for now)
+    /// Get working directory for a process
     #[cfg(target_os = "linux")]
     pub fn get_process_cwd(pid: u32) -> Option<String> {
         std::fs::read_link(format!("/proc/{}/cwd", pid))
@@ -125,7 +367,33 @@ impl ProcessDetector {
             .and_then(|p| p.to_str().map(|s| s.to_string()))
     }
 
-    #[cfg(not(target_os = "linux"))]
+    /// Get working directory for a process
+    ///
+    /// macOS has no `/proc`; the kernel reports the current-directory vnode
+    /// through `proc_pidinfo`. Processes of other users are only readable as root.
+    #[cfg(target_os = "macos")]
+    pub fn get_process_cwd(pid: u32) -> Option<String> {
+        let mut info: libc::proc_vnodepathinfo = unsafe { std::mem::zeroed() };
+        let size = std::mem::size_of::<libc::proc_vnodepathinfo>() as libc::c_int;
+
+        let written = unsafe {
+            libc::proc_pidinfo(
+                pid as libc::c_int,
+                libc::PROC_PIDVNODEPATHINFO,
+                0,
+                &mut info as *mut libc::proc_vnodepathinfo as *mut libc::c_void,
+                size,
+            )
+        };
+        if written != size {
+            return None;
+        }
+
+        let path = unsafe { std::ffi::CStr::from_ptr(info.pvi_cdir.vip_path.as_ptr() as *const libc::c_char) };
+        path.to_str().ok().filter(|path| !path.is_empty()).map(str::to_string)
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
     pub fn get_process_cwd(_pid: u32) -> Option<String> {
         None
     }
@@ -182,6 +450,60 @@ impl ProcessDetector {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_classify_process_kind() {
+        assert_eq!(ProcessKind::classify("claude"), ProcessKind::Cli);
+        assert_eq!(ProcessKind::classify("/usr/local/bin/claude --resume abc"), ProcessKind::Cli);
+        assert_eq!(
+            ProcessKind::classify("node /usr/lib/node_modules/@anthropic-ai/claude-code/cli.js"),
+            ProcessKind::Cli
+        );
+        assert_eq!(
+            ProcessKind::classify("/Applications/Claude.app/Contents/MacOS/Claude"),
+            ProcessKind::Desktop
+        );
+        assert_eq!(
+            ProcessKind::classify("C:\\Users\\me\\AppData\\Local\\AnthropicClaude\\claude.exe"),
+            ProcessKind::Desktop
+        );
+        assert_eq!(ProcessKind::classify("vim claude.md"), ProcessKind::Unknown);
+        assert_eq!(ProcessKind::classify("claude-inject find"), ProcessKind::Unknown);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_user_filter_compares_uids() {
+        let process = ProcessDetector::parse_ps_line("    0  4242 claude --resume  abc").unwrap();
+        assert_eq!(process.pid, 4242);
+        assert_eq!(process.uid, Some(0));
+        assert_eq!(process.user.as_deref(), Some("root"));
+        assert_eq!(process.command, "claude --resume abc");
+        assert!(ProcessDetector::parse_ps_line("root 4242 claude").is_none());
+
+        let own = unsafe { libc::getuid() };
+        let own_name = user_name(own).unwrap();
+        assert_eq!(uid_of(&own_name), Some(own));
+
+        assert!(ProcessUserFilter::Current.matches(Some(own)));
+        assert!(!ProcessUserFilter::Current.matches(Some(own + 1)));
+        assert!(ProcessUserFilter::Named(own_name).matches(Some(own)));
+        assert!(ProcessUserFilter::Named("root".to_string()).matches(Some(0)));
+        assert!(!ProcessUserFilter::Named("root".to_string()).matches(Some(4242)));
+        assert!(ProcessUserFilter::Named("4242".to_string()).matches(Some(4242)));
+        assert!(!ProcessUserFilter::Named("no-such-user-here".to_string()).matches(Some(0)));
+        assert!(ProcessUserFilter::Named("no-such-user-here".to_string()).wanted_uid().is_err());
+        assert!(ProcessUserFilter::All.matches(Some(4242)));
+        assert!(ProcessUserFilter::Current.matches(None));
+    }
+
+    #[test]
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    fn test_get_process_cwd_of_current_process() {
+        let cwd = std::env::current_dir().unwrap();
+        let found = ProcessDetector::get_process_cwd(std::process::id()).expect("cwd of own process");
+        assert_eq!(std::path::PathBuf::from(found), cwd);
+    }
+
     #[test]
     fn test_find_running_processes() {
         match ProcessDetector::find_running_claude_processes() {
@@ -202,6 +524,26 @@ mod tests {
         }
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_spawned_children_are_not_detected() {
+        // A child whose command line mentions claude must not show up as a discovered session
+        let mut child = Command::new("sh")
+            .args(["-c", "sleep 5; echo claude"])
+            .spawn()
+            .unwrap();
+        let child_pid = child.id();
+
+        assert!(ProcessDetector::is_self_or_descendant(child_pid, std::process::id()));
+
+        let processes = ProcessDetector::find_running_claude_processes().unwrap();
+        assert!(processes.iter().all(|p| p.pid != child_pid));
+        assert!(processes.iter().all(|p| p.pid != std::process::id()));
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+
     #[test]
     fn test_process_detection() {
         // This test requires a Claude process to be running
@@ -0,0 +1,81 @@
+use anyhow::{Context, Result};
+use std::time::Duration;
+
+/// Parse a human duration such as `500ms`, `10m`, `2h` or `1h30m`
+///
+/// Supported units are `ms`, `s`, `m`, `h` and `d`. A bare number is taken
+/// as seconds.
+pub fn parse_duration(input: &str) -> Result<Duration> {
+    let input = input.trim();
+    if input.is_empty() {
+        anyhow::bail!("Empty duration");
+    }
+
+    if let Ok(secs) = input.parse::<u64>() {
+        return Ok(Duration::from_secs(secs));
+    }
+
+    let mut total = Duration::ZERO;
+    let mut rest = input;
+
+    while !rest.is_empty() {
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits == 0 {
+            anyhow::bail!("Invalid duration '{}': expected a number before '{}'", input, rest);
+        }
+
+        let value: u64 = rest[..digits]
+            .parse()
+            .context(format!("Invalid duration '{}'", input))?;
+        rest = &rest[digits..];
+
+        let unit_len = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_alphabetic()).len();
+        let unit = &rest[..unit_len];
+        rest = &rest[unit_len..];
+
+        let too_long = || anyhow::anyhow!("Invalid duration '{}': too long", input);
+        let secs_per = |unit_secs: u64| value.checked_mul(unit_secs).map(Duration::from_secs).ok_or_else(too_long);
+
+        let part = match unit {
+            "ms" => Duration::from_millis(value),
+            "s" => Duration::from_secs(value),
+            "m" => secs_per(60)?,
+            "h" => secs_per(60 * 60)?,
+            "d" => secs_per(60 * 60 * 24)?,
+            "" => anyhow::bail!("Invalid duration '{}': missing unit after {}", input, value),
+            other => anyhow::bail!(
+                "Invalid duration '{}': unknown unit '{}' (use ms, s, m, h or d)",
+                input,
+                other
+            ),
+        };
+
+        total = total.checked_add(part).ok_or_else(too_long)?;
+    }
+
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("500ms").unwrap(), Duration::from_millis(500));
+        assert_eq!(parse_duration("10m").unwrap(), Duration::from_secs(600));
+        assert_eq!(parse_duration("2h").unwrap(), Duration::from_secs(7200));
+        assert_eq!(parse_duration("1h30m").unwrap(), Duration::from_secs(5400));
+        assert_eq!(parse_duration("1m30s500ms").unwrap(), Duration::from_millis(90_500));
+        assert_eq!(parse_duration("45").unwrap(), Duration::from_secs(45));
+
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("10x").is_err());
+        assert!(parse_duration("1h30").is_err());
+        assert!(parse_duration("h").is_err());
+
+        // Overflow is an error, not a panic or a wrapped value
+        assert!(parse_duration("99999999999999999d").is_err());
+        assert!(parse_duration("18446744073709551615s1s").is_err());
+    }
+}
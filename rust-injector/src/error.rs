@@ -0,0 +1,135 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Failures callers may want to tell apart
+///
+/// Returned inside `anyhow::Error`; use `err.downcast_ref::<InjectorError>()`
+/// to inspect them, or [`InjectorError::exit_code_of`] to map them to a
+/// process exit code.
+#[derive(Debug)]
+pub enum InjectorError {
+    /// No running Claude session with this id (or in this directory)
+    SessionNotFound(String),
+    /// No tmux session with this name
+    TmuxSessionNotFound(String),
+    /// A tmux session went away while it was being used
+    SessionGone(String),
+    /// tmux is not installed
+    TmuxUnavailable,
+    /// The `claude` binary is not on `PATH`
+    ClaudeNotFound {
+        /// Directories that were searched
+        searched: Vec<PathBuf>,
+    },
+    /// The terminal refused the injection (permissions, or TIOCSTI disabled)
+    PermissionDenied(String),
+    /// A wait for Claude exceeded its total time bound
+    Timeout {
+        limit: Duration,
+        /// Output collected before the bound was hit
+        partial_output: String,
+    },
+    /// A worker was sent a message sooner than its minimum injection interval allows
+    RateLimited {
+        name: String,
+        /// How much longer the caller has to wait
+        retry_after: Duration,
+    },
+    /// The message was empty or only whitespace, which would submit a blank prompt
+    EmptyMessage,
+}
+
+impl InjectorError {
+    /// Exit code for this failure; other errors exit with 1
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            InjectorError::SessionNotFound(_)
+            | InjectorError::TmuxSessionNotFound(_)
+            | InjectorError::SessionGone(_) => 2,
+            InjectorError::TmuxUnavailable => 3,
+            InjectorError::PermissionDenied(_) => 4,
+            InjectorError::Timeout { .. } => 5,
+            InjectorError::RateLimited { .. } => 6,
+            InjectorError::ClaudeNotFound { .. } => 7,
+            InjectorError::EmptyMessage => 1,
+        }
+    }
+
+    /// Exit code for any error: that of the first [`InjectorError`] in its chain, else 1
+    pub fn exit_code_of(err: &anyhow::Error) -> i32 {
+        Self::find_in(err).map_or(1, InjectorError::exit_code)
+    }
+
+    /// The first [`InjectorError`] in an error's chain
+    pub fn find_in(err: &anyhow::Error) -> Option<&InjectorError> {
+        err.chain().find_map(|cause| cause.downcast_ref::<InjectorError>())
+    }
+}
+
+impl fmt::Display for InjectorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InjectorError::SessionNotFound(id) => write!(f, "Session '{}' not found or not running", id),
+            InjectorError::TmuxSessionNotFound(name) => write!(f, "Tmux session '{}' not found", name),
+            InjectorError::SessionGone(name) => write!(f, "Tmux session '{}' disappeared while in use", name),
+            InjectorError::TmuxUnavailable => write!(f, "tmux is not installed. Install with: sudo apt install tmux"),
+            InjectorError::PermissionDenied(detail) => write!(f, "Permission denied: {}", detail),
+            InjectorError::Timeout { limit, partial_output } => write!(
+                f,
+                "Timed out after {:?} ({} bytes of partial output)",
+                limit,
+                partial_output.len()
+            ),
+            InjectorError::RateLimited { name, retry_after } => write!(
+                f,
+                "Worker '{}' was messaged too recently; retry in {:?}",
+                name, retry_after
+            ),
+            InjectorError::ClaudeNotFound { searched } => write!(
+                f,
+                "claude is not installed or not on PATH (searched: {}). \
+                 Install with: npm install -g @anthropic-ai/claude-code",
+                searched.iter().map(|dir| dir.display().to_string()).collect::<Vec<_>>().join(", ")
+            ),
+            InjectorError::EmptyMessage => write!(
+                f,
+                "Refusing to inject an empty message (use --allow-empty to send a bare Enter)"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InjectorError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_exit_code_of_wrapped_errors() {
+        let err = Err::<(), _>(InjectorError::TmuxSessionNotFound("w1".to_string()))
+            .context("Failed to notify w1")
+            .unwrap_err();
+        assert_eq!(InjectorError::exit_code_of(&err), 2);
+
+        let gone = anyhow::Error::new(InjectorError::SessionGone("w1".to_string())).context("Failed to send Enter key");
+        assert!(matches!(InjectorError::find_in(&gone), Some(InjectorError::SessionGone(_))));
+        assert_eq!(InjectorError::exit_code_of(&gone), 2);
+
+        let timeout = anyhow::Error::new(InjectorError::Timeout {
+            limit: Duration::from_secs(1),
+            partial_output: String::new(),
+        });
+        assert_eq!(InjectorError::exit_code_of(&timeout), 5);
+
+        let limited = anyhow::Error::new(InjectorError::RateLimited {
+            name: "w1".to_string(),
+            retry_after: Duration::from_secs(3),
+        });
+        assert_eq!(InjectorError::exit_code_of(&limited), 6);
+        assert_eq!(InjectorError::exit_code_of(&InjectorError::ClaudeNotFound { searched: vec![] }.into()), 7);
+        assert_eq!(InjectorError::exit_code_of(&anyhow::anyhow!("other")), 1);
+    }
+}
@@ -0,0 +1,151 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use tokio::io::AsyncWriteExt;
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{broadcast, Mutex};
+
+use crate::worker_registry::WorkerStatus;
+
+/// Event emitted by the worker supervisor
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WorkerEvent {
+    /// A worker's status changed
+    StatusChanged {
+        name: String,
+        from: WorkerStatus,
+        to: WorkerStatus,
+    },
+    /// A stopped worker was removed from the registry
+    Reaped { name: String },
+    /// A worker outlived its TTL and was stopped and removed
+    Expired { name: String },
+    /// A payload was injected into a worker, see [`emit`]
+    ///
+    /// For a managed session `name` is its session id.
+    Injected {
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        correlation_id: Option<String>,
+        /// Bytes written, see [`crate::InjectStats`]
+        bytes: usize,
+        /// Time from the inject call until the write was flushed
+        duration_ms: u64,
+    },
+}
+
+/// Events a subscriber may fall behind by before it starts missing them
+const EVENT_BUFFER: usize = 256;
+
+/// Process-wide channel for events raised outside the supervisor, such as injections
+static EVENTS: OnceLock<broadcast::Sender<WorkerEvent>> = OnceLock::new();
+
+fn events() -> &'static broadcast::Sender<WorkerEvent> {
+    EVENTS.get_or_init(|| broadcast::channel(EVENT_BUFFER).0)
+}
+
+/// Receive the events [`emit`]ted in this process from now on
+pub fn subscribe() -> broadcast::Receiver<WorkerEvent> {
+    events().subscribe()
+}
+
+/// Send an event to this process's subscribers; dropped when there are none
+pub fn emit(event: WorkerEvent) {
+    let _ = events().send(event);
+}
+
+/// Unix socket that streams events to connected clients as JSON lines
+pub struct EventSocket {
+    path: PathBuf,
+    clients: Arc<Mutex<Vec<UnixStream>>>,
+    accept_task: tokio::task::JoinHandle<()>,
+}
+
+impl EventSocket {
+    /// Bind the socket and start accepting clients
+    pub async fn bind(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+
+        // Remove a stale socket left by a previous run
+        if path.exists() {
+            std::fs::remove_file(&path)
+                .context(format!("Failed to remove stale socket: {}", path.display()))?;
+        }
+
+        let listener = UnixListener::bind(&path)
+            .context(format!("Failed to bind event socket: {}", path.display()))?;
+
+        let clients: Arc<Mutex<Vec<UnixStream>>> = Arc::new(Mutex::new(Vec::new()));
+        let accept_clients = clients.clone();
+
+        let accept_task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                log::debug!("Event socket client connected");
+                accept_clients.lock().await.push(stream);
+            }
+        });
+
+        Ok(Self {
+            path,
+            clients,
+            accept_task,
+        })
+    }
+
+    /// Send an event to every connected client, dropping clients that went away
+    pub async fn publish<T: Serialize>(&self, event: &T) -> Result<()> {
+        let mut line = serde_json::to_string(event)?;
+        line.push('\n');
+
+        let mut clients = self.clients.lock().await;
+        let mut connected = Vec::with_capacity(clients.len());
+
+        for mut client in clients.drain(..) {
+            if client.write_all(line.as_bytes()).await.is_ok() {
+                connected.push(client);
+            }
+        }
+
+        *clients = connected;
+        Ok(())
+    }
+
+    /// Socket path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for EventSocket {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    #[tokio::test]
+    async fn test_publish_to_client() {
+        let path = std::env::temp_dir().join(format!("claude-events-test-{}.sock", std::process::id()));
+        let socket = EventSocket::bind(&path).await.unwrap();
+
+        let client = UnixStream::connect(&path).await.unwrap();
+        // Give the accept loop a moment to register the client
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+        let event = WorkerEvent::Reaped {
+            name: "worker-1".to_string(),
+        };
+        socket.publish(&event).await.unwrap();
+
+        let mut lines = BufReader::new(client).lines();
+        let line = lines.next_line().await.unwrap().unwrap();
+        assert_eq!(serde_json::from_str::<WorkerEvent>(&line).unwrap(), event);
+    }
+}
@@ -0,0 +1,276 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::Path;
+
+use crate::payload::{InjectionPayload, PayloadType};
+use crate::state_file::StateFile;
+
+/// Characters of payload content shown in a [`PayloadSummary`]
+const PREVIEW_LENGTH: usize = 60;
+
+/// A payload waiting to be delivered to a session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueuedPayload {
+    pub queued_at: u64,
+    pub payload: InjectionPayload,
+    /// Drop the payload instead of delivering it from this time on (unix seconds)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+}
+
+impl QueuedPayload {
+    /// Whether the payload is past its expiry at `now` (unix seconds)
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+}
+
+/// What an operator needs to recognise a queued payload
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PayloadSummary {
+    /// Place in the queue, 1 being delivered next
+    pub position: usize,
+    pub payload_type: PayloadType,
+    pub preview: String,
+    pub queued_at: u64,
+    pub expires_at: Option<u64>,
+}
+
+/// Undelivered payloads per session, kept in `~/.claude-injector-queue.json`
+///
+/// The file is shared between invocations, so one command can queue prompts
+/// or cancel them while another process is delivering them.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct InjectionQueue {
+    sessions: HashMap<String, VecDeque<QueuedPayload>>,
+}
+
+impl InjectionQueue {
+    /// Load queues from the default file
+    pub fn load() -> Result<Self> {
+        match Self::state_file().read()? {
+            Some(content) => Ok(serde_json::from_str(&content)?),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Load queues from a specific file
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Save queues to the default file
+    pub fn save(&self) -> Result<()> {
+        Self::state_file().write(&serde_json::to_string_pretty(&self)?)
+    }
+
+    /// Save queues to a specific file
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        crate::state_file::write_atomic(path, &serde_json::to_string_pretty(&self)?)?;
+        Ok(())
+    }
+
+    /// Load, change and save the default file while holding its lock
+    ///
+    /// Use this for every modification, so concurrent invocations cannot
+    /// overwrite each other's changes.
+    pub fn update<T>(change: impl FnOnce(&mut Self) -> T) -> Result<T> {
+        let _lock = Self::state_file().lock()?;
+        let mut queue = Self::load()?;
+        let result = change(&mut queue);
+        queue.save()?;
+        Ok(result)
+    }
+
+    /// Queue file in the home directory, with fallbacks for read-only homes
+    fn state_file() -> StateFile {
+        StateFile::in_home(".claude-injector-queue.json")
+    }
+
+    /// Add a payload to the end of a session's queue
+    ///
+    /// With a `ttl`, the payload is dropped rather than delivered once it has
+    /// waited that long.
+    pub fn push(&mut self, session_id: &str, payload: InjectionPayload, ttl: Option<std::time::Duration>) {
+        let queued_at = now();
+
+        self.sessions
+            .entry(session_id.to_string())
+            .or_default()
+            .push_back(QueuedPayload {
+                queued_at,
+                payload,
+                expires_at: ttl.map(|ttl| queued_at.saturating_add(ttl.as_secs())),
+            });
+    }
+
+    /// Take the next payload to deliver to a session
+    ///
+    /// Expired payloads ahead of it are removed and logged.
+    pub fn pop(&mut self, session_id: &str) -> Option<InjectionPayload> {
+        let next = self.peek(session_id)?;
+        self.remove(session_id, &next);
+        Some(next.payload)
+    }
+
+    /// The next payload to deliver to a session, left in the queue
+    ///
+    /// Expired payloads ahead of it are removed and logged. Once the payload
+    /// has been delivered, take it out with [`InjectionQueue::remove`].
+    pub fn peek(&mut self, session_id: &str) -> Option<QueuedPayload> {
+        let queue = self.sessions.get_mut(session_id)?;
+        let now = now();
+
+        while let Some(queued) = queue.front() {
+            if !queued.is_expired(now) {
+                break;
+            }
+            let queued = queue.pop_front().unwrap();
+            log::warn!(
+                "Dropping expired {:?} payload for session {} (queued {}s ago): {}",
+                queued.payload.payload_type,
+                session_id,
+                now.saturating_sub(queued.queued_at),
+                preview(&queued.payload.content)
+            );
+        }
+
+        let next = queue.front().cloned();
+        if queue.is_empty() {
+            self.sessions.remove(session_id);
+        }
+        next
+    }
+
+    /// Remove a payload returned by [`InjectionQueue::peek`], if it is still queued
+    pub fn remove(&mut self, session_id: &str, queued: &QueuedPayload) {
+        let Some(queue) = self.sessions.get_mut(session_id) else {
+            return;
+        };
+
+        if let Some(index) = queue.iter().position(|candidate| candidate == queued) {
+            queue.remove(index);
+        }
+        if queue.is_empty() {
+            self.sessions.remove(session_id);
+        }
+    }
+
+    /// Summaries of a session's undelivered payloads, next first
+    pub fn pending(&self, session_id: &str) -> Vec<PayloadSummary> {
+        self.sessions
+            .get(session_id)
+            .map(|queue| {
+                queue
+                    .iter()
+                    .enumerate()
+                    .map(|(i, queued)| PayloadSummary {
+                        position: i + 1,
+                        payload_type: queued.payload.payload_type.clone(),
+                        preview: preview(&queued.payload.content),
+                        queued_at: queued.queued_at,
+                        expires_at: queued.expires_at,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Drop a session's undelivered payloads, returning how many there were
+    pub fn clear(&mut self, session_id: &str) -> usize {
+        self.sessions.remove(session_id).map_or(0, |queue| queue.len())
+    }
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// First line of `content`, shortened to [`PREVIEW_LENGTH`] characters
+fn preview(content: &str) -> String {
+    let line = content.lines().next().unwrap_or_default();
+    if line.chars().count() > PREVIEW_LENGTH || content.lines().nth(1).is_some() {
+        format!("{}…", line.chars().take(PREVIEW_LENGTH).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_can_be_listed_and_cleared() {
+        let path = std::env::temp_dir().join(format!("claude-injector-queue-test-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut queue = InjectionQueue::load_from(&path).unwrap();
+        queue.push("s1", InjectionPayload::user_prompt("first"), None);
+        queue.push("s1", InjectionPayload::warning(format!("{}\nmore", "x".repeat(80))), None);
+        queue.push("s2", InjectionPayload::user_prompt("other"), None);
+        queue.save_to(&path).unwrap();
+
+        let mut queue = InjectionQueue::load_from(&path).unwrap();
+        let pending = queue.pending("s1");
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].position, 1);
+        assert_eq!(pending[0].preview, "first");
+        assert_eq!(pending[1].payload_type, PayloadType::Warning);
+        assert_eq!(pending[1].preview, format!("{}…", "x".repeat(60)));
+
+        assert_eq!(queue.pop("s1").unwrap().content, "first");
+        assert_eq!(queue.clear("s1"), 1);
+        assert!(queue.pending("s1").is_empty());
+        assert!(queue.pop("s1").is_none());
+        assert_eq!(queue.pending("s2").len(), 1);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_expired_payloads_are_dropped() {
+        let mut queue = InjectionQueue::default();
+        queue.push("s1", InjectionPayload::user_prompt("stale"), Some(std::time::Duration::ZERO));
+        queue.push("s1", InjectionPayload::user_prompt("fresh"), Some(std::time::Duration::from_secs(600)));
+        queue.push("s2", InjectionPayload::user_prompt("stale"), Some(std::time::Duration::ZERO));
+
+        let pending = queue.pending("s1");
+        assert_eq!(pending[1].expires_at, Some(pending[1].queued_at + 600));
+
+        assert_eq!(queue.pop("s1").unwrap().content, "fresh");
+        assert!(queue.pop("s2").is_none());
+        assert!(queue.pending("s2").is_empty());
+    }
+
+    #[test]
+    fn test_peek_leaves_payload_until_removed() {
+        let mut queue = InjectionQueue::default();
+        queue.push("s1", InjectionPayload::user_prompt("stale"), Some(std::time::Duration::ZERO));
+        queue.push("s1", InjectionPayload::user_prompt("next"), None);
+
+        let next = queue.peek("s1").unwrap();
+        assert_eq!(next.payload.content, "next");
+        assert_eq!(queue.pending("s1").len(), 1);
+
+        // Cleared while it was being delivered: removing it is then a no-op
+        queue.clear("s1");
+        queue.push("s1", InjectionPayload::user_prompt("later"), None);
+        queue.remove("s1", &next);
+        assert_eq!(queue.pending("s1")[0].preview, "later");
+
+        let later = queue.peek("s1").unwrap();
+        queue.remove("s1", &later);
+        assert!(queue.peek("s1").is_none());
+    }
+}
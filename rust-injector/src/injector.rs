@@ -1,18 +1,45 @@
 use anyhow::{Context, Result};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::process::Stdio;
-use std::sync::Arc;
-use tokio::io::AsyncWriteExt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
 use tokio::process::{Child, Command};
-use tokio::sync::Mutex;
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tokio_stream::Stream;
 
+use crate::error::InjectorError;
+use crate::injection_queue::{InjectionQueue, PayloadSummary};
 use crate::payload::InjectionPayload;
-use crate::session::ClaudeSession;
+use crate::session::{ClaudeSession, SessionDetector};
+
+/// How long to wait for a session to start answering a prompt
+const RESPONSE_START_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+
+/// Quiet time after which [`ClaudeProcessManager::inject_and_wait`] considers a response complete
+pub const RESPONSE_IDLE_WINDOW: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// How long to wait for a spawned Claude process to create its conversation file
+const NEW_SESSION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// How often the liveness monitor checks managed processes for exit
+const LIVENESS_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+type ProcessMap = Mutex<HashMap<String, ProcessHandle>>;
 
 /// Manages active Claude processes with stdin pipes for injection
 pub struct ClaudeProcessManager {
     /// Active processes: session_id -> ProcessHandle
-    processes: Arc<Mutex<HashMap<String, ProcessHandle>>>,
+    processes: Arc<ProcessMap>,
+    /// Set once the liveness monitor task has been started
+    monitoring: AtomicBool,
+    /// Most sessions [`ClaudeProcessManager::broadcast`] may reach, if limited
+    broadcast_limit: Option<usize>,
+    /// Injection latency per session, kept after the session ends
+    metrics: Mutex<HashMap<String, InjectionMetrics>>,
 }
 
 /// Handle to a running Claude process
@@ -20,80 +47,320 @@ pub struct ProcessHandle {
     pub session: ClaudeSession,
     pub child: Child,
     pub started_at: chrono::DateTime<chrono::Utc>,
+    /// Prompt/response pairs from waited-on injections, oldest first
+    pub history: Vec<InjectionRecord>,
+    /// Tasks draining the process's stderr and, once taken, its stdout
+    pub readers: Vec<JoinHandle<()>>,
+}
+
+/// An injected prompt paired with the response it produced
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InjectionRecord {
+    pub injected_at: chrono::DateTime<chrono::Utc>,
+    pub prompt: String,
+    pub response: String,
+    pub response_at: chrono::DateTime<chrono::Utc>,
+    /// Correlation id of the injected payload, if it had one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+}
+
+/// What a single injection wrote and how long it took, from call to flush
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InjectStats {
+    pub bytes: usize,
+    pub duration: std::time::Duration,
+}
+
+impl InjectStats {
+    /// Stats for `bytes` written by an injection that started at `started`
+    pub fn since(started: std::time::Instant, bytes: usize) -> Self {
+        Self {
+            bytes,
+            duration: started.elapsed(),
+        }
+    }
+
+    /// [`WorkerEvent::Injected`](crate::events::WorkerEvent::Injected) for this injection into `name`
+    pub fn event(&self, name: &str, correlation_id: Option<&str>) -> crate::events::WorkerEvent {
+        crate::events::WorkerEvent::Injected {
+            name: name.to_string(),
+            correlation_id: correlation_id.map(str::to_string),
+            bytes: self.bytes,
+            duration_ms: u64::try_from(self.duration.as_millis()).unwrap_or(u64::MAX),
+        }
+    }
+}
+
+/// Injection totals for one session, see [`ClaudeProcessManager::injection_metrics`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InjectionMetrics {
+    pub injections: u64,
+    pub total_bytes: u64,
+    pub total_duration: std::time::Duration,
+}
+
+impl InjectionMetrics {
+    /// Add one injection to the totals
+    pub fn record(&mut self, stats: &InjectStats) {
+        self.injections += 1;
+        self.total_bytes += stats.bytes as u64;
+        self.total_duration += stats.duration;
+    }
+
+    /// Mean time per injection
+    pub fn average_duration(&self) -> std::time::Duration {
+        match self.injections {
+            0 => std::time::Duration::ZERO,
+            n => self.total_duration.div_f64(n as f64),
+        }
+    }
+
+    /// Mean bytes per injection
+    pub fn average_bytes(&self) -> u64 {
+        self.total_bytes.checked_div(self.injections).unwrap_or(0)
+    }
+}
+
+/// Sessions selected by `claude-inject stop`, for [`ClaudeProcessManager::stop_matching`]
+///
+/// A session matches when it passes every filter that is set.
+#[derive(Debug, Clone, Default)]
+pub struct SessionFilter {
+    /// Only sessions whose project path is at or below this directory
+    pub project_path_prefix: Option<std::path::PathBuf>,
+    /// Only sessions created longer ago than this
+    pub older_than: Option<std::time::Duration>,
+}
+
+impl SessionFilter {
+    pub fn matches(&self, session: &ClaudeSession) -> bool {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |now| now.as_secs());
+
+        self.project_path_prefix
+            .as_ref()
+            .is_none_or(|prefix| std::path::Path::new(&session.project_path).starts_with(prefix))
+            && self
+                .older_than
+                .is_none_or(|age| now.saturating_sub(session.created_at) > age.as_secs())
+    }
 }
 
 impl ClaudeProcessManager {
     pub fn new() -> Self {
         Self {
             processes: Arc::new(Mutex::new(HashMap::new())),
+            monitoring: AtomicBool::new(false),
+            broadcast_limit: None,
+            metrics: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Make [`ClaudeProcessManager::broadcast`] refuse to reach more than `max` sessions
+    pub fn with_broadcast_limit(mut self, max: usize) -> Self {
+        self.broadcast_limit = Some(max);
+        self
+    }
+
+    /// Start the background task that drops exited processes, once per manager
+    ///
+    /// Exited sessions disappear from [`ClaudeProcessManager::list_active_sessions`]
+    /// within [`LIVENESS_INTERVAL`], and workers linked to them are marked
+    /// `Stopped` in the registry. The task ends when the manager is dropped.
+    fn start_liveness_monitor(&self) {
+        if self.monitoring.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let processes: Weak<ProcessMap> = Arc::downgrade(&self.processes);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(LIVENESS_INTERVAL);
+            loop {
+                interval.tick().await;
+                let Some(processes) = processes.upgrade() else {
+                    break;
+                };
+
+                for session_id in Self::reap_exited(&processes).await {
+                    log::info!("Session {} has exited", session_id);
+                    Self::mark_workers_stopped(&session_id);
+                }
+            }
+        });
+    }
+
+    /// Remove processes that have exited, returning their session ids
+    async fn reap_exited(processes: &ProcessMap) -> Vec<String> {
+        let mut processes = processes.lock().await;
+        let exited: Vec<String> = processes
+            .iter_mut()
+            .filter_map(|(session_id, handle)| {
+                (!matches!(handle.child.try_wait(), Ok(None))).then(|| session_id.clone())
+            })
+            .collect();
+
+        for session_id in &exited {
+            processes.remove(session_id);
+        }
+        exited
+    }
+
+    /// Mark registry workers running an exited session as `Stopped`
+    fn mark_workers_stopped(session_id: &str) {
+        let result = crate::WorkerRegistry::load().and_then(|mut registry| registry.mark_session_stopped(session_id));
+        match result {
+            Ok(stopped) => {
+                for name in stopped {
+                    log::info!("Worker {} stopped with session {}", name, session_id);
+                }
+            }
+            Err(e) => log::warn!("Failed to update workers of exited session {}: {}", session_id, e),
         }
     }
 
     /// Start a new Claude session with stdin/stdout/stderr pipes
     ///
-    /// This spawns `claude` CLI and keeps stdin open for injection
+    /// This spawns `claude` CLI and keeps stdin open for injection. Claude starts
+    /// a new conversation, so the returned id is the one of the JSONL file that
+    /// appears in the project after the spawn; if none shows up in time the
+    /// detected session's id is kept.
     pub async fn start_session(
         &self,
         session: ClaudeSession,
         initial_prompt: Option<String>,
     ) -> Result<String> {
-        let session_id = session.session_id.clone();
-
         log::info!(
             "Starting Claude session: {} in {}",
-            session_id,
+            session.session_id,
             session.project_path
         );
 
+        let detector = SessionDetector::new()?;
+        let existing: HashSet<String> = detector
+            .get_project_sessions(&session.project_id)
+            .map(|sessions| sessions.into_iter().map(|s| s.session_id).collect())
+            .unwrap_or_default();
+
+        let project_id = session.project_id.clone();
+        let args: Vec<String> = initial_prompt.into_iter().collect();
+        let command = crate::claude_command(&[], &args)?;
+        let session_id = self.spawn_process(session, &command[0], &command[1..]).await?;
+
+        let deadline = tokio::time::Instant::now() + NEW_SESSION_TIMEOUT;
+        loop {
+            let sessions = detector.get_project_sessions(&project_id).unwrap_or_default();
+            if let Some(created) = Self::new_session(&existing, sessions) {
+                log::info!("Claude started conversation {}", created.session_id);
+                return self.rekey(&session_id, created).await;
+            }
+
+            if !self.is_session_active(&session_id).await {
+                anyhow::bail!("Claude exited before starting a conversation in {}", project_id);
+            }
+
+            if tokio::time::Instant::now() >= deadline {
+                log::warn!(
+                    "No new conversation file appeared for session {}; keeping its id",
+                    session_id
+                );
+                return Ok(session_id);
+            }
+
+            tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+        }
+    }
+
+    /// The newest session that was not in `existing`
+    fn new_session(existing: &HashSet<String>, sessions: Vec<ClaudeSession>) -> Option<ClaudeSession> {
+        sessions
+            .into_iter()
+            .filter(|s| !existing.contains(&s.session_id))
+            .max_by_key(|s| s.created_at)
+    }
+
+    /// Track a running process under the id of the conversation it created
+    async fn rekey(&self, old_id: &str, session: ClaudeSession) -> Result<String> {
+        let mut processes = self.processes.lock().await;
+        let mut handle = processes
+            .remove(old_id)
+            .context(format!("Session {} not found in active processes", old_id))?;
+
+        let session_id = session.session_id.clone();
+        handle.session = session;
+        processes.insert(session_id.clone(), handle);
+
+        Ok(session_id)
+    }
+
+    /// Spawn `program` for a session with piped stdio and track it
+    pub(crate) async fn spawn_process(&self, session: ClaudeSession, program: &str, args: &[String]) -> Result<String> {
+        let session_id = session.session_id.clone();
+
         // Build command
-        let mut cmd = Command::new("claude");
+        let mut cmd = Command::new(program);
         cmd.current_dir(&session.project_path)
+            .args(args)
             .stdin(Stdio::piped()) // CRITICAL: Keep stdin open for injection!
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
-        // Add initial prompt if provided
-        if let Some(prompt) = initial_prompt {
-            cmd.arg(prompt);
-        }
-
         // Spawn process
-        let child = cmd
+        let mut child = cmd
             .spawn()
-            .context("Failed to spawn claude process")?;
+            .context(format!("Failed to spawn {} process", program))?;
 
         log::info!("Spawned Claude process with PID: {:?}", child.id());
 
+        // Nobody reads stderr, so drain it before a full pipe blocks Claude
+        let mut readers = Vec::new();
+        if let Some(stderr) = child.stderr.take() {
+            let id = session_id.clone();
+            readers.push(tokio::spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    log::debug!("[{} stderr] {}", id, line);
+                }
+            }));
+        }
+
         // Store process handle
         let handle = ProcessHandle {
             session: session.clone(),
             child,
             started_at: chrono::Utc::now(),
+            history: Vec::new(),
+            readers,
         };
 
         {
             let mut processes = self.processes.lock().await;
             processes.insert(session_id.clone(), handle);
         }
+        self.start_liveness_monitor();
 
         Ok(session_id)
     }
 
     /// Inject payload into a running session via stdin
     ///
-    /// This is the KEY function that enables automatic injection!
-    pub async fn inject(&self, session_id: &str, payload: InjectionPayload) -> Result<()> {
+    /// This is the KEY function that enables automatic injection! The returned
+    /// stats are also added to [`ClaudeProcessManager::injection_metrics`].
+    pub async fn inject(&self, session_id: &str, payload: InjectionPayload) -> Result<InjectStats> {
+        let started = std::time::Instant::now();
         log::info!(
-            "Injecting payload into session {}: {:?}",
+            "Injecting payload into session {}: {:?} (correlation id: {})",
             session_id,
-            payload.payload_type
+            payload.payload_type,
+            payload.correlation_id().unwrap_or("none")
         );
 
         let mut processes = self.processes.lock().await;
 
         let handle = processes
             .get_mut(session_id)
-            .context(format!("Session {} not found in active processes", session_id))?;
+            .ok_or_else(|| InjectorError::SessionNotFound(session_id.to_string()))?;
 
         // Get stdin handle
         let stdin = handle
@@ -120,19 +387,292 @@ impl ClaudeProcessManager {
 
         // Flush to ensure immediate delivery
         stdin.flush().await.context("Failed to flush stdin")?;
+        drop(processes);
 
-        log::info!("Successfully injected payload into session {}", session_id);
+        let stats = InjectStats::since(started, message.len() + 1);
+        self.metrics
+            .lock()
+            .await
+            .entry(session_id.to_string())
+            .or_default()
+            .record(&stats);
+        crate::events::emit(stats.event(session_id, payload.correlation_id()));
 
-        Ok(())
+        log::info!(
+            "Successfully injected payload into session {} ({} bytes in {:?})",
+            session_id,
+            stats.bytes,
+            stats.duration
+        );
+
+        Ok(stats)
+    }
+
+    /// Injection count and latency totals for a session, if anything was injected
+    pub async fn injection_metrics(&self, session_id: &str) -> Option<InjectionMetrics> {
+        self.metrics.lock().await.get(session_id).cloned()
+    }
+
+    /// Collect a session's stdout until it has been quiet for `idle_timeout`
+    ///
+    /// Waits up to two minutes for output to start, then returns everything
+    /// received before the output settles (or the process closes stdout).
+    /// Output that never settles is cut off after `max_total` with
+    /// [`InjectorError::Timeout`] carrying what was collected.
+    pub async fn wait_for_response(
+        &self,
+        session_id: &str,
+        idle_timeout: std::time::Duration,
+        max_total: std::time::Duration,
+    ) -> Result<String> {
+        self.read_response(session_id, idle_timeout, max_total, false).await
+    }
+
+    /// Inject a payload and return the output it produces
+    ///
+    /// Output is collected until it has been quiet for [`RESPONSE_IDLE_WINDOW`],
+    /// so scripted sequences need no fixed sleeps between prompts. Fails if the
+    /// process exits before answering, and with [`InjectorError::Timeout`]
+    /// carrying the output so far when `timeout` passes first.
+    pub async fn inject_and_wait(
+        &self,
+        session_id: &str,
+        payload: InjectionPayload,
+        timeout: std::time::Duration,
+    ) -> Result<String> {
+        self.inject_and_record(session_id, payload, RESPONSE_IDLE_WINDOW, timeout, true, || Ok(()))
+            .await
+    }
+
+    /// [`ClaudeProcessManager::wait_for_response`], optionally treating a closed stdout as an error
+    async fn read_response(
+        &self,
+        session_id: &str,
+        idle_timeout: std::time::Duration,
+        max_total: std::time::Duration,
+        exit_is_error: bool,
+    ) -> Result<String> {
+        // Take stdout out of the handle so the process map is not locked while reading
+        let mut stdout = {
+            let mut processes = self.processes.lock().await;
+            let handle = processes
+                .get_mut(session_id)
+                .context(format!("Session {} not found in active processes", session_id))?;
+            handle.child.stdout.take().context("Session stdout not available")?
+        };
+
+        let deadline = tokio::time::Instant::now() + max_total;
+        let mut response = Vec::new();
+        let mut buf = [0u8; 4096];
+        let result = loop {
+            let wait = if response.is_empty() { RESPONSE_START_TIMEOUT } else { idle_timeout };
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+
+            match tokio::time::timeout(wait.min(remaining), stdout.read(&mut buf)).await {
+                Ok(Ok(0)) if exit_is_error => {
+                    break Err(anyhow::anyhow!(
+                        "Session {} exited while responding ({} bytes received)",
+                        session_id,
+                        response.len()
+                    ))
+                }
+                Ok(Ok(0)) => break Ok(()),
+                Ok(Ok(n)) => response.extend_from_slice(&buf[..n]),
+                Ok(Err(e)) => break Err(anyhow::Error::new(e).context("Failed to read session stdout")),
+                Err(_) if wait > remaining => {
+                    break Err(anyhow::Error::new(InjectorError::Timeout {
+                        limit: max_total,
+                        partial_output: String::from_utf8_lossy(&response).into_owned(),
+                    }))
+                }
+                Err(_) if response.is_empty() => {
+                    break Err(anyhow::anyhow!("Session {} produced no output within {:?}", session_id, wait))
+                }
+                Err(_) => break Ok(()),
+            }
+        };
+
+        {
+            let mut processes = self.processes.lock().await;
+            if let Some(handle) = processes.get_mut(session_id) {
+                handle.child.stdout = Some(stdout);
+            }
+        }
+
+        result?;
+        Ok(String::from_utf8_lossy(&response).into_owned())
+    }
+
+    /// Stream a session's stdout line by line
+    ///
+    /// A background task reads stdout for the rest of the session, so output
+    /// no longer piles up in the pipe; lines are buffered until the stream is
+    /// read and discarded once it is dropped. Stdout can only be taken once,
+    /// after which [`ClaudeProcessManager::wait_for_response`] fails for the session.
+    pub async fn take_output_stream(&self, session_id: &str) -> Result<impl Stream<Item = String>> {
+        let mut processes = self.processes.lock().await;
+        let handle = processes
+            .get_mut(session_id)
+            .context(format!("Session {} not found in active processes", session_id))?;
+        let stdout = handle.child.stdout.take().context("Session stdout not available")?;
+
+        let (sender, receiver) = mpsc::unbounded_channel();
+        handle.readers.push(tokio::spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                // Keep draining after the receiver is dropped
+                let _ = sender.send(line);
+            }
+        }));
+
+        Ok(UnboundedReceiverStream::new(receiver))
+    }
+
+    /// Run a scripted conversation: inject each prompt after the previous response settles
+    ///
+    /// Returns the responses in prompt order. The whole conversation is bounded
+    /// by `max_total`; see [`ClaudeProcessManager::wait_for_response`].
+    pub async fn converse(
+        &self,
+        session_id: &str,
+        prompts: Vec<InjectionPayload>,
+        idle_timeout: std::time::Duration,
+        max_total: std::time::Duration,
+    ) -> Result<Vec<String>> {
+        let deadline = tokio::time::Instant::now() + max_total;
+        let mut responses = Vec::with_capacity(prompts.len());
+
+        for (turn, prompt) in prompts.into_iter().enumerate() {
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            let response = self
+                .inject_and_record(session_id, prompt, idle_timeout, remaining, false, || Ok(()))
+                .await
+                .context(format!("No response to prompt {} in session {}", turn + 1, session_id))?;
+            responses.push(response);
+        }
+
+        Ok(responses)
+    }
+
+    /// Inject a payload, wait for the response and add the pair to the session's history
+    ///
+    /// `sent` runs once the payload has been written, before the response wait.
+    async fn inject_and_record(
+        &self,
+        session_id: &str,
+        payload: InjectionPayload,
+        idle_timeout: std::time::Duration,
+        max_total: std::time::Duration,
+        exit_is_error: bool,
+        sent: impl FnOnce() -> Result<()>,
+    ) -> Result<String> {
+        let prompt = payload.to_injection_string();
+        let correlation_id = payload.correlation_id().map(str::to_string);
+        let injected_at = chrono::Utc::now();
+
+        self.inject(session_id, payload).await?;
+        sent()?;
+        let response = self
+            .read_response(session_id, idle_timeout, max_total, exit_is_error)
+            .await?;
+
+        let mut processes = self.processes.lock().await;
+        if let Some(handle) = processes.get_mut(session_id) {
+            handle.history.push(InjectionRecord {
+                injected_at,
+                prompt,
+                response: response.clone(),
+                response_at: chrono::Utc::now(),
+                correlation_id,
+            });
+        }
+
+        Ok(response)
+    }
+
+    /// Prompt/response log of a session's waited-on injections, oldest first
+    pub async fn injection_history(&self, session_id: &str) -> Result<Vec<InjectionRecord>> {
+        let processes = self.processes.lock().await;
+        let handle = processes
+            .get(session_id)
+            .context(format!("Session {} not found in active processes", session_id))?;
+
+        Ok(handle.history.clone())
+    }
+
+    /// Queue a payload for a session, to be sent by [`ClaudeProcessManager::deliver_pending`]
+    ///
+    /// With a `ttl`, the payload is dropped instead of sent if it is still
+    /// queued after that long.
+    pub fn enqueue(&self, session_id: &str, payload: InjectionPayload, ttl: Option<std::time::Duration>) -> Result<()> {
+        InjectionQueue::update(|queue| queue.push(session_id, payload, ttl))
+    }
+
+    /// Payloads queued for a session that have not reached Claude yet
+    pub fn pending(&self, session_id: &str) -> Vec<PayloadSummary> {
+        match InjectionQueue::load() {
+            Ok(queue) => queue.pending(session_id),
+            Err(e) => {
+                log::warn!("Failed to read injection queue: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Cancel a session's undelivered payloads, returning how many were dropped
+    pub fn clear_pending(&self, session_id: &str) -> Result<usize> {
+        let cleared = InjectionQueue::update(|queue| queue.clear(session_id))?;
+
+        if cleared > 0 {
+            log::info!("Cleared {} queued payload(s) for session {}", cleared, session_id);
+        }
+        Ok(cleared)
+    }
+
+    /// Deliver a session's queued payloads one at a time, each after the previous response
+    ///
+    /// The queue is re-read before every payload, so [`ClaudeProcessManager::clear_pending`]
+    /// (from this or another process) cancels whatever has not been sent yet.
+    /// Payloads past their TTL are dropped and logged rather than delivered.
+    /// A payload stays queued until it has been written to Claude, so an
+    /// interrupted delivery sends it again next time. Each response wait is
+    /// bounded by `idle_timeout` and `max_total` as in
+    /// [`ClaudeProcessManager::wait_for_response`]. Returns how many were delivered.
+    pub async fn deliver_pending(
+        &self,
+        session_id: &str,
+        idle_timeout: std::time::Duration,
+        max_total: std::time::Duration,
+    ) -> Result<usize> {
+        let mut delivered = 0;
+
+        loop {
+            let Some(queued) = InjectionQueue::update(|queue| queue.peek(session_id))? else {
+                return Ok(delivered);
+            };
+
+            let sent = || InjectionQueue::update(|queue| queue.remove(session_id, &queued));
+            self.inject_and_record(session_id, queued.payload.clone(), idle_timeout, max_total, false, sent)
+                .await
+                .context(format!("Failed to deliver queued payload to session {}", session_id))?;
+            delivered += 1;
+        }
     }
 
     /// Inject into ALL active sessions
+    ///
+    /// With [`ClaudeProcessManager::with_broadcast_limit`], nothing is sent when
+    /// there are more active sessions than the limit.
     pub async fn broadcast(&self, payload: InjectionPayload) -> Result<Vec<String>> {
         let session_ids: Vec<String> = {
             let processes = self.processes.lock().await;
             processes.keys().cloned().collect()
         };
 
+        if let Some(max) = self.broadcast_limit {
+            crate::safety::check_broadcast_targets(&session_ids, max)?;
+        }
+
         let mut injected = Vec::new();
 
         for session_id in session_ids {
@@ -189,6 +729,9 @@ impl ClaudeProcessManager {
 
         if let Some(mut handle) = processes.remove(session_id) {
             log::info!("Stopping session {}", session_id);
+            for reader in &handle.readers {
+                reader.abort();
+            }
             handle.child.start_kill().context("Failed to kill process")?;
             handle.child.wait().await.context("Failed to wait for process")?;
         }
@@ -212,6 +755,31 @@ impl ClaudeProcessManager {
         Ok(())
     }
 
+    /// Stop every active session whose [`ClaudeSession`] matches `predicate`
+    ///
+    /// Returns the ids of the sessions that were stopped; sessions that fail to
+    /// stop are logged and left out.
+    pub async fn stop_matching(&self, predicate: impl Fn(&ClaudeSession) -> bool) -> Result<Vec<String>> {
+        let session_ids: Vec<String> = {
+            let processes = self.processes.lock().await;
+            processes
+                .iter()
+                .filter(|(_, handle)| predicate(&handle.session))
+                .map(|(session_id, _)| session_id.clone())
+                .collect()
+        };
+
+        let mut stopped = Vec::new();
+        for session_id in session_ids {
+            match self.stop_session(&session_id).await {
+                Ok(()) => stopped.push(session_id),
+                Err(e) => log::warn!("Failed to stop session {}: {}", session_id, e),
+            }
+        }
+
+        Ok(stopped)
+    }
+
     /// Cleanup finished processes
     pub async fn cleanup_finished(&self) -> Vec<String> {
         let session_ids: Vec<String> = {
@@ -241,7 +809,193 @@ impl Default for ClaudeProcessManager {
 mod tests {
     use super::*;
     use crate::payload::PayloadType;
-    use crate::session::SessionDetector;
+    use crate::session::{test_session, SessionDetector};
+
+    #[test]
+    fn test_average_duration_past_u32_injections() {
+        let metrics = InjectionMetrics {
+            injections: u64::from(u32::MAX) + 2,
+            total_bytes: 0,
+            total_duration: std::time::Duration::from_secs(10),
+        };
+        assert!(metrics.average_duration() < std::time::Duration::from_micros(1));
+        assert_eq!(InjectionMetrics::default().average_duration(), std::time::Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_converse_collects_each_response() {
+        let session = test_session("converse-test");
+
+        // `cat` answers every prompt by echoing it back
+        let manager = ClaudeProcessManager::new();
+        let session_id = manager.spawn_process(session, "cat", &[]).await.unwrap();
+        let mut events = crate::events::subscribe();
+
+        let responses = manager
+            .converse(
+                &session_id,
+                vec![InjectionPayload::user_prompt("first"), InjectionPayload::user_prompt("second")],
+                std::time::Duration::from_millis(200),
+                std::time::Duration::from_secs(10),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(responses, vec!["first\n".to_string(), "second\n".to_string()]);
+
+        let metrics = manager.injection_metrics(&session_id).await.unwrap();
+        assert_eq!(metrics.injections, 2);
+        assert_eq!(metrics.total_bytes, 13);
+        assert_eq!(metrics.average_bytes(), 6);
+        assert!(metrics.average_duration() <= metrics.total_duration);
+        assert!(manager.injection_metrics("unknown").await.is_none());
+
+        let injected: Vec<usize> = std::iter::from_fn(|| events.try_recv().ok())
+            .filter_map(|event| match event {
+                crate::events::WorkerEvent::Injected { name, bytes, .. } if name == session_id => Some(bytes),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(injected, [6, 7]);
+
+        let history = manager.injection_history(&session_id).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].prompt, "first");
+        assert_eq!(history[1].response, "second\n");
+        assert!(history[0].response_at >= history[0].injected_at);
+
+        manager.stop_session(&session_id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_inject_and_wait() {
+        let session = |id: &str| test_session(id);
+        let manager = ClaudeProcessManager::new();
+        let timeout = std::time::Duration::from_secs(10);
+
+        let echo = manager.spawn_process(session("wait-test"), "cat", &[]).await.unwrap();
+        let response = manager
+            .inject_and_wait(&echo, InjectionPayload::user_prompt("hello"), timeout)
+            .await
+            .unwrap();
+        assert_eq!(response, "hello\n");
+        manager.stop_session(&echo).await.unwrap();
+
+        // `head -n 1` answers once and exits, closing stdout mid-wait
+        let once = manager
+            .spawn_process(session("wait-exit-test"), "head", &["-n".to_string(), "1".to_string()])
+            .await
+            .unwrap();
+        let err = manager
+            .inject_and_wait(&once, InjectionPayload::user_prompt("bye"), timeout)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("exited while responding"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_output_stream_round_trip() {
+        use tokio_stream::StreamExt;
+
+        let session = test_session("stream-test");
+
+        // `cat` echoes each injected line back on stdout
+        let manager = ClaudeProcessManager::new();
+        let session_id = manager.spawn_process(session, "cat", &[]).await.unwrap();
+        let mut output = manager.take_output_stream(&session_id).await.unwrap();
+        assert!(manager.take_output_stream(&session_id).await.is_err());
+
+        manager.inject(&session_id, InjectionPayload::user_prompt("ping")).await.unwrap();
+        let line = tokio::time::timeout(std::time::Duration::from_secs(5), output.next()).await.unwrap();
+        assert_eq!(line.as_deref(), Some("ping"));
+
+        manager.stop_session(&session_id).await.unwrap();
+        let end = tokio::time::timeout(std::time::Duration::from_secs(5), output.next()).await.unwrap();
+        assert_eq!(end, None);
+    }
+
+    #[tokio::test]
+    async fn test_new_session_is_tracked_under_its_own_id() {
+        let session = |id: &str, created_at: u64| ClaudeSession {
+            created_at,
+            ..test_session(id)
+        };
+
+        let existing: HashSet<String> = ["old".to_string()].into_iter().collect();
+        let found = vec![session("old", 30), session("new", 20), session("newer", 25)];
+        let created = ClaudeProcessManager::new_session(&existing, found).unwrap();
+        assert_eq!(created.session_id, "newer");
+        assert!(ClaudeProcessManager::new_session(&existing, vec![session("old", 30)]).is_none());
+
+        let manager = ClaudeProcessManager::new();
+        let spawned_id = manager.spawn_process(session("old", 30), "cat", &[]).await.unwrap();
+        let session_id = manager.rekey(&spawned_id, created).await.unwrap();
+
+        assert_eq!(session_id, "newer");
+        assert_eq!(manager.list_active_sessions().await, ["newer"]);
+
+        manager.stop_session(&session_id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stop_matching_leaves_other_sessions() {
+        let session = |id: &str, project_path: &str| ClaudeSession {
+            project_path: project_path.to_string(),
+            ..test_session(id)
+        };
+
+        let manager = ClaudeProcessManager::new();
+        for (id, path) in [("usr", "/usr"), ("usr-bin", "/usr/bin"), ("tmp", "/tmp")] {
+            manager.spawn_process(session(id, path), "cat", &[]).await.unwrap();
+        }
+
+        let filter = SessionFilter {
+            project_path_prefix: Some("/usr".into()),
+            older_than: Some(std::time::Duration::from_secs(60)),
+        };
+        let mut stopped = manager.stop_matching(|session| filter.matches(session)).await.unwrap();
+        stopped.sort();
+
+        assert_eq!(stopped, ["usr", "usr-bin"]);
+        assert_eq!(manager.list_active_sessions().await, ["tmp"]);
+
+        manager.stop_all().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_exited_session_is_dropped_without_cleanup() {
+        let session = test_session("exit-test");
+
+        let manager = ClaudeProcessManager::new();
+        let session_id = manager.spawn_process(session, "true", &[]).await.unwrap();
+
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(5);
+        while !manager.list_active_sessions().await.is_empty() {
+            assert!(tokio::time::Instant::now() < deadline, "{} still listed after exiting", session_id);
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_response_stops_endless_output() {
+        let session = test_session("endless-test");
+
+        // `yes` never goes quiet, so only the total bound can end the wait
+        let manager = ClaudeProcessManager::new();
+        let session_id = manager.spawn_process(session, "yes", &[]).await.unwrap();
+
+        let err = manager
+            .wait_for_response(&session_id, std::time::Duration::from_millis(200), std::time::Duration::from_millis(500))
+            .await
+            .unwrap_err();
+
+        match err.downcast_ref::<InjectorError>() {
+            Some(InjectorError::Timeout { partial_output, .. }) => assert!(partial_output.starts_with("y\n")),
+            other => panic!("expected timeout, got {:?}", other),
+        }
+
+        manager.stop_session(&session_id).await.unwrap();
+    }
 
     #[tokio::test]
     async fn test_start_and_inject() {
@@ -267,11 +1021,9 @@ mod tests {
             .await
             .unwrap();
 
+        // start_session returns once Claude has created the conversation
         println!("Started session: {}", session_id);
 
-        // Wait a bit for session to initialize
-        tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-
         // Inject a message
         let payload = InjectionPayload {
             payload_type: PayloadType::Context,
@@ -1,3 +1,4 @@
+pub mod error;
 pub mod session;
 pub mod detector;
 pub mod injector;
@@ -6,7 +7,25 @@ pub mod session_mapper;
 pub mod pty_injector;
 pub mod tmux_spawner;
 pub mod worker_registry;
+pub mod worker_history;
+pub mod transcript;
+pub mod config;
+pub mod events;
+pub mod supervisor;
+pub mod detached;
+pub mod duration;
+pub mod logging;
+pub mod safety;
+pub mod ci;
+pub mod state_file;
+pub mod pty_manager;
+pub mod worker_manager;
+pub mod injection_queue;
+pub mod snapshot;
+pub mod daemon;
+pub mod remote;
 
+pub use error::*;
 pub use session::*;
 pub use detector::*;
 pub use injector::*;
@@ -15,3 +34,20 @@ pub use session_mapper::*;
 pub use pty_injector::*;
 pub use tmux_spawner::*;
 pub use worker_registry::*;
+pub use worker_history::*;
+pub use transcript::*;
+pub use config::*;
+pub use events::*;
+pub use supervisor::*;
+pub use detached::*;
+pub use duration::*;
+pub use logging::*;
+pub use safety::*;
+pub use ci::*;
+pub use state_file::*;
+pub use pty_manager::*;
+pub use worker_manager::*;
+pub use injection_queue::*;
+pub use snapshot::*;
+pub use daemon::*;
+pub use remote::*;
@@ -0,0 +1,53 @@
+use log::LevelFilter;
+use std::io::Write;
+
+/// Log level selected by the `--quiet` / `--verbose` command line flags
+pub fn level_from_flags(quiet: bool, verbose: u8) -> LevelFilter {
+    if quiet {
+        return LevelFilter::Warn;
+    }
+
+    match verbose {
+        0 => LevelFilter::Info,
+        1 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+/// Initialize logging for the command line tools
+///
+/// Status messages go to stderr through the `log` crate so stdout only carries
+/// command results. `RUST_LOG` applies unless `--quiet` or `--verbose` is given.
+pub fn init_cli_logging(quiet: bool, verbose: u8) {
+    let mut builder = env_logger::Builder::new();
+    builder.filter_level(level_from_flags(quiet, verbose));
+
+    if !quiet && verbose == 0 {
+        if let Ok(filters) = std::env::var("RUST_LOG") {
+            builder.parse_filters(&filters);
+        }
+    }
+
+    builder.format(|buf, record| {
+        if record.level() <= log::Level::Info {
+            writeln!(buf, "{}", record.args())
+        } else {
+            writeln!(buf, "[{} {}] {}", record.level(), record.target(), record.args())
+        }
+    });
+
+    let _ = builder.try_init();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_from_flags() {
+        assert_eq!(level_from_flags(false, 0), LevelFilter::Info);
+        assert_eq!(level_from_flags(true, 0), LevelFilter::Warn);
+        assert_eq!(level_from_flags(false, 1), LevelFilter::Debug);
+        assert_eq!(level_from_flags(false, 3), LevelFilter::Trace);
+    }
+}
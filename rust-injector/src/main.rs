@@ -1,6 +1,5 @@
 use anyhow::Result;
 use claude_injector::*;
-use std::collections::HashMap;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -105,47 +104,36 @@ async fn main() -> Result<()> {
 
         println!("✓ Session started: {}", session_id);
 
-        // Wait for session to initialize
-        tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+        // Let the reply to the initial prompt settle before injecting
+        manager
+            .wait_for_response(&session_id, RESPONSE_IDLE_WINDOW, tokio::time::Duration::from_secs(300))
+            .await?;
 
         // Inject a series of payloads as USER INPUT (simulating user typing)
         let injection_sequence = vec![
-            (
-                3,
-                InjectionPayload::user_prompt(
-                    "Can you help me build an authentication system with JWT tokens?",
-                ),
+            InjectionPayload::user_prompt(
+                "Can you help me build an authentication system with JWT tokens?",
             ),
-            (
-                5,
-                InjectionPayload::user_prompt(
-                    "Great! Can you start by creating the user model with fields for email, password_hash, and timestamps?",
-                ),
+            InjectionPayload::user_prompt(
+                "Great! Can you start by creating the user model with fields for email, password_hash, and timestamps?",
             ),
-            (
-                5,
-                InjectionPayload::user_prompt(
-                    "Now let's add the JWT token generation function. It should accept user_id and return a signed token.",
-                ),
+            InjectionPayload::user_prompt(
+                "Now let's add the JWT token generation function. It should accept user_id and return a signed token.",
             ),
-            (
-                5,
-                InjectionPayload::user_prompt(
-                    "Perfect! Can you also add token validation middleware?",
-                ),
+            InjectionPayload::user_prompt(
+                "Perfect! Can you also add token validation middleware?",
             ),
-            (
-                5,
-                InjectionPayload::user_prompt(
-                    "Excellent work! Please write unit tests for the JWT functions.",
-                ),
+            InjectionPayload::user_prompt(
+                "Excellent work! Please write unit tests for the JWT functions.",
             ),
         ];
 
-        for (delay, payload) in injection_sequence {
-            tokio::time::sleep(tokio::time::Duration::from_secs(delay)).await;
+        for payload in injection_sequence {
             println!("\n⚡ Injecting: {:?}", payload.payload_type);
-            manager.inject(&session_id, payload).await?;
+            let response = manager
+                .inject_and_wait(&session_id, payload, tokio::time::Duration::from_secs(300))
+                .await?;
+            println!("{}", response.trim_end());
         }
 
         println!("\n\n✅ Injection sequence complete!");
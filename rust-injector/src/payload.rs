@@ -1,8 +1,20 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+/// Wrapper applied to every injection string, set by `--prefix`/`--suffix`
+static INJECTION_WRAPPER: OnceLock<Mutex<InjectionWrapper>> = OnceLock::new();
+
+/// Whether injection strings end with a hidden correlation id marker
+static CORRELATION_MARKERS: AtomicBool = AtomicBool::new(false);
+
+/// Metadata key holding a payload's correlation id
+pub const CORRELATION_ID_KEY: &str = "correlation_id";
 
 /// Type of payload to inject
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum PayloadType {
     /// Regular context/information
     Context,
@@ -16,10 +28,29 @@ pub enum PayloadType {
     Progress,
     /// User prompt (simulated user input)
     UserPrompt,
+    /// Request for Claude to call an MCP tool
+    McpCall,
+    /// One prompt made of labelled sections, as `(label, body)` pairs
+    MultiPart(Vec<(String, String)>),
+}
+
+impl std::fmt::Display for PayloadType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PayloadType::Context => write!(f, "context"),
+            PayloadType::Warning => write!(f, "warning"),
+            PayloadType::Block => write!(f, "block"),
+            PayloadType::Completion => write!(f, "completion"),
+            PayloadType::Progress => write!(f, "progress"),
+            PayloadType::UserPrompt => write!(f, "user prompt"),
+            PayloadType::McpCall => write!(f, "mcp call"),
+            PayloadType::MultiPart(_) => write!(f, "multi-part"),
+        }
+    }
 }
 
 /// Payload to inject into Claude session
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct InjectionPayload {
     pub payload_type: PayloadType,
     pub content: String,
@@ -87,6 +118,37 @@ impl InjectionPayload {
         }
     }
 
+    /// Create a prompt made of labelled sections, e.g. a header, a code block and a question
+    ///
+    /// Sections are rendered in order under their labels; `content` holds the
+    /// same rendering so previews and logs show the whole prompt.
+    pub fn multi_part<L: Into<String>, B: Into<String>>(sections: impl IntoIterator<Item = (L, B)>) -> Self {
+        let sections: Vec<(String, String)> = sections
+            .into_iter()
+            .map(|(label, body)| (label.into(), body.into()))
+            .collect();
+
+        Self {
+            content: render_sections(&sections),
+            payload_type: PayloadType::MultiPart(sections),
+            metadata: None,
+        }
+    }
+
+    /// Create a payload asking Claude to call an MCP tool
+    ///
+    /// `tool` is the full tool name (`mcp__<server>__<tool>`); `args` is a JSON
+    /// object, a single string argument, or null for none. See [`mcp_invocation`].
+    pub fn mcp_call(tool: &str, args: serde_json::Value) -> anyhow::Result<Self> {
+        Ok(Self {
+            payload_type: PayloadType::McpCall,
+            content: mcp_invocation(tool, &args)?,
+            metadata: None,
+        }
+        .with_metadata("mcp_tool", tool)
+        .with_metadata("mcp_args", args))
+    }
+
     /// Add metadata to payload
     pub fn with_metadata(mut self, key: impl Into<String>, value: impl Serialize) -> Self {
         let metadata = self.metadata.get_or_insert_with(HashMap::new);
@@ -97,8 +159,31 @@ impl InjectionPayload {
         self
     }
 
+    /// Tag the payload with an id that ties it to a request across systems
+    pub fn with_correlation_id(self, id: impl Into<String>) -> Self {
+        self.with_metadata(CORRELATION_ID_KEY, id.into())
+    }
+
+    /// Correlation id set by [`InjectionPayload::with_correlation_id`]
+    pub fn correlation_id(&self) -> Option<&str> {
+        self.metadata.as_ref()?.get(CORRELATION_ID_KEY)?.as_str()
+    }
+
     /// Convert payload to string suitable for injection
+    ///
+    /// The text is wrapped with the prefix and suffix set by [`set_injection_wrapper`].
+    /// With [`set_correlation_markers`] on, a correlation id is appended as an
+    /// HTML comment (`<!-- correlation-id: ... -->`).
     pub fn to_injection_string(&self) -> String {
+        let mut message = wrap_injection(&self.format_content());
+
+        if let Some(id) = self.correlation_id().filter(|_| CORRELATION_MARKERS.load(Ordering::Relaxed)) {
+            message.push_str(&format!(" <!-- correlation-id: {} -->", id));
+        }
+        message
+    }
+
+    fn format_content(&self) -> String {
         match self.payload_type {
             PayloadType::Context => format!("\n\n📋 REAL-TIME CONTEXT UPDATE:\n{}\n", self.content),
 
@@ -139,10 +224,14 @@ impl InjectionPayload {
                 )
             }
 
+            PayloadType::McpCall => self.content.clone(),
+
+            PayloadType::MultiPart(ref sections) => render_sections(sections),
+
             PayloadType::UserPrompt => {
                 // For user prompts, just send the content directly
                 // Claude will interpret this as if the user typed it
-                format!("{}", self.content)
+                self.content.clone()
             }
         }
     }
@@ -151,6 +240,257 @@ impl InjectionPayload {
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
         serde_json::to_string_pretty(self)
     }
+
+    /// Parse a payload from JSON produced by [`InjectionPayload::to_json`]
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Save the payload as `<dir>/<name>.json`, returning the file written
+    ///
+    /// [`presets_dir`] is where `inject --preset` looks. Names may hold
+    /// letters, digits, `-` and `_`; an existing preset of that name is replaced.
+    pub fn save_preset(&self, name: &str, dir: &Path) -> anyhow::Result<PathBuf> {
+        use anyhow::Context;
+
+        let path = preset_path(name, dir)?;
+        std::fs::create_dir_all(dir).context(format!("Failed to create {}", dir.display()))?;
+        std::fs::write(&path, self.to_json()?).context(format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Load a payload saved with [`InjectionPayload::save_preset`]
+    pub fn load_preset(name: &str, dir: &Path) -> anyhow::Result<Self> {
+        use anyhow::Context;
+
+        let path = preset_path(name, dir)?;
+        let json = match std::fs::read_to_string(&path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                anyhow::bail!("No preset named '{}' in {}", name, dir.display())
+            }
+            Err(e) => return Err(e).context(format!("Failed to read {}", path.display())),
+        };
+
+        Self::from_json(&json).context(format!("Invalid preset {}", path.display()))
+    }
+
+    /// Load a payload from a `.json`, `.yaml` or `.yml` file, chosen by extension
+    ///
+    /// Payloads without content are refused, since they would inject nothing.
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        use anyhow::Context;
+
+        let text = std::fs::read_to_string(path).context(format!("Failed to read {}", path.display()))?;
+        let payload: Self = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::from_json(&text).context(format!("Invalid payload file {}", path.display()))?,
+            Some("yaml" | "yml") => {
+                serde_yaml::from_str(&text).context(format!("Invalid payload file {}", path.display()))?
+            }
+            _ => anyhow::bail!("Unsupported payload file {}: use .json, .yaml or .yml", path.display()),
+        };
+
+        if payload.content.trim().is_empty() {
+            anyhow::bail!("Payload file {} has no content to inject", path.display());
+        }
+        Ok(payload)
+    }
+}
+
+/// Directory of user presets: `~/.config/claude-injector/presets`
+pub fn presets_dir() -> PathBuf {
+    crate::InjectorConfig::config_dir().join("presets")
+}
+
+/// File of a named preset, refusing names that would leave `dir`
+fn preset_path(name: &str, dir: &Path) -> anyhow::Result<PathBuf> {
+    let valid = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        anyhow::bail!("Invalid preset name '{}': use letters, digits, '-' and '_'", name);
+    }
+    Ok(dir.join(format!("{}.json", name)))
+}
+
+/// Render `(label, body)` sections as `## label` headings followed by their body
+///
+/// Sections are separated by a blank line; a section with an empty label is
+/// rendered as its body alone.
+fn render_sections(sections: &[(String, String)]) -> String {
+    sections
+        .iter()
+        .map(|(label, body)| {
+            let body = body.trim_end_matches('\n');
+            if label.trim().is_empty() {
+                body.to_string()
+            } else {
+                format!("## {}\n{}", label.trim(), body)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Render the text that makes Claude call an MCP tool, e.g. `mcp__agenthub_http__call_agent("coding-agent")`
+///
+/// Arguments are written as compact JSON; null renders as an empty argument list.
+pub fn mcp_invocation(tool: &str, args: &serde_json::Value) -> anyhow::Result<String> {
+    let valid_part = |part: &str| {
+        !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    };
+    let valid = tool
+        .strip_prefix("mcp__")
+        .and_then(|rest| rest.split_once("__"))
+        .is_some_and(|(server, name)| valid_part(server) && valid_part(name));
+    if !valid {
+        anyhow::bail!("Invalid MCP tool name '{}': expected mcp__<server>__<tool>", tool);
+    }
+
+    let args = match args {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::Object(_) | serde_json::Value::String(_) => args.to_string(),
+        other => anyhow::bail!("MCP tool arguments must be a JSON object or string, got {}", other),
+    };
+
+    Ok(format!("{}({})", tool, args))
+}
+
+/// Text placed before and after every injected message
+///
+/// Lets agents tell automated injections (e.g. `[AUTOMATION]`) from human input.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InjectionWrapper {
+    pub prefix: Option<String>,
+    pub suffix: Option<String>,
+}
+
+impl InjectionWrapper {
+    /// Wrap a message, adding a space where the message does not start or end with whitespace
+    ///
+    /// Blank messages are returned unchanged so they are still seen as empty.
+    pub fn wrap(&self, message: &str) -> String {
+        if message.trim().is_empty() {
+            return message.to_string();
+        }
+
+        let mut wrapped = String::new();
+
+        if let Some(ref prefix) = self.prefix {
+            wrapped.push_str(prefix);
+            if !message.starts_with(char::is_whitespace) {
+                wrapped.push(' ');
+            }
+        }
+
+        wrapped.push_str(message);
+
+        if let Some(ref suffix) = self.suffix {
+            if !message.ends_with(char::is_whitespace) {
+                wrapped.push(' ');
+            }
+            wrapped.push_str(suffix);
+        }
+
+        wrapped
+    }
+}
+
+/// Set the wrapper applied to injections for the rest of the process
+pub fn set_injection_wrapper(wrapper: InjectionWrapper) {
+    *injection_wrapper().lock().unwrap() = wrapper;
+}
+
+/// Wrap a raw message with the prefix and suffix set by [`set_injection_wrapper`]
+pub fn wrap_injection(message: &str) -> String {
+    injection_wrapper().lock().unwrap().wrap(message)
+}
+
+/// Set whether injections carry their correlation id as a hidden marker
+pub fn set_correlation_markers(enabled: bool) {
+    CORRELATION_MARKERS.store(enabled, Ordering::Relaxed);
+}
+
+fn injection_wrapper() -> &'static Mutex<InjectionWrapper> {
+    INJECTION_WRAPPER.get_or_init(|| Mutex::new(InjectionWrapper::default()))
+}
+
+/// Largest frame [`PayloadCodec`] will accept (16 MiB)
+pub const MAX_FRAME_LEN: usize = 16 * 1024 * 1024;
+
+/// Length-prefixed framing for sending payloads over a byte stream
+///
+/// Each frame is a 4-byte big-endian length followed by the payload as compact JSON.
+pub struct PayloadCodec;
+
+impl PayloadCodec {
+    /// Serialize a payload into a single frame
+    pub fn encode(payload: &InjectionPayload) -> std::io::Result<Vec<u8>> {
+        let json = serde_json::to_vec(payload)?;
+        if json.len() > MAX_FRAME_LEN {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Payload of {} bytes exceeds the {} byte frame limit", json.len(), MAX_FRAME_LEN),
+            ));
+        }
+
+        let mut frame = Vec::with_capacity(4 + json.len());
+        frame.extend_from_slice(&(json.len() as u32).to_be_bytes());
+        frame.extend_from_slice(&json);
+        Ok(frame)
+    }
+
+    /// Decode the first frame in `buf`
+    ///
+    /// Returns the payload and the number of bytes consumed, or `None` when `buf`
+    /// does not yet hold a complete frame.
+    pub fn decode(buf: &[u8]) -> std::io::Result<Option<(InjectionPayload, usize)>> {
+        let Some(header) = buf.get(..4) else {
+            return Ok(None);
+        };
+
+        let len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        if len > MAX_FRAME_LEN {
+            return Err(Self::oversized_frame(len));
+        }
+
+        let Some(body) = buf.get(4..4 + len) else {
+            return Ok(None);
+        };
+
+        let payload = serde_json::from_slice(body)?;
+        Ok(Some((payload, 4 + len)))
+    }
+
+    fn oversized_frame(len: usize) -> std::io::Error {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Frame of {} bytes exceeds the {} byte limit", len, MAX_FRAME_LEN),
+        )
+    }
+
+    /// Write one framed payload to a stream
+    pub fn write_to<W: std::io::Write>(writer: &mut W, payload: &InjectionPayload) -> std::io::Result<()> {
+        writer.write_all(&Self::encode(payload)?)?;
+        writer.flush()
+    }
+
+    /// Read one framed payload from a stream, or `None` at a clean end of stream
+    pub fn read_from<R: std::io::Read>(reader: &mut R) -> std::io::Result<Option<InjectionPayload>> {
+        let mut header = [0u8; 4];
+        match reader.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        }
+
+        let len = u32::from_be_bytes(header) as usize;
+        if len > MAX_FRAME_LEN {
+            return Err(Self::oversized_frame(len));
+        }
+
+        let mut body = vec![0u8; len];
+        reader.read_exact(&mut body)?;
+        Ok(Some(serde_json::from_slice(&body)?))
+    }
 }
 
 /// Builder for creating complex injection payloads
@@ -239,6 +579,66 @@ pub mod presets {
             .build()
     }
 
+    /// A blocker raised earlier is resolved
+    ///
+    /// `blocker_ref` names the original blocker (a test, pipeline or correlation
+    /// id) and is kept under the `blocker_ref` metadata key.
+    pub fn blocker_cleared(blocker_ref: &str, resolution: &str) -> InjectionPayload {
+        PayloadBuilder::new(PayloadType::Completion)
+            .content(format!(
+                "Blocker '{}' has been resolved.\n\nResolution: {}\n\nYou can continue with your task.",
+                blocker_ref, resolution
+            ))
+            .metadata("blocker_ref", blocker_ref)
+            .metadata("resolution", resolution)
+            .build()
+    }
+
+    /// CI pipeline outcome: a blocker listing failed tests, or a completion when green
+    pub fn ci_result(
+        pipeline: &str,
+        passed: bool,
+        failed_tests: Vec<String>,
+        log_url: &str,
+    ) -> InjectionPayload {
+        if passed {
+            return PayloadBuilder::new(PayloadType::Completion)
+                .content(format!(
+                    "CI pipeline '{}' passed.\n\nLogs: {}",
+                    pipeline, log_url
+                ))
+                .metadata("pipeline", pipeline)
+                .metadata("log_url", log_url)
+                .build();
+        }
+
+        let list: Vec<String> = failed_tests.iter().map(|test| format!("- {}", test)).collect();
+        PayloadBuilder::new(PayloadType::Block)
+            .content(format!(
+                "CI pipeline '{}' failed with {} failing test(s):\n\n{}\n\nLogs: {}\n\nPlease fix the failing tests before proceeding.",
+                pipeline,
+                failed_tests.len(),
+                list.join("\n"),
+                log_url
+            ))
+            .metadata("pipeline", pipeline)
+            .metadata("failed_tests", failed_tests)
+            .metadata("log_url", log_url)
+            .build()
+    }
+
+    /// Ask an agent to report its status and blockers in the structure of `format_hint`
+    pub fn status_request(format_hint: &str) -> InjectionPayload {
+        PayloadBuilder::new(PayloadType::Context)
+            .content(format!(
+                "Status check: reply with your current status and any blockers, using exactly this format:\n\n{}\n\nKeep it brief and carry on with your task afterwards.",
+                format_hint
+            ))
+            .metadata("request", "status")
+            .metadata("format_hint", format_hint)
+            .build()
+    }
+
     /// Security audit warning
     pub fn security_warning(issue: &str, severity: &str) -> InjectionPayload {
         PayloadBuilder::new(PayloadType::Warning)
@@ -281,6 +681,151 @@ mod tests {
         println!("{}", payload.to_injection_string());
     }
 
+    fn all_payload_types() -> Vec<InjectionPayload> {
+        let mut details = HashMap::new();
+        details.insert("files_changed".to_string(), serde_json::json!(3));
+
+        vec![
+            InjectionPayload::context("Schema is ready").with_metadata("task", "db"),
+            InjectionPayload::warning("Disk almost full"),
+            InjectionPayload::block("Tests are failing").with_metadata("tests", vec!["a", "b"]),
+            InjectionPayload::completion("Migration done", details),
+            InjectionPayload::progress(75, "Three quarters"),
+            InjectionPayload::user_prompt("Continue with the next step"),
+            InjectionPayload::multi_part([("Question", "Why does this fail?"), ("Code", "```rust\nfoo()\n```")]),
+        ]
+    }
+
+    #[test]
+    fn test_multi_part_sections() {
+        let payload = InjectionPayload::multi_part([
+            ("Context", "The login test started failing today.\n"),
+            ("", "```rust\nassert!(login().is_ok());\n```"),
+            ("Question", "What changed?"),
+        ]);
+
+        let expected = "## Context\nThe login test started failing today.\n\n```rust\nassert!(login().is_ok());\n```\n\n## Question\nWhat changed?";
+        assert_eq!(payload.to_injection_string(), expected);
+        assert_eq!(payload.content, expected);
+        assert!(matches!(payload.payload_type, PayloadType::MultiPart(ref sections) if sections.len() == 3));
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        for payload in all_payload_types() {
+            let json = payload.to_json().unwrap();
+            assert_eq!(InjectionPayload::from_json(&json).unwrap(), payload);
+        }
+    }
+
+    #[test]
+    fn test_codec_round_trip() {
+        let payloads = all_payload_types();
+
+        let mut stream = Vec::new();
+        for payload in &payloads {
+            PayloadCodec::write_to(&mut stream, payload).unwrap();
+        }
+
+        // Buffer decoding waits for complete frames
+        let first_len = PayloadCodec::encode(&payloads[0]).unwrap().len();
+        assert!(PayloadCodec::decode(&stream[..first_len - 1]).unwrap().is_none());
+        let (decoded, consumed) = PayloadCodec::decode(&stream).unwrap().unwrap();
+        assert_eq!(decoded, payloads[0]);
+        assert_eq!(consumed, first_len);
+
+        let mut reader = std::io::Cursor::new(stream);
+        for payload in &payloads {
+            assert_eq!(&PayloadCodec::read_from(&mut reader).unwrap().unwrap(), payload);
+        }
+        assert!(PayloadCodec::read_from(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_mcp_call() {
+        let payload = InjectionPayload::mcp_call("mcp__agenthub_http__call_agent", serde_json::json!("coding-agent")).unwrap();
+        assert_eq!(payload.payload_type, PayloadType::McpCall);
+        assert_eq!(payload.content, "mcp__agenthub_http__call_agent(\"coding-agent\")");
+
+        let payload = InjectionPayload::mcp_call("mcp__tasks__create", serde_json::json!({"title": "Fix CI"})).unwrap();
+        assert_eq!(payload.content, r#"mcp__tasks__create({"title":"Fix CI"})"#);
+        assert_eq!(mcp_invocation("mcp__tasks__list", &serde_json::Value::Null).unwrap(), "mcp__tasks__list()");
+
+        assert!(InjectionPayload::mcp_call("call_agent", serde_json::Value::Null).is_err());
+        assert!(InjectionPayload::mcp_call("mcp__server__", serde_json::Value::Null).is_err());
+        assert!(InjectionPayload::mcp_call("mcp__a b__tool", serde_json::Value::Null).is_err());
+        assert!(InjectionPayload::mcp_call("mcp__tasks__list", serde_json::json!([1, 2])).is_err());
+    }
+
+    #[test]
+    fn test_correlation_id() {
+        let payload = InjectionPayload::user_prompt("deploy").with_correlation_id("req-42");
+        assert_eq!(payload.correlation_id(), Some("req-42"));
+        assert_eq!(InjectionPayload::from_json(&payload.to_json().unwrap()).unwrap(), payload);
+        assert_eq!(InjectionPayload::user_prompt("deploy").correlation_id(), None);
+
+        set_correlation_markers(true);
+        assert_eq!(payload.to_injection_string(), "deploy <!-- correlation-id: req-42 -->");
+        set_correlation_markers(false);
+        assert_eq!(payload.to_injection_string(), "deploy");
+    }
+
+    #[test]
+    fn test_injection_wrapper() {
+        let wrapper = InjectionWrapper {
+            prefix: Some("[AUTOMATION]".to_string()),
+            suffix: Some("[END]".to_string()),
+        };
+
+        assert_eq!(wrapper.wrap("run the tests"), "[AUTOMATION] run the tests [END]");
+        assert_eq!(wrapper.wrap("\n\nblock\n"), "[AUTOMATION]\n\nblock\n[END]");
+        assert_eq!(InjectionWrapper::default().wrap("as is"), "as is");
+        assert_eq!(wrapper.wrap("  "), "  ");
+    }
+
+    #[test]
+    fn test_payload_from_file() {
+        let dir = std::env::temp_dir().join(format!("claude-payload-file-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let json = dir.join("warning.json");
+        std::fs::write(&json, r#"{"payload_type": "Warning", "content": "Disk almost full", "metadata": null}"#).unwrap();
+        assert_eq!(InjectionPayload::from_file(&json).unwrap(), InjectionPayload::warning("Disk almost full"));
+
+        let yaml = dir.join("prompt.yaml");
+        std::fs::write(&yaml, "payload_type: UserPrompt\ncontent: Run the tests\nmetadata:\n  owner: ci\n").unwrap();
+        let payload = InjectionPayload::from_file(&yaml).unwrap();
+        assert_eq!(payload.payload_type, PayloadType::UserPrompt);
+        assert_eq!(payload.metadata.unwrap()["owner"], "ci");
+
+        let empty = dir.join("empty.yml");
+        std::fs::write(&empty, "payload_type: Context\ncontent: '  '\nmetadata: null\n").unwrap();
+        let err = InjectionPayload::from_file(&empty).unwrap_err().to_string();
+        assert!(err.contains("empty.yml"), "{}", err);
+
+        let text = dir.join("prompt.txt");
+        std::fs::write(&text, "hello").unwrap();
+        assert!(InjectionPayload::from_file(&text).unwrap_err().to_string().contains("Unsupported"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_saved_preset_round_trip() {
+        let dir = std::env::temp_dir().join(format!("claude-presets-test-{}", std::process::id()));
+        let payload = InjectionPayload::warning("Freeze merges until the release is out").with_metadata("owner", "ops");
+
+        let path = payload.save_preset("release-freeze", &dir).unwrap();
+        assert_eq!(path, dir.join("release-freeze.json"));
+        assert_eq!(InjectionPayload::load_preset("release-freeze", &dir).unwrap(), payload);
+
+        let err = InjectionPayload::load_preset("missing", &dir).unwrap_err().to_string();
+        assert!(err.contains("No preset named 'missing'"), "{}", err);
+        assert!(payload.save_preset("../escape", &dir).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     fn test_presets() {
         let payload = presets::dependency_completed(
@@ -295,5 +840,22 @@ mod tests {
 
         let payload = presets::test_failed("test_jwt_expiry", "Token expiry calculation incorrect");
         println!("{}", payload.to_injection_string());
+
+        let url = "https://ci.example.com/runs/42";
+        let payload = presets::ci_result("main", false, vec!["auth.jwt_expiry".to_string()], url);
+        assert_eq!(payload.payload_type, PayloadType::Block);
+        assert!(payload.content.contains("- auth.jwt_expiry"));
+        assert_eq!(payload.metadata.unwrap()["log_url"], url);
+
+        let payload = presets::ci_result("main", true, vec![], url);
+        assert_eq!(payload.payload_type, PayloadType::Completion);
+
+        let payload = presets::blocker_cleared("test_jwt_expiry", "Fixed the clock skew in the expiry check");
+        assert_eq!(payload.payload_type, PayloadType::Completion);
+        assert!(payload.to_injection_string().contains("\"blocker_ref\": \"test_jwt_expiry\""));
+
+        let payload = presets::status_request("STATUS: <one line>\nBLOCKERS: <list>");
+        assert_eq!(payload.payload_type, PayloadType::Context);
+        assert!(payload.content.contains("BLOCKERS: <list>"));
     }
 }
@@ -1,36 +1,289 @@
 use anyhow::{Context, Result};
-use std::fs::{File, OpenOptions};
-use std::io::Write;
+use std::fs::OpenOptions;
 use std::path::PathBuf;
 
+use crate::error::InjectorError;
+use crate::injector::InjectStats;
+
+/// How a message is delivered to a terminal device
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum PtyWriteMode {
+    /// Push bytes into the terminal's input queue with TIOCSTI (seen as typed input)
+    #[default]
+    Tiocsti,
+    /// Write bytes to the device (shown as terminal output, not read as input)
+    Direct,
+}
+
+/// Sysctl that restricts TIOCSTI to CAP_SYS_ADMIN when set to 0 (Linux 6.2+)
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+const LEGACY_TIOCSTI_SYSCTL: &str = "/proc/sys/dev/tty/legacy_tiocsti";
+
+/// Capability bit for CAP_SYS_ADMIN, which keeps TIOCSTI usable when restricted
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+const CAP_SYS_ADMIN: u32 = 21;
+
+/// Most offending characters listed in a [`PtyInjector::check_printable`] error
+const MAX_REPORTED_CONTROL_CHARS: usize = 10;
+
+/// Terminal multiplexer that can type into a session without TIOCSTI
+#[derive(Debug, Clone, PartialEq)]
+pub enum Multiplexer {
+    /// Id of the tmux pane hosting the process
+    Tmux(String),
+    /// GNU screen session (its `STY`) and window number
+    Screen { session: String, window: Option<String> },
+}
+
+impl std::fmt::Display for Multiplexer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Multiplexer::Tmux(pane) => write!(f, "tmux pane {}", pane),
+            Multiplexer::Screen { session, .. } => write!(f, "screen session {}", session),
+        }
+    }
+}
+
 /// PTY Injector - Injects into existing Claude sessions via terminal device
 pub struct PtyInjector;
 
 impl PtyInjector {
     /// Inject message into a Claude session by writing to its controlling terminal
-    pub fn inject_to_session(session_id: &str, message: &str) -> Result<()> {
+    ///
+    /// With `sanitize`, messages holding control characters are refused (see
+    /// [`PtyInjector::check_printable`]). Blank messages are refused with
+    /// [`InjectorError::EmptyMessage`] unless `allow_empty` is set. The returned
+    /// duration covers the session lookup as well as the write.
+    ///
+    /// Where TIOCSTI is disabled, a session running inside tmux or screen is
+    /// typed into through the multiplexer instead; see [`PtyInjector::find_multiplexer`].
+    pub fn inject_to_session(session_id: &str, message: &str, sanitize: bool, allow_empty: bool) -> Result<InjectStats> {
+        let started = std::time::Instant::now();
+        if !allow_empty {
+            crate::safety::check_not_empty(message)?;
+        }
+        if sanitize {
+            Self::check_printable(message)?;
+        }
+
         // Find the session
         let session = crate::SessionMapper::find_session_by_id(session_id)?
-            .context(format!("Session '{}' not found or not running", session_id))?;
+            .ok_or_else(|| InjectorError::SessionNotFound(session_id.to_string()))?;
+
+        log::info!("📌 Found session: {} (PID {})", session.session_id, session.pid);
+        session.ensure_injectable()?;
 
-        println!("📌 Found session: {}", session.session_id);
-        println!("📌 Process PID: {}", session.pid);
+        if !Self::tiocsti_available() {
+            if let Some(multiplexer) = Self::find_multiplexer(session.pid)? {
+                log::info!("📌 TIOCSTI is disabled, typing through {}", multiplexer);
+                Self::inject_via_multiplexer(&multiplexer, message)?;
+
+                let stats = InjectStats::since(started, message.len() + 1);
+                log::debug!("Injected {} bytes in {:?}", stats.bytes, stats.duration);
+                return Ok(stats);
+            }
+        }
 
         // Get the controlling terminal
         let pty_path = Self::get_controlling_terminal(session.pid)?;
-        println!("📌 Terminal device: {}", pty_path.display());
+        log::info!("📌 Terminal device: {}", pty_path.display());
 
         // Write to the pty
         Self::write_to_pty(&pty_path, message)?;
 
-        println!("✅ Message injected to terminal!");
+        let stats = InjectStats::since(started, message.len() + 1);
+        log::debug!("Injected {} bytes in {:?}", stats.bytes, stats.duration);
+        Ok(stats)
+    }
+
+    /// Inject message into a known terminal device, skipping session lookup
+    pub fn inject_to_device(
+        pty_path: &str,
+        message: &str,
+        mode: PtyWriteMode,
+        sanitize: bool,
+        allow_empty: bool,
+    ) -> Result<InjectStats> {
+        let started = std::time::Instant::now();
+        if !allow_empty {
+            crate::safety::check_not_empty(message)?;
+        }
+        if sanitize {
+            Self::check_printable(message)?;
+        }
+        let pty_path = PathBuf::from(pty_path);
+        Self::validate_tty_device(&pty_path)?;
+
+        match mode {
+            PtyWriteMode::Tiocsti => Self::write_to_pty(&pty_path, message)?,
+            PtyWriteMode::Direct => Self::write_direct(&pty_path, message)?,
+        }
+
+        let stats = InjectStats::since(started, message.len() + 1);
+        log::debug!("Injected {} bytes in {:?}", stats.bytes, stats.duration);
+        Ok(stats)
+    }
+
+    /// The tmux pane or screen window a process runs in, if any
+    ///
+    /// tmux is asked which pane hosts the process; screen sessions are found
+    /// from the `STY` and `WINDOW` variables in the process's environment.
+    pub fn find_multiplexer(pid: u32) -> Result<Option<Multiplexer>> {
+        if crate::TmuxSpawner::is_available() {
+            if let Some(pane) = crate::TmuxSpawner::find_pane_for_pid(pid)? {
+                return Ok(Some(Multiplexer::Tmux(pane)));
+            }
+        }
+
+        Ok(Self::read_environ(pid).and_then(|environ| Self::screen_from_environ(&environ)))
+    }
+
+    /// Type a message and Enter into a multiplexer's pane or window
+    ///
+    /// The message is sent even when blank; callers check it first.
+    pub fn inject_via_multiplexer(multiplexer: &Multiplexer, message: &str) -> Result<()> {
+        match multiplexer {
+            Multiplexer::Tmux(pane) => {
+                let options = crate::TmuxInjectOptions {
+                    allow_empty: true,
+                    ..Default::default()
+                };
+                crate::TmuxSpawner::inject_message_with_options(pane, message, &options)
+            }
+            Multiplexer::Screen { session, window } => {
+                let mut command = std::process::Command::new("screen");
+                command.args(["-S", session]);
+                if let Some(window) = window {
+                    command.args(["-p", window]);
+                }
+
+                let output = command
+                    .args(["-X", "stuff", &Self::screen_stuff_text(message)])
+                    .output()
+                    .context("Failed to run screen")?;
+                if !output.status.success() {
+                    anyhow::bail!(
+                        "screen could not type into {}: {}",
+                        session,
+                        String::from_utf8_lossy(&output.stdout).trim()
+                    );
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// A message escaped for `screen -X stuff`, which expands `\`, `^` and `$` sequences, then Enter
+    fn screen_stuff_text(message: &str) -> String {
+        let mut text = String::with_capacity(message.len() + 1);
+        for ch in message.chars() {
+            if matches!(ch, '\\' | '^' | '$') {
+                text.push('\\');
+            }
+            text.push(ch);
+        }
+        text.push('\r');
+        text
+    }
+
+    #[cfg(target_os = "linux")]
+    fn read_environ(pid: u32) -> Option<Vec<u8>> {
+        std::fs::read(format!("/proc/{}/environ", pid)).ok()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn read_environ(_pid: u32) -> Option<Vec<u8>> {
+        None
+    }
+
+    /// The screen session named by `STY` in a NUL-separated environment block
+    fn screen_from_environ(environ: &[u8]) -> Option<Multiplexer> {
+        let mut session = None;
+        let mut window = None;
+
+        for var in environ.split(|byte| *byte == 0).filter_map(|var| std::str::from_utf8(var).ok()) {
+            if let Some(sty) = var.strip_prefix("STY=") {
+                session = Some(sty.to_string());
+            } else if let Some(number) = var.strip_prefix("WINDOW=") {
+                window = Some(number.to_string());
+            }
+        }
+
+        Some(Multiplexer::Screen { session: session?, window })
+    }
+
+    /// Fail if a message holds control characters
+    ///
+    /// Pushed as keyboard input, bytes like Ctrl-C (0x03) or ESC (0x1b) act on
+    /// the target instead of being typed; the error lists each one found with
+    /// its byte offset.
+    pub fn check_printable(message: &str) -> Result<()> {
+        let offending: Vec<String> = message
+            .char_indices()
+            .filter(|(_, ch)| ch.is_control())
+            .map(|(offset, ch)| format!("U+{:04X} at byte {}", ch as u32, offset))
+            .collect();
+
+        if offending.is_empty() {
+            return Ok(());
+        }
+
+        let shown = offending.iter().take(MAX_REPORTED_CONTROL_CHARS).cloned().collect::<Vec<_>>().join(", ");
+        let more = offending.len().saturating_sub(MAX_REPORTED_CONTROL_CHARS);
+        anyhow::bail!(
+            "Message contains {} control character(s): {}{}",
+            offending.len(),
+            shown,
+            if more > 0 { format!(" and {} more", more) } else { String::new() }
+        )
+    }
+
+    /// Ensure a path names a terminal device we can open
+    #[cfg(unix)]
+    fn validate_tty_device(pty_path: &PathBuf) -> Result<()> {
+        use std::os::unix::io::AsRawFd;
+
+        let path_str = pty_path.to_string_lossy();
+        if !path_str.starts_with("/dev/pts/") && !path_str.starts_with("/dev/tty") {
+            anyhow::bail!("Not a terminal device path: {}", path_str);
+        }
+
+        let device = OpenOptions::new()
+            .write(true)
+            .open(pty_path)
+            .context(format!("Failed to open terminal device: {}", path_str))?;
+
+        if unsafe { libc::isatty(device.as_raw_fd()) } != 1 {
+            anyhow::bail!("{} is not a tty", path_str);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn validate_tty_device(_pty_path: &PathBuf) -> Result<()> {
+        anyhow::bail!("Terminal device injection only supported on Unix");
+    }
+
+    /// Write message bytes straight to a terminal device (appears as output)
+    fn write_direct(pty_path: &PathBuf, message: &str) -> Result<()> {
+        use std::io::Write;
+
+        let mut pty = OpenOptions::new()
+            .write(true)
+            .open(pty_path)
+            .context(format!("Failed to open pty device: {}", pty_path.display()))?;
+
+        pty.write_all(message.as_bytes())?;
+        pty.write_all(b"\n")?;
+        pty.flush()?;
 
         Ok(())
     }
 
     /// Get the controlling terminal device for a process
     #[cfg(target_os = "linux")]
-    fn get_controlling_terminal(pid: u32) -> Result<PathBuf> {
+    pub fn get_controlling_terminal(pid: u32) -> Result<PathBuf> {
         // Read /proc/PID/fd/0 (stdin) to find the terminal device
         let fd0_path = format!("/proc/{}/fd/0", pid);
         let target = std::fs::read_link(&fd0_path)
@@ -46,50 +299,112 @@ impl PtyInjector {
     }
 
     #[cfg(not(target_os = "linux"))]
-    fn get_controlling_terminal(_pid: u32) -> Result<PathBuf> {
+    pub fn get_controlling_terminal(_pid: u32) -> Result<PathBuf> {
         anyhow::bail!("PTY injection only supported on Linux");
     }
 
+    /// Whether TIOCSTI injection can work on this system
+    ///
+    /// Probed once from the `dev.tty.legacy_tiocsti` sysctl and the process's
+    /// capabilities, then cached, so callers can pick a fallback before trying.
+    pub fn tiocsti_available() -> bool {
+        static AVAILABLE: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+        *AVAILABLE.get_or_init(Self::probe_tiocsti)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn probe_tiocsti() -> bool {
+        let setting = std::fs::read_to_string(LEGACY_TIOCSTI_SYSCTL).ok();
+        let cap_eff = std::fs::read_to_string("/proc/self/status").ok().and_then(|status| {
+            status
+                .lines()
+                .find_map(|line| line.strip_prefix("CapEff:"))
+                .and_then(|caps| u64::from_str_radix(caps.trim(), 16).ok())
+        });
+
+        let available = Self::tiocsti_allowed(setting.as_deref(), cap_eff);
+        log::debug!("TIOCSTI available: {} (legacy_tiocsti: {:?})", available, setting.map(|s| s.trim().to_string()));
+        available
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn probe_tiocsti() -> bool {
+        false
+    }
+
+    /// Decide TIOCSTI availability from the sysctl value and effective capabilities
+    ///
+    /// Kernels without the sysctl predate the restriction and always allow it.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn tiocsti_allowed(legacy_setting: Option<&str>, cap_eff: Option<u64>) -> bool {
+        match legacy_setting.map(str::trim) {
+            None | Some("1") => true,
+            Some(_) => cap_eff.is_some_and(|caps| caps & (1 << CAP_SYS_ADMIN) != 0),
+        }
+    }
+
     /// Write message to a pty device using TIOCSTI to inject as keyboard input
     #[cfg(target_os = "linux")]
     fn write_to_pty(pty_path: &PathBuf, message: &str) -> Result<()> {
         use std::os::unix::io::AsRawFd;
 
+        if !Self::tiocsti_available() {
+            return Err(InjectorError::PermissionDenied(
+                "TIOCSTI is disabled on this system (dev.tty.legacy_tiocsti = 0). \
+                 Consider using tmux/screen or terminal automation tools instead."
+                    .to_string(),
+            )
+            .into());
+        }
+
         // Open the pty device for writing
-        let pty = OpenOptions::new()
-            .write(true)
-            .open(pty_path)
-            .context(format!(
-                "Failed to open pty device: {}. You may need permissions.",
-                pty_path.display()
-            ))?;
+        let pty = match OpenOptions::new().write(true).open(pty_path) {
+            Ok(pty) => pty,
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+                return Err(InjectorError::PermissionDenied(format!("cannot write to {}", pty_path.display())).into());
+            }
+            Err(e) => return Err(e).context(format!("Failed to open pty device: {}", pty_path.display())),
+        };
 
         let fd = pty.as_raw_fd();
 
         // TIOCSTI constant (0x5412 on Linux)
         const TIOCSTI: libc::c_ulong = 0x5412;
 
-        // Inject each character using TIOCSTI ioctl
-        for byte in message.as_bytes() {
-            unsafe {
-                let result = libc::ioctl(fd, TIOCSTI, byte as *const u8);
-                if result < 0 {
-                    // TIOCSTI might be disabled in kernel 6.2+
-                    return Err(anyhow::anyhow!(
-                        "TIOCSTI ioctl failed. Your kernel may have disabled TIOCSTI (Linux 6.2+). \
-                         Consider using tmux/screen or terminal automation tools instead."
-                    ));
-                }
+        // Inject each character (then Enter) using TIOCSTI ioctl
+        Self::push_message(message, |byte| {
+            let result = unsafe { libc::ioctl(fd, TIOCSTI, &byte as *const u8) };
+            if result < 0 {
+                // TIOCSTI might be disabled in kernel 6.2+
+                return Err(InjectorError::PermissionDenied(
+                    "TIOCSTI ioctl failed. Your kernel may have disabled TIOCSTI (Linux 6.2+). \
+                     Consider using tmux/screen or terminal automation tools instead."
+                        .to_string(),
+                )
+                .into());
             }
-        }
+            Ok(())
+        })
+    }
+
+    /// Feed a message to `push_byte` one character at a time, followed by Enter
+    ///
+    /// TIOCSTI takes a single byte per call, so multi-byte UTF-8 characters
+    /// (accents, CJK, emoji) are pushed as consecutive bytes with nothing
+    /// interleaved. Terminals reassemble them as typed input, but a line
+    /// discipline without `IUTF8` may erase them byte-wise, and some terminals
+    /// render wide emoji or combining sequences with the wrong width.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn push_message(message: &str, mut push_byte: impl FnMut(u8) -> Result<()>) -> Result<()> {
+        let mut buf = [0u8; 4];
 
-        // Send Enter key
-        unsafe {
-            let newline: u8 = b'\n';
-            libc::ioctl(fd, TIOCSTI, &newline as *const u8);
+        for ch in message.chars() {
+            for byte in ch.encode_utf8(&mut buf).as_bytes() {
+                push_byte(*byte)?;
+            }
         }
 
-        Ok(())
+        push_byte(b'\n')
     }
 
     #[cfg(not(target_os = "linux"))]
@@ -98,11 +413,13 @@ impl PtyInjector {
     }
 
     /// Inject message with proper escaping for terminal
-    pub fn inject_safe(session_id: &str, message: &str) -> Result<()> {
+    ///
+    /// Newlines are escaped; any other control character is refused.
+    pub fn inject_safe(session_id: &str, message: &str) -> Result<InjectStats> {
         // Escape special characters that might cause issues
         let escaped = message.replace('\\', "\\\\").replace('\n', "\\n");
 
-        Self::inject_to_session(session_id, &escaped)
+        Self::inject_to_session(session_id, &escaped, true, false)
     }
 
     /// Check if we have permission to write to a session's terminal
@@ -130,6 +447,73 @@ impl PtyInjector {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_inject_to_device_rejects_non_tty() {
+        let result = PtyInjector::inject_to_device("/dev/null", "hello", PtyWriteMode::Direct, false, false);
+        assert!(result.is_err());
+
+        let result = PtyInjector::inject_to_device("/tmp/not-a-pty", "hello", PtyWriteMode::Tiocsti, false, false);
+        assert!(result.is_err());
+
+        let err = PtyInjector::inject_to_device("/dev/null", " \n", PtyWriteMode::Direct, false, false).unwrap_err();
+        assert!(matches!(InjectorError::find_in(&err), Some(InjectorError::EmptyMessage)));
+    }
+
+    #[test]
+    fn test_check_printable() {
+        assert!(PtyInjector::check_printable("plain text, café 🚀").is_ok());
+
+        let err = PtyInjector::check_printable("stop\u{3} now\u{1b}[2J").unwrap_err().to_string();
+        assert!(err.contains("2 control character(s)"), "{}", err);
+        assert!(err.contains("U+0003 at byte 4"), "{}", err);
+        assert!(err.contains("U+001B at byte 9"), "{}", err);
+
+        let err = PtyInjector::check_printable(&"\t".repeat(12)).unwrap_err().to_string();
+        assert!(err.ends_with("and 2 more"), "{}", err);
+    }
+
+    #[test]
+    fn test_push_message_keeps_utf8_sequences_intact() {
+        let message = "ok 🚀 café";
+        let mut pushed = Vec::new();
+
+        PtyInjector::push_message(message, |byte| {
+            pushed.push(byte);
+            Ok(())
+        })
+        .unwrap();
+
+        // Enter comes last and the payload bytes are valid UTF-8 in order
+        assert_eq!(pushed.last(), Some(&b'\n'));
+        assert_eq!(std::str::from_utf8(&pushed[..pushed.len() - 1]).unwrap(), message);
+    }
+
+    #[test]
+    fn test_screen_fallback() {
+        let environ = b"HOME=/root\0WINDOW=2\0STY=1234.pts-0.host\0TERM=screen\0";
+        assert_eq!(
+            PtyInjector::screen_from_environ(environ),
+            Some(Multiplexer::Screen {
+                session: "1234.pts-0.host".to_string(),
+                window: Some("2".to_string()),
+            })
+        );
+        assert_eq!(PtyInjector::screen_from_environ(b"WINDOW=2\0TERM=xterm\0"), None);
+
+        assert_eq!(PtyInjector::screen_stuff_text("cost $5 ^C \\n"), "cost \\$5 \\^C \\\\n\r");
+    }
+
+    #[test]
+    fn test_tiocsti_allowed() {
+        let sys_admin = 1u64 << CAP_SYS_ADMIN;
+
+        assert!(PtyInjector::tiocsti_allowed(None, None));
+        assert!(PtyInjector::tiocsti_allowed(Some("1\n"), Some(0)));
+        assert!(!PtyInjector::tiocsti_allowed(Some("0\n"), Some(0)));
+        assert!(!PtyInjector::tiocsti_allowed(Some("0\n"), None));
+        assert!(PtyInjector::tiocsti_allowed(Some("0\n"), Some(sys_admin)));
+    }
+
     #[test]
     fn test_get_controlling_terminal() {
         // Test with current process (should have a terminal if run from terminal)
@@ -0,0 +1,223 @@
+use anyhow::{Context, Result};
+use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty, PtySize};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+use crate::payload::InjectionPayload;
+use crate::session::ClaudeSession;
+
+/// Most recent output kept per session for [`PtyManager::output`] (1 MiB)
+const OUTPUT_BUFFER_LIMIT: usize = 1024 * 1024;
+
+/// Output chunks buffered for slow [`PtyManager::subscribe`] receivers
+const OUTPUT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Terminal size Claude is started with
+const PTY_SIZE: PtySize = PtySize {
+    rows: 50,
+    cols: 200,
+    pixel_width: 0,
+    pixel_height: 0,
+};
+
+/// Runs Claude on pseudo-terminals we allocate ourselves
+///
+/// Claude gets a real terminal, so its interactive UI works, while we hold the
+/// master end: injection is a plain write (no TIOCSTI, no tmux) and every byte
+/// Claude prints is available to read.
+pub struct PtyManager {
+    sessions: Mutex<HashMap<String, PtySession>>,
+}
+
+/// A process running on a pty owned by [`PtyManager`]
+struct PtySession {
+    child: Box<dyn Child + Send + Sync>,
+    writer: Box<dyn Write + Send>,
+    // Kept so the pty stays open for as long as the session is tracked
+    _master: Box<dyn MasterPty + Send>,
+    output: Arc<Mutex<Vec<u8>>>,
+    output_tx: broadcast::Sender<Vec<u8>>,
+}
+
+impl PtyManager {
+    pub fn new() -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Start `claude` for a session on a new pty, tracked under the session id
+    pub fn spawn(&self, session: &ClaudeSession, initial_prompt: Option<String>) -> Result<String> {
+        let args: Vec<String> = initial_prompt.into_iter().collect();
+        let command = crate::claude_command(&[], &args)?;
+
+        self.spawn_program(&session.session_id, &command[0], &command[1..], &session.project_path)?;
+        Ok(session.session_id.clone())
+    }
+
+    /// Start `program` on a new pty and track it as `id`
+    pub fn spawn_program(&self, id: &str, program: &str, args: &[String], working_dir: &str) -> Result<()> {
+        let pair = native_pty_system()
+            .openpty(PTY_SIZE)
+            .context("Failed to allocate a pty")?;
+
+        let mut cmd = CommandBuilder::new(program);
+        cmd.args(args);
+        cmd.cwd(working_dir);
+
+        let child = pair
+            .slave
+            .spawn_command(cmd)
+            .context(format!("Failed to spawn {} on a pty", program))?;
+        // Only the child needs the slave end; holding it would hide the child's exit
+        drop(pair.slave);
+
+        let mut reader = pair.master.try_clone_reader().context("Failed to read from pty")?;
+        let writer = pair.master.take_writer().context("Failed to write to pty")?;
+
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let (output_tx, _) = broadcast::channel(OUTPUT_CHANNEL_CAPACITY);
+
+        let buffer = Arc::clone(&output);
+        let tx = output_tx.clone();
+        std::thread::spawn(move || {
+            let mut chunk = [0u8; 4096];
+            loop {
+                match reader.read(&mut chunk) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => {
+                        let mut buffer = buffer.lock().unwrap();
+                        buffer.extend_from_slice(&chunk[..n]);
+                        if buffer.len() > OUTPUT_BUFFER_LIMIT {
+                            let excess = buffer.len() - OUTPUT_BUFFER_LIMIT;
+                            buffer.drain(..excess);
+                        }
+                        drop(buffer);
+
+                        // No subscribers is fine; the buffer still has the output
+                        let _ = tx.send(chunk[..n].to_vec());
+                    }
+                }
+            }
+        });
+
+        log::info!("Spawned {} on a pty with PID {:?}", program, child.process_id());
+
+        self.sessions.lock().unwrap().insert(
+            id.to_string(),
+            PtySession {
+                child,
+                writer,
+                _master: pair.master,
+                output,
+                output_tx,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Type a payload into the session's terminal and press Enter
+    pub fn inject(&self, id: &str, payload: &InjectionPayload) -> Result<()> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .get_mut(id)
+            .context(format!("Session {} not found in pty sessions", id))?;
+
+        let message = payload.to_injection_string();
+        session
+            .writer
+            .write_all(message.as_bytes())
+            .and_then(|_| session.writer.write_all(b"\r"))
+            .and_then(|_| session.writer.flush())
+            .context("Failed to write to pty")?;
+
+        log::info!("Injected payload into pty session {}", id);
+        Ok(())
+    }
+
+    /// Everything the session has printed so far (up to the last 1 MiB)
+    pub fn output(&self, id: &str) -> Result<String> {
+        let sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .get(id)
+            .context(format!("Session {} not found in pty sessions", id))?;
+
+        let output = session.output.lock().unwrap();
+        Ok(String::from_utf8_lossy(&output).into_owned())
+    }
+
+    /// Receive the session's output as it is printed, from now on
+    pub fn subscribe(&self, id: &str) -> Result<broadcast::Receiver<Vec<u8>>> {
+        let sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .get(id)
+            .context(format!("Session {} not found in pty sessions", id))?;
+
+        Ok(session.output_tx.subscribe())
+    }
+
+    /// Check if a session's process is still running
+    pub fn is_running(&self, id: &str) -> bool {
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions
+            .get_mut(id)
+            .is_some_and(|session| matches!(session.child.try_wait(), Ok(None)))
+    }
+
+    /// List tracked session ids
+    pub fn list(&self) -> Vec<String> {
+        self.sessions.lock().unwrap().keys().cloned().collect()
+    }
+
+    /// Kill a session's process and close its pty
+    pub fn stop(&self, id: &str) -> Result<()> {
+        let session = self.sessions.lock().unwrap().remove(id);
+
+        if let Some(mut session) = session {
+            log::info!("Stopping pty session {}", id);
+            if matches!(session.child.try_wait(), Ok(None)) {
+                session.child.kill().context("Failed to kill pty process")?;
+            }
+            let _ = session.child.wait();
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for PtyManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inject_and_read_own_pty() {
+        let manager = PtyManager::new();
+        manager.spawn_program("pty-test", "cat", &[], "/tmp").unwrap();
+        let mut stream = manager.subscribe("pty-test").unwrap();
+
+        manager
+            .inject("pty-test", &InjectionPayload::user_prompt("hello pty"))
+            .unwrap();
+
+        // The terminal echoes the input and cat prints it back
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while manager.output("pty-test").unwrap().matches("hello pty").count() < 2 {
+            assert!(std::time::Instant::now() < deadline, "no echo from pty");
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+        assert!(String::from_utf8_lossy(&stream.try_recv().unwrap()).contains("hello"));
+
+        assert!(manager.is_running("pty-test"));
+        manager.stop("pty-test").unwrap();
+        assert!(!manager.is_running("pty-test"));
+    }
+}
@@ -0,0 +1,136 @@
+use anyhow::{Context, Result};
+use std::process::{Command, Output};
+
+use crate::error::InjectorError;
+use crate::tmux_spawner::TmuxSpawner;
+use crate::worker_registry::{WorkerRegistry, GLOBAL_REGISTRY_FILE};
+
+/// Exit status `ssh` reports for its own failures, as opposed to the remote command's
+const SSH_FAILURE_STATUS: i32 = 255;
+
+/// Exit status of a shell that could not find the command
+const COMMAND_NOT_FOUND_STATUS: i32 = 127;
+
+/// A host whose tmux workers are driven over `ssh`
+///
+/// Commands run non-interactively (`BatchMode`), so a host that needs a
+/// password fails instead of prompting; set up key authentication first.
+#[derive(Debug, Clone)]
+pub struct SshHost {
+    destination: String,
+}
+
+impl SshHost {
+    /// Host to reach as `user@host` (anything `ssh` accepts as a destination)
+    pub fn new(destination: impl Into<String>) -> Self {
+        Self {
+            destination: destination.into(),
+        }
+    }
+
+    pub fn destination(&self) -> &str {
+        &self.destination
+    }
+
+    /// Whether a tmux session exists on the host
+    pub fn session_exists(&self, session_name: &str) -> Result<bool> {
+        let output = self.run_tmux(&["has-session".to_string(), "-t".to_string(), session_name.to_string()])?;
+        Ok(output.status.success())
+    }
+
+    /// Type a message into a remote tmux session and press Enter
+    pub fn inject_message(&self, target: &str, message: &str) -> Result<()> {
+        let session = target.split(':').next().unwrap_or(target);
+        if !self.session_exists(session)? {
+            return Err(anyhow::Error::new(InjectorError::TmuxSessionNotFound(session.to_string()))
+                .context(format!("No such tmux session on {}", self.destination)));
+        }
+
+        self.checked_tmux(target, &TmuxSpawner::send_literal_args(target, message))
+            .context("Failed to inject message text")?;
+        self.checked_tmux(target, &["send-keys".to_string(), "-t".to_string(), target.to_string(), "Enter".to_string()])
+            .context("Failed to send Enter key")?;
+        Ok(())
+    }
+
+    /// The host's home-level worker registry, read with `cat`
+    ///
+    /// A host without a registry has no workers. The copy is read-only; see
+    /// [`WorkerRegistry::from_json`].
+    pub fn load_registry(&self) -> Result<WorkerRegistry> {
+        let path = format!("\"$HOME\"/{}", GLOBAL_REGISTRY_FILE);
+        let output = self.run(&format!("test ! -e {0} || cat {0}", path))?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "Failed to read the worker registry on {}: {}",
+                self.destination,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        WorkerRegistry::from_json(&String::from_utf8_lossy(&output.stdout))
+            .context(format!("Invalid worker registry on {}", self.destination))
+    }
+
+    /// Run tmux on the host against `target`, failing unless it succeeds
+    fn checked_tmux(&self, target: &str, args: &[String]) -> Result<()> {
+        let output = self.run_tmux(args)?;
+        if !output.status.success() {
+            return Err(TmuxSpawner::command_error(target, &format!("tmux on {}", self.destination), &output.stderr));
+        }
+        Ok(())
+    }
+
+    fn run_tmux(&self, args: &[String]) -> Result<Output> {
+        let mut command = vec!["tmux".to_string()];
+        command.extend_from_slice(args);
+
+        let output = self.run(&command_line(&command))?;
+        if output.status.code() == Some(COMMAND_NOT_FOUND_STATUS) {
+            anyhow::bail!("tmux is not installed on {}", self.destination);
+        }
+        Ok(output)
+    }
+
+    /// Run a shell command line on the host; fails only when ssh itself does
+    fn run(&self, command_line: &str) -> Result<Output> {
+        let output = Command::new("ssh")
+            .args(["-o", "BatchMode=yes", "--", &self.destination, command_line])
+            .output()
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => anyhow::anyhow!("ssh is not installed"),
+                _ => anyhow::Error::new(e).context("Failed to run ssh"),
+            })?;
+
+        if output.status.code() == Some(SSH_FAILURE_STATUS) {
+            anyhow::bail!(
+                "SSH to {} failed: {}",
+                self.destination,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(output)
+    }
+}
+
+/// Quote arguments for the remote shell, which `ssh` hands the joined command line to
+fn command_line(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| format!("'{}'", arg.replace('\'', r"'\''")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_line_quotes_arguments() {
+        let args = TmuxSpawner::send_literal_args("w1", "it's $HOME; `x`;");
+        assert_eq!(
+            command_line(&args),
+            r"'send-keys' '-l' '-t' 'w1' '--' 'it'\''s $HOME; `x`\;'"
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use anyhow::Result;
+use std::ffi::OsStr;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::error::InjectorError;
+
+/// Environment variable that turns on safe mode when set to `1`
+pub const SAFE_MODE_ENV: &str = "CLAUDE_INJECTOR_SAFE";
+
+/// Program every spawn path runs
+pub const CLAUDE_BINARY: &str = "claude";
+
+/// Claude flag that disables permission prompts
+pub const DANGEROUS_SKIP_PERMISSIONS: &str = "--dangerously-skip-permissions";
+
+/// Most targets a broadcast may reach without explicit confirmation
+pub const DEFAULT_MAX_BROADCAST_TARGETS: usize = 10;
+
+/// Set by `--safe` on the command line tools
+static SAFE_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Turn on safe mode for the rest of the process
+pub fn enable_safe_mode() {
+    SAFE_MODE.store(true, Ordering::SeqCst);
+}
+
+/// Whether safe mode is on, via `--safe` or `CLAUDE_INJECTOR_SAFE`
+pub fn safe_mode_enabled() -> bool {
+    SAFE_MODE.load(Ordering::SeqCst)
+        || std::env::var(SAFE_MODE_ENV)
+            .map(|value| matches!(value.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false)
+}
+
+/// Build the `claude` command line for a spawn
+///
+/// `default_flags` are flags a spawn path adds on its own; in safe mode
+/// `--dangerously-skip-permissions` is dropped from them. `explicit_args` come
+/// from the caller, and asking for the dangerous flag there is an error in safe mode.
+/// Fails with [`InjectorError::ClaudeNotFound`] when `claude` is not on `PATH`,
+/// rather than leaving the spawn to die with an OS error.
+pub fn claude_command(default_flags: &[&str], explicit_args: &[String]) -> Result<Vec<String>> {
+    find_claude_binary()?;
+    build_claude_command(safe_mode_enabled(), default_flags, explicit_args)
+}
+
+/// Path of the `claude` binary found on `PATH`
+pub fn find_claude_binary() -> Result<PathBuf> {
+    let path = std::env::var_os("PATH").unwrap_or_default();
+    find_in_path(CLAUDE_BINARY, &path).map_err(|searched| InjectorError::ClaudeNotFound { searched }.into())
+}
+
+/// First executable `name` in the directories of a `PATH` value, or the directories searched
+fn find_in_path(name: &str, path: &OsStr) -> std::result::Result<PathBuf, Vec<PathBuf>> {
+    let dirs: Vec<PathBuf> = std::env::split_paths(path).filter(|dir| !dir.as_os_str().is_empty()).collect();
+
+    dirs.iter()
+        .map(|dir| dir.join(name))
+        .find(|candidate| is_executable(candidate))
+        .ok_or(dirs)
+}
+
+#[cfg(unix)]
+fn is_executable(path: &std::path::Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0).unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &std::path::Path) -> bool {
+    path.is_file()
+}
+
+/// Fail if safe mode is on and `args` ask for `--dangerously-skip-permissions`
+pub fn check_explicit_args(args: &[String]) -> Result<()> {
+    check_args(safe_mode_enabled(), args)
+}
+
+/// Fail when a broadcast would reach more than `max` targets
+///
+/// The error gives the count and lists every target, so the caller can see
+/// what would have been hit before confirming.
+pub fn check_broadcast_targets(targets: &[String], max: usize) -> Result<()> {
+    if targets.len() > max {
+        anyhow::bail!(
+            "Broadcast would reach {} targets, more than the limit of {} (max_broadcast_targets): {}. \
+             Pass --confirm to send anyway",
+            targets.len(),
+            max,
+            targets.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+/// Fail with [`InjectorError::EmptyMessage`] when a message is blank after trimming
+///
+/// Injected as is, it would press Enter on an empty prompt and waste a turn.
+pub fn check_not_empty(message: &str) -> Result<()> {
+    if message.trim().is_empty() {
+        return Err(InjectorError::EmptyMessage.into());
+    }
+
+    Ok(())
+}
+
+fn check_args(safe: bool, args: &[String]) -> Result<()> {
+    if safe && args.iter().any(|arg| arg == DANGEROUS_SKIP_PERMISSIONS) {
+        anyhow::bail!(
+            "{} was requested but safe mode ({}=1 or --safe) forbids it",
+            DANGEROUS_SKIP_PERMISSIONS,
+            SAFE_MODE_ENV
+        );
+    }
+
+    Ok(())
+}
+
+fn build_claude_command(safe: bool, default_flags: &[&str], explicit_args: &[String]) -> Result<Vec<String>> {
+    check_args(safe, explicit_args)?;
+
+    let mut command = vec![CLAUDE_BINARY.to_string()];
+    for flag in default_flags {
+        if safe && *flag == DANGEROUS_SKIP_PERMISSIONS {
+            log::info!("Safe mode: not passing {}", DANGEROUS_SKIP_PERMISSIONS);
+            continue;
+        }
+        command.push(flag.to_string());
+    }
+    command.extend(explicit_args.iter().cloned());
+
+    Ok(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safe_mode_strips_and_rejects_dangerous_flag() {
+        let defaults = [DANGEROUS_SKIP_PERMISSIONS];
+        let prompt = vec!["hello".to_string()];
+
+        let normal = build_claude_command(false, &defaults, &prompt).unwrap();
+        assert_eq!(normal, ["claude", DANGEROUS_SKIP_PERMISSIONS, "hello"]);
+
+        let safe = build_claude_command(true, &defaults, &prompt).unwrap();
+        assert_eq!(safe, ["claude", "hello"]);
+
+        let explicit = vec![DANGEROUS_SKIP_PERMISSIONS.to_string()];
+        assert!(build_claude_command(true, &[], &explicit).is_err());
+        assert!(build_claude_command(false, &[], &explicit).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_in_path() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("claude-path-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let binary = dir.join("claude");
+        std::fs::write(&binary, "#!/bin/sh\n").unwrap();
+
+        let path = std::env::join_paths(["/nonexistent-claude-dir".into(), dir.clone()]).unwrap();
+        let err = find_in_path("claude", &path).unwrap_err();
+        assert_eq!(err, [PathBuf::from("/nonexistent-claude-dir"), dir.clone()]);
+
+        std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+        assert_eq!(find_in_path("claude", &path), Ok(binary));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_broadcast_targets() {
+        let targets: Vec<String> = (1..=3).map(|i| format!("w{}", i)).collect();
+
+        assert!(check_broadcast_targets(&targets, 3).is_ok());
+        let err = check_broadcast_targets(&targets, 2).unwrap_err().to_string();
+        assert!(err.contains("3 targets"), "{}", err);
+        assert!(err.contains("w1, w2, w3"), "{}", err);
+    }
+
+    #[test]
+    fn test_check_not_empty() {
+        assert!(check_not_empty("run the tests").is_ok());
+        for blank in ["", "   ", "\n\t "] {
+            let err = check_not_empty(blank).unwrap_err();
+            assert!(matches!(InjectorError::find_in(&err), Some(InjectorError::EmptyMessage)));
+        }
+    }
+}
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Represents a Claude Code session
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +17,20 @@ pub struct ClaudeSession {
     pub jsonl_path: PathBuf,
 }
 
+/// A session of the `/tmp` project created at time 0 without a transcript, for tests to adjust
+#[cfg(test)]
+pub(crate) fn test_session(id: &str) -> ClaudeSession {
+    ClaudeSession {
+        session_id: id.to_string(),
+        project_id: "-tmp".to_string(),
+        project_path: "/tmp".to_string(),
+        created_at: 0,
+        first_message: None,
+        model: None,
+        jsonl_path: PathBuf::new(),
+    }
+}
+
 /// Entry in the JSONL session file
 #[derive(Debug, Clone, Deserialize)]
 pub struct JsonlEntry {
@@ -34,9 +48,48 @@ pub struct JsonlMessage {
     pub content: Option<serde_json::Value>,
 }
 
+/// Just enough about a session to show it in a picker
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionSummary {
+    pub session_id: String,
+    pub project_path: String,
+    /// First user message, on one line and cut to [`SUMMARY_PREVIEW_LENGTH`] characters
+    pub first_message_preview: Option<String>,
+    pub created_at: u64,
+}
+
+/// Characters of the first message kept in a [`SessionSummary`]
+pub const SUMMARY_PREVIEW_LENGTH: usize = 80;
+
+/// Disk usage of one project's session transcripts
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectStat {
+    pub project_id: String,
+    pub decoded_path: String,
+    pub session_count: usize,
+    pub total_bytes: u64,
+}
+
+/// Claude config files (relative to ~/.claude) that may relocate the projects directory
+const CLAUDE_CONFIG_FILES: &[&str] = &["config.json", "settings.json"];
+
+/// Keys checked in the config files for a custom projects directory
+const PROJECTS_DIR_KEYS: &[&str] = &["projectsDir", "projects_dir"];
+
+/// Encode a project path the way Claude names its project directories
+///
+/// Every character that is not an ASCII letter or digit becomes `-`
+/// (e.g. `/home/me/my.app` -> `-home-me-my-app`).
+pub fn encode_project_path(path: &str) -> String {
+    path.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
 /// Session detector - finds Claude Code sessions on the system
 pub struct SessionDetector {
     claude_dir: PathBuf,
+    projects_dir: PathBuf,
 }
 
 impl SessionDetector {
@@ -49,12 +102,69 @@ impl SessionDetector {
             log::warn!("Claude directory not found at: {:?}", claude_dir);
         }
 
-        Ok(Self { claude_dir })
+        let projects_dir = Self::resolve_projects_dir(&claude_dir);
+
+        Ok(Self {
+            claude_dir,
+            projects_dir,
+        })
+    }
+
+    /// Claude's base directory (usually ~/.claude)
+    pub fn claude_dir(&self) -> &Path {
+        &self.claude_dir
+    }
+
+    /// Directory holding per-project session folders (usually ~/.claude/projects)
+    pub fn projects_dir(&self) -> &Path {
+        &self.projects_dir
+    }
+
+    /// Resolve the projects directory from Claude's config, falling back to `<claude_dir>/projects`
+    fn resolve_projects_dir(claude_dir: &Path) -> PathBuf {
+        for file_name in CLAUDE_CONFIG_FILES {
+            let config_path = claude_dir.join(file_name);
+
+            let Ok(content) = fs::read_to_string(&config_path) else {
+                continue;
+            };
+
+            let config: serde_json::Value = match serde_json::from_str(&content) {
+                Ok(config) => config,
+                Err(e) => {
+                    log::warn!("Ignoring unreadable Claude config {:?}: {}", config_path, e);
+                    continue;
+                }
+            };
+
+            for key in PROJECTS_DIR_KEYS {
+                if let Some(dir) = config.get(key).and_then(|v| v.as_str()) {
+                    let dir = match dir.strip_prefix("~/") {
+                        Some(rest) => dirs::home_dir()
+                            .map(|home| home.join(rest))
+                            .unwrap_or_else(|| PathBuf::from(dir)),
+                        None => PathBuf::from(dir),
+                    };
+
+                    // Relative paths are relative to the Claude directory
+                    let dir = if dir.is_relative() {
+                        claude_dir.join(dir)
+                    } else {
+                        dir
+                    };
+
+                    log::debug!("Using projects directory from {:?}: {:?}", config_path, dir);
+                    return dir;
+                }
+            }
+        }
+
+        claude_dir.join("projects")
     }
 
-    /// List all projects in ~/.claude/projects
+    /// List all projects in the projects directory
     pub fn list_projects(&self) -> Result<Vec<String>> {
-        let projects_dir = self.claude_dir.join("projects");
+        let projects_dir = &self.projects_dir;
 
         if !projects_dir.exists() {
             return Ok(Vec::new());
@@ -62,7 +172,7 @@ impl SessionDetector {
 
         let mut projects = Vec::new();
 
-        for entry in fs::read_dir(&projects_dir)? {
+        for entry in fs::read_dir(projects_dir)? {
             let entry = entry?;
             if entry.path().is_dir() {
                 if let Some(name) = entry.file_name().to_str() {
@@ -76,7 +186,7 @@ impl SessionDetector {
 
     /// Get all sessions for a specific project
     pub fn get_project_sessions(&self, project_id: &str) -> Result<Vec<ClaudeSession>> {
-        let project_dir = self.claude_dir.join("projects").join(project_id);
+        let project_dir = self.projects_dir.join(project_id);
 
         if !project_dir.exists() {
             anyhow::bail!("Project directory not found: {}", project_id);
@@ -96,10 +206,7 @@ impl SessionDetector {
             if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("jsonl") {
                 if let Some(session_id) = path.file_stem().and_then(|s| s.to_str()) {
                     let metadata = fs::metadata(&path)?;
-                    let created_at = metadata
-                        .created()
-                        .or_else(|_| metadata.modified())
-                        .ok()
+                    let created_at = Self::created_time(&metadata)
                         .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
                         .map(|d| d.as_secs())
                         .unwrap_or(0);
@@ -120,11 +227,107 @@ impl SessionDetector {
         }
 
         // Sort by creation time (newest first)
-        sessions.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.created_at));
 
         Ok(sessions)
     }
 
+    /// When a transcript was created, or last written where creation time is unavailable
+    fn created_time(metadata: &fs::Metadata) -> Option<std::time::SystemTime> {
+        metadata.created().or_else(|_| metadata.modified()).ok()
+    }
+
+    /// The `limit` most recent sessions across all projects, newest first
+    ///
+    /// Only file metadata is read for every transcript; first messages and
+    /// project paths are read just for the sessions returned.
+    pub fn session_summaries(&self, limit: usize) -> Vec<SessionSummary> {
+        let projects = match self.list_projects() {
+            Ok(projects) => projects,
+            Err(e) => {
+                log::warn!("Failed to list projects: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let mut transcripts: Vec<(std::time::SystemTime, String, PathBuf)> = Vec::new();
+        for project_id in projects {
+            let Ok(entries) = fs::read_dir(self.projects_dir.join(&project_id)) else {
+                continue;
+            };
+
+            for entry in entries.filter_map(|e| e.ok()) {
+                let path = entry.path();
+                if path.extension().and_then(|s| s.to_str()) != Some("jsonl") {
+                    continue;
+                }
+                if let Some(created) = entry.metadata().ok().and_then(|m| Self::created_time(&m)) {
+                    transcripts.push((created, project_id.clone(), path));
+                }
+            }
+        }
+
+        transcripts.sort_by_key(|(created, _, _)| std::cmp::Reverse(*created));
+        transcripts.truncate(limit);
+
+        let mut project_paths: HashMap<String, String> = HashMap::new();
+        transcripts
+            .into_iter()
+            .filter_map(|(created, project_id, path)| {
+                let project_path = project_paths
+                    .entry(project_id.clone())
+                    .or_insert_with(|| {
+                        self.get_project_path_from_jsonl(&self.projects_dir.join(&project_id))
+                            .unwrap_or_else(|_| self.decode_project_path(&project_id))
+                    })
+                    .clone();
+
+                self.summarize(&path, project_path, created)
+            })
+            .collect()
+    }
+
+    /// Summary of one session, or `None` if its transcript does not exist
+    pub fn session_summary(&self, session_id: &str) -> Result<Option<SessionSummary>> {
+        let Some(path) = self.find_session_file(session_id)? else {
+            return Ok(None);
+        };
+
+        let project_dir = path.parent().context("Session file has no project directory")?.to_path_buf();
+        let project_path = self.get_project_path_from_jsonl(&project_dir).unwrap_or_else(|_| {
+            let project_id = project_dir.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            self.decode_project_path(project_id)
+        });
+        let created = Self::created_time(&fs::metadata(&path)?).unwrap_or(std::time::UNIX_EPOCH);
+
+        Ok(self.summarize(&path, project_path, created))
+    }
+
+    fn summarize(&self, path: &Path, project_path: String, created: std::time::SystemTime) -> Option<SessionSummary> {
+        let session_id = path.file_stem()?.to_str()?.to_string();
+        let (first_message, _) = self.extract_first_message_and_model(&path.to_path_buf());
+
+        Some(SessionSummary {
+            session_id,
+            project_path,
+            first_message_preview: first_message.map(|m| Self::preview(&m)),
+            created_at: created
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        })
+    }
+
+    /// Collapse a message onto one line and cut it to [`SUMMARY_PREVIEW_LENGTH`] characters
+    fn preview(message: &str) -> String {
+        let line = message.split_whitespace().collect::<Vec<_>>().join(" ");
+        if line.chars().count() > SUMMARY_PREVIEW_LENGTH {
+            format!("{}…", line.chars().take(SUMMARY_PREVIEW_LENGTH - 1).collect::<String>())
+        } else {
+            line
+        }
+    }
+
     /// Get ALL sessions across all projects
     pub fn get_all_sessions(&self) -> Result<HashMap<String, Vec<ClaudeSession>>> {
         let mut all_sessions = HashMap::new();
@@ -145,6 +348,118 @@ impl SessionDetector {
         Ok(all_sessions)
     }
 
+    /// Session count and transcript size per project, largest first
+    pub fn project_stats(&self) -> Vec<ProjectStat> {
+        let projects = match self.list_projects() {
+            Ok(projects) => projects,
+            Err(e) => {
+                log::warn!("Failed to list projects: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let mut stats = Vec::new();
+
+        for project_id in projects {
+            let project_dir = self.projects_dir.join(&project_id);
+            let entries = match fs::read_dir(&project_dir) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    log::warn!("Failed to read project {}: {}", project_id, e);
+                    continue;
+                }
+            };
+
+            let mut session_count = 0;
+            let mut total_bytes = 0;
+
+            for entry in entries.filter_map(|e| e.ok()) {
+                let path = entry.path();
+                if path.extension().and_then(|s| s.to_str()) != Some("jsonl") {
+                    continue;
+                }
+                if let Ok(metadata) = entry.metadata() {
+                    if metadata.is_file() {
+                        session_count += 1;
+                        total_bytes += metadata.len();
+                    }
+                }
+            }
+
+            let decoded_path = self
+                .get_project_path_from_jsonl(&project_dir)
+                .unwrap_or_else(|_| self.decode_project_path(&project_id));
+
+            stats.push(ProjectStat {
+                project_id,
+                decoded_path,
+                session_count,
+                total_bytes,
+            });
+        }
+
+        stats.sort_by_key(|stat| std::cmp::Reverse(stat.total_bytes));
+        stats
+    }
+
+    /// Find the live Claude process for a detected session, if it is running
+    ///
+    /// Matches the mapper's session ID first. Because the mapper cannot always
+    /// tell sessions of one project apart, the newest session of a project is
+    /// also matched to the single Claude process running in that project.
+    pub fn to_running(&self, session: &ClaudeSession) -> Result<Option<crate::RunningClaudeSession>> {
+        let running = crate::SessionMapper::map_sessions_to_processes()?;
+        let newest = self
+            .get_project_sessions(&session.project_id)
+            .ok()
+            .and_then(|sessions| sessions.into_iter().next())
+            .map(|newest| newest.session_id);
+
+        Ok(Self::pick_running(session, newest.as_deref(), running))
+    }
+
+    fn pick_running(
+        session: &ClaudeSession,
+        newest_in_project: Option<&str>,
+        running: Vec<crate::RunningClaudeSession>,
+    ) -> Option<crate::RunningClaudeSession> {
+        if let Some(exact) = running.iter().find(|r| r.session_id == session.session_id) {
+            return Some(exact.clone());
+        }
+
+        if newest_in_project != Some(session.session_id.as_str()) {
+            return None;
+        }
+
+        let project_path = crate::normalize_path(&session.project_path);
+        let mut in_project: Vec<crate::RunningClaudeSession> = running
+            .into_iter()
+            .filter(|r| crate::normalize_path(&r.project_path) == project_path)
+            .collect();
+
+        if in_project.len() != 1 {
+            return None;
+        }
+
+        let mut live = in_project.remove(0);
+        live.session_id = session.session_id.clone();
+        Some(live)
+    }
+
+    /// Locate the JSONL file for a session ID across all projects
+    pub fn find_session_file(&self, session_id: &str) -> Result<Option<PathBuf>> {
+        let file_name = format!("{}.jsonl", session_id);
+
+        for project_id in self.list_projects()? {
+            let path = self.projects_dir.join(&project_id).join(&file_name);
+            if path.is_file() {
+                return Ok(Some(path));
+            }
+        }
+
+        Ok(None)
+    }
+
     /// Read project path from JSONL files
     fn get_project_path_from_jsonl(&self, project_dir: &PathBuf) -> Result<String> {
         for entry in fs::read_dir(project_dir)? {
@@ -155,9 +470,9 @@ impl SessionDetector {
                 let file = fs::File::open(&path)?;
                 let reader = BufReader::new(file);
 
-                if let Some(Ok(first_line)) = reader.lines().next() {
-                    let entry: JsonlEntry = serde_json::from_str(&first_line)?;
-                    if let Some(cwd) = entry.cwd {
+                // The first entries may be summaries without a cwd
+                for line in reader.lines().map_while(Result::ok).take(20) {
+                    if let Ok(JsonlEntry { cwd: Some(cwd), .. }) = serde_json::from_str::<JsonlEntry>(&line) {
                         return Ok(cwd);
                     }
                 }
@@ -168,8 +483,48 @@ impl SessionDetector {
     }
 
     /// Decode project directory name to path (fallback)
+    ///
+    /// Claude replaces every non-alphanumeric character with `-`, so the name is
+    /// matched against the filesystem to recover dots, hyphens and underscores.
+    /// Falls back to reading every `-` as `/` when no existing path matches.
     fn decode_project_path(&self, encoded: &str) -> String {
-        encoded.replace('-', "/")
+        Self::resolve_encoded_path(Path::new("/"), encoded, 0)
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_else(|| encoded.replace('-', "/"))
+    }
+
+    /// Find an existing path below `dir` whose encoding is `rest`
+    fn resolve_encoded_path(dir: &Path, rest: &str, depth: usize) -> Option<PathBuf> {
+        if rest.is_empty() {
+            return Some(dir.to_path_buf());
+        }
+        if depth >= 64 {
+            return None;
+        }
+
+        for entry in fs::read_dir(dir).ok()?.filter_map(|e| e.ok()) {
+            // Follows symlinks, so projects reached through a linked directory resolve too
+            if !entry.path().is_dir() {
+                continue;
+            }
+
+            let name = entry.file_name();
+            let segment = encode_project_path(&format!("/{}", name.to_string_lossy()));
+            let Some(remaining) = rest.strip_prefix(segment.as_str()) else {
+                continue;
+            };
+
+            // The segment must end at a path separator (or the end of the name)
+            if !remaining.is_empty() && !remaining.starts_with('-') {
+                continue;
+            }
+
+            if let Some(found) = Self::resolve_encoded_path(&entry.path(), remaining, depth + 1) {
+                return Some(found);
+            }
+        }
+
+        None
     }
 
     /// Extract first user message and model from JSONL
@@ -182,42 +537,45 @@ impl SessionDetector {
         let reader = BufReader::new(file);
         let mut model = None;
 
-        for line in reader.lines() {
-            if let Ok(line) = line {
-                if let Ok(entry) = serde_json::from_str::<JsonlEntry>(&line) {
-                    // Capture model if present
-                    if model.is_none() && entry.model.is_some() {
-                        model = entry.model;
-                    }
-
-                    // Find first user message
-                    if let Some(message) = entry.message {
-                        if message.role.as_deref() == Some("user") {
-                            if let Some(content) = message.content {
-                                let content_str = match content {
-                                    serde_json::Value::String(s) => s,
-                                    serde_json::Value::Array(arr) => {
-                                        // Handle array content (e.g., text blocks)
-                                        arr.iter()
-                                            .filter_map(|v| v.get("text").and_then(|t| t.as_str()))
-                                            .collect::<Vec<_>>()
-                                            .join("\n")
-                                    }
-                                    _ => continue,
-                                };
-
-                                // Skip system caveat messages
-                                if content_str.contains("Caveat: The messages below were generated") {
-                                    continue;
-                                }
+        for line in reader.lines().map_while(Result::ok) {
+            if let Ok(entry) = serde_json::from_str::<JsonlEntry>(&line) {
+                // Capture model if present
+                if model.is_none() && entry.model.is_some() {
+                    model = entry.model;
+                }
 
-                                // Skip command output
-                                if content_str.starts_with("<command-name>") {
-                                    continue;
+                // Find first user message
+                if let Some(message) = entry.message {
+                    if message.role.as_deref() == Some("user") {
+                        if let Some(content) = message.content {
+                            let content_str = match content {
+                                serde_json::Value::String(s) => s,
+                                serde_json::Value::Array(arr) => {
+                                    // Handle array content (e.g., text blocks)
+                                    arr.iter()
+                                        .filter_map(|v| v.get("text").and_then(|t| t.as_str()))
+                                        .collect::<Vec<_>>()
+                                        .join("\n")
                                 }
+                                // Single content block object
+                                serde_json::Value::Object(obj) => match obj.get("text").and_then(|t| t.as_str()) {
+                                    Some(text) => text.to_string(),
+                                    None => continue,
+                                },
+                                _ => continue,
+                            };
+
+                            // Skip system caveat messages
+                            if content_str.contains("Caveat: The messages below were generated") {
+                                continue;
+                            }
 
-                                return (Some(content_str), model);
+                            // Skip command output
+                            if content_str.starts_with("<command-name>") {
+                                continue;
                             }
+
+                            return (Some(content_str), model);
                         }
                     }
                 }
@@ -255,6 +613,171 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_resolve_projects_dir_from_config() {
+        let claude_dir = std::env::temp_dir().join(format!("claude-config-test-{}", std::process::id()));
+        fs::create_dir_all(&claude_dir).unwrap();
+
+        // No config: default layout
+        assert_eq!(
+            SessionDetector::resolve_projects_dir(&claude_dir),
+            claude_dir.join("projects")
+        );
+
+        // Absolute override
+        fs::write(claude_dir.join("config.json"), r#"{"projectsDir": "/srv/claude/projects"}"#).unwrap();
+        assert_eq!(
+            SessionDetector::resolve_projects_dir(&claude_dir),
+            PathBuf::from("/srv/claude/projects")
+        );
+
+        // Relative override is resolved against the Claude directory
+        fs::write(claude_dir.join("config.json"), r#"{"projects_dir": "relocated"}"#).unwrap();
+        assert_eq!(
+            SessionDetector::resolve_projects_dir(&claude_dir),
+            claude_dir.join("relocated")
+        );
+
+        fs::remove_dir_all(&claude_dir).unwrap();
+    }
+
+    #[test]
+    fn test_project_stats() {
+        let claude_dir = std::env::temp_dir().join(format!("claude-stats-test-{}", std::process::id()));
+        let projects_dir = claude_dir.join("projects");
+        fs::create_dir_all(projects_dir.join("-small")).unwrap();
+        fs::create_dir_all(projects_dir.join("-large")).unwrap();
+
+        fs::write(projects_dir.join("-small/a.jsonl"), r#"{"cwd":"/small"}"#).unwrap();
+        fs::write(projects_dir.join("-large/b.jsonl"), "x".repeat(100)).unwrap();
+        fs::write(projects_dir.join("-large/c.jsonl"), "x".repeat(50)).unwrap();
+        fs::write(projects_dir.join("-large/notes.txt"), "x".repeat(1000)).unwrap();
+
+        let detector = SessionDetector { claude_dir: claude_dir.clone(), projects_dir };
+        let stats = detector.project_stats();
+
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].project_id, "-large");
+        assert_eq!(stats[0].session_count, 2);
+        assert_eq!(stats[0].total_bytes, 150);
+        assert_eq!(stats[1].decoded_path, "/small");
+
+        fs::remove_dir_all(&claude_dir).unwrap();
+    }
+
+    #[test]
+    fn test_session_summaries() {
+        let claude_dir = std::env::temp_dir().join(format!("claude-summaries-test-{}", std::process::id()));
+        let projects_dir = claude_dir.join("projects");
+        fs::create_dir_all(projects_dir.join("-one")).unwrap();
+        fs::create_dir_all(projects_dir.join("-two")).unwrap();
+
+        let transcript = |message: &str| {
+            format!(r#"{{"cwd":"/work","message":{{"role":"user","content":"{}"}}}}"#, message)
+        };
+        fs::write(projects_dir.join("-one/old.jsonl"), transcript("old")).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        fs::write(projects_dir.join("-two/mid.jsonl"), transcript("middle")).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        fs::write(projects_dir.join("-one/new.jsonl"), transcript(&format!("fix\\n{}", "y".repeat(200)))).unwrap();
+
+        let detector = SessionDetector { claude_dir: claude_dir.clone(), projects_dir };
+        let summaries = detector.session_summaries(2);
+
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].session_id, "new");
+        assert_eq!(summaries[1].session_id, "mid");
+        assert_eq!(summaries[1].project_path, "/work");
+        assert_eq!(summaries[1].first_message_preview.as_deref(), Some("middle"));
+
+        let preview = summaries[0].first_message_preview.as_deref().unwrap();
+        assert!(preview.starts_with("fix yyy"));
+        assert!(preview.ends_with('…'));
+        assert_eq!(preview.chars().count(), SUMMARY_PREVIEW_LENGTH);
+
+        fs::remove_dir_all(&claude_dir).unwrap();
+    }
+
+    #[test]
+    fn test_first_message_from_object_content() {
+        let path = std::env::temp_dir().join(format!("claude-object-content-test-{}.jsonl", std::process::id()));
+        fs::write(
+            &path,
+            [
+                r#"{"message":{"role":"user","content":{"type":"image","source":{}}}}"#,
+                r#"{"model":"claude-sonnet","message":{"role":"user","content":{"type":"text","text":"hello object"}}}"#,
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+
+        let detector = SessionDetector { claude_dir: std::env::temp_dir(), projects_dir: std::env::temp_dir() };
+        let (first_message, model) = detector.extract_first_message_and_model(&path);
+
+        assert_eq!(first_message.as_deref(), Some("hello object"));
+        assert_eq!(model.as_deref(), Some("claude-sonnet"));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_pick_running() {
+        let session = |id: &str| ClaudeSession {
+            project_id: "-srv-app".to_string(),
+            project_path: "/srv/app".to_string(),
+            ..test_session(id)
+        };
+        let running = |id: &str, path: &str| crate::RunningClaudeSession {
+            session_id: id.to_string(),
+            pid: 42,
+            project_path: path.to_string(),
+            command: "claude".to_string(),
+            terminal_info: None,
+        };
+
+        // Exact ID match
+        let live = SessionDetector::pick_running(&session("a"), Some("b"), vec![running("a", "/other")]);
+        assert_eq!(live.unwrap().pid, 42);
+
+        // Newest session of the project maps to the only process running there
+        let live = SessionDetector::pick_running(&session("new"), Some("new"), vec![running("old", "/srv/app/")]);
+        assert_eq!(live.unwrap().session_id, "new");
+
+        // Older sessions of the project are not running
+        assert!(SessionDetector::pick_running(&session("old"), Some("new"), vec![running("x", "/srv/app")]).is_none());
+    }
+
+    #[test]
+    fn test_encode_project_path() {
+        assert_eq!(encode_project_path("/home/me/my.app"), "-home-me-my-app");
+        assert_eq!(encode_project_path("/srv/foo-bar/a_b"), "-srv-foo-bar-a-b");
+        assert_eq!(encode_project_path("/home/me/.config"), "-home-me--config");
+    }
+
+    #[test]
+    fn test_decode_project_path_from_encoded_name() {
+        let detector = SessionDetector::new().unwrap();
+        let root = std::env::temp_dir().join(format!("claude-decode-test-{}", std::process::id()));
+
+        for project in ["my.project", "foo-bar/api_v2", ".hidden/x"] {
+            let path = root.join(project);
+            fs::create_dir_all(&path).unwrap();
+
+            let encoded = encode_project_path(&path.to_string_lossy());
+            assert_eq!(detector.decode_project_path(&encoded), path.to_string_lossy());
+        }
+
+        // Paths that no longer exist fall back to the naive decoding
+        assert_eq!(detector.decode_project_path("-no-such-dir-anywhere"), "/no/such/dir/anywhere");
+
+        let linked = root.join("linked.dir");
+        std::os::unix::fs::symlink(root.join("my.project"), &linked).unwrap();
+        let encoded = encode_project_path(&linked.to_string_lossy());
+        assert_eq!(detector.decode_project_path(&encoded), linked.to_string_lossy());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
     #[test]
     fn test_get_all_sessions() {
         let detector = SessionDetector::new().unwrap();
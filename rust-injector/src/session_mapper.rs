@@ -1,9 +1,10 @@
 use anyhow::Result;
-use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
 use serde::{Deserialize, Serialize};
 
+/// Most threads used to resolve processes in [`SessionMapper::map_processes`]
+const MAX_LOOKUP_THREADS: usize = 16;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RunningClaudeSession {
     pub session_id: String,
@@ -13,6 +14,53 @@ pub struct RunningClaudeSession {
     pub terminal_info: Option<TerminalInfo>,
 }
 
+impl RunningClaudeSession {
+    /// What kind of Claude this session's process is
+    pub fn kind(&self) -> crate::ProcessKind {
+        crate::ProcessKind::classify(&self.command)
+    }
+
+    /// Error out for processes that have no terminal to inject into
+    pub fn ensure_injectable(&self) -> Result<()> {
+        if self.kind() == crate::ProcessKind::Desktop {
+            anyhow::bail!(
+                "PID {} is Claude Desktop, which has no terminal to inject into. \
+                 Injection only works with the claude CLI (run it in tmux or via `claude-inject start`)",
+                self.pid
+            );
+        }
+        Ok(())
+    }
+}
+
+/// A running Claude process whose session may not be known
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaudeProcessEntry {
+    pub session_id: Option<String>,
+    pub pid: u32,
+    pub cwd: Option<String>,
+    pub command: String,
+    pub terminal_info: Option<TerminalInfo>,
+}
+
+impl ClaudeProcessEntry {
+    /// What kind of Claude this process is
+    pub fn kind(&self) -> crate::ProcessKind {
+        crate::ProcessKind::classify(&self.command)
+    }
+
+    /// The running session, if this process was mapped to one
+    pub fn into_running(self) -> Option<RunningClaudeSession> {
+        Some(RunningClaudeSession {
+            session_id: self.session_id?,
+            pid: self.pid,
+            project_path: self.cwd?,
+            command: self.command,
+            terminal_info: self.terminal_info,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TerminalInfo {
     pub terminal_pid: u32,
@@ -25,28 +73,66 @@ pub struct SessionMapper;
 impl SessionMapper {
     /// Find all running Claude processes with their session IDs
     pub fn map_sessions_to_processes() -> Result<Vec<RunningClaudeSession>> {
-        let mut mapped = Vec::new();
+        let mapped = Self::map_processes(false)?
+            .into_iter()
+            .filter_map(ClaudeProcessEntry::into_running)
+            .collect();
 
+        Ok(mapped)
+    }
+
+    /// Find all running Claude processes, mapping each to its session where possible
+    ///
+    /// With `include_unmapped`, processes whose session cannot be found (no
+    /// readable cwd, or no JSONL mentioning it) are kept with `session_id: None`.
+    pub fn map_processes(include_unmapped: bool) -> Result<Vec<ClaudeProcessEntry>> {
         // Get all running Claude processes
         let processes = crate::ProcessDetector::find_running_claude_processes()?;
 
-        for process in processes {
-            // Try to extract session info from process
-            if let Some(session_info) = Self::extract_session_from_process(&process) {
-                // Try to find the terminal
-                let terminal_info = Self::find_terminal_for_process(process.pid);
-
-                mapped.push(RunningClaudeSession {
-                    session_id: session_info.session_id,
-                    pid: process.pid,
-                    project_path: session_info.project_path,
-                    command: process.command,
-                    terminal_info,
-                });
-            }
-        }
+        // Each lookup does its own /proc and JSONL reads, so spread them over threads
+        let threads = processes.len().clamp(1, MAX_LOOKUP_THREADS);
+        let mut entries: Vec<(usize, ClaudeProcessEntry)> = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|worker| {
+                    let processes = &processes;
+                    scope.spawn(move || {
+                        processes
+                            .iter()
+                            .enumerate()
+                            .skip(worker)
+                            .step_by(threads)
+                            .map(|(i, process)| (i, Self::lookup_process(process)))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
 
-        Ok(mapped)
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("process lookup thread panicked"))
+                .collect()
+        });
+
+        entries.sort_by_key(|(i, _)| *i);
+        Ok(entries
+            .into_iter()
+            .map(|(_, entry)| entry)
+            .filter(|entry| include_unmapped || entry.session_id.is_some())
+            .collect())
+    }
+
+    /// Resolve a process's working directory, session and terminal
+    fn lookup_process(process: &crate::RunningProcess) -> ClaudeProcessEntry {
+        let cwd = crate::ProcessDetector::get_process_cwd(process.pid);
+        let session_id = cwd.as_deref().and_then(Self::find_session_for_cwd);
+
+        ClaudeProcessEntry {
+            session_id,
+            pid: process.pid,
+            cwd,
+            command: process.command.clone(),
+            terminal_info: Self::find_terminal_for_process(process.pid),
+        }
     }
 
     /// Find a specific session by ID
@@ -55,39 +141,44 @@ impl SessionMapper {
         Ok(sessions.into_iter().find(|s| s.session_id == session_id))
     }
 
-    /// Extract session information from process command line
-    fn extract_session_from_process(process: &crate::RunningProcess) -> Option<SessionInfo> {
-        // Method 1: Check /proc/PID/cwd for working directory
-        #[cfg(target_os = "linux")]
-        {
-            let cwd = crate::ProcessDetector::get_process_cwd(process.pid)?;
+    /// Find the single running session whose project path matches `path`
+    ///
+    /// Errors when no session or more than one session runs in that directory.
+    pub fn find_session_by_project_path(path: &str) -> Result<RunningClaudeSession> {
+        let target = normalize_path(path);
 
-            // Try to find session files in ~/.claude/projects/
-            let session_id = Self::find_session_for_cwd(&cwd)?;
+        let mut matches: Vec<RunningClaudeSession> = Self::map_sessions_to_processes()?
+            .into_iter()
+            .filter(|s| normalize_path(&s.project_path) == target)
+            .collect();
 
-            Some(SessionInfo {
-                session_id,
-                project_path: cwd,
-            })
-        }
-
-        #[cfg(not(target_os = "linux"))]
-        {
-            None
+        match matches.len() {
+            0 => Err(anyhow::Error::new(crate::InjectorError::SessionNotFound(target.clone()))
+                .context(format!("No running Claude session found in {}", target))),
+            1 => Ok(matches.remove(0)),
+            n => {
+                let ids: Vec<String> = matches.iter().map(|s| format!("{} (PID {})", s.session_id, s.pid)).collect();
+                anyhow::bail!(
+                    "{} running Claude sessions found in {}: {}. Use --id to pick one",
+                    n,
+                    target,
+                    ids.join(", ")
+                )
+            }
         }
     }
 
     /// Find session ID for a given working directory
     fn find_session_for_cwd(cwd: &str) -> Option<String> {
-        let home = dirs::home_dir()?;
-        let claude_dir = home.join(".claude/projects");
+        let detector = crate::SessionDetector::new().ok()?;
+        let projects_dir = detector.projects_dir();
 
-        if !claude_dir.exists() {
+        if !projects_dir.exists() {
             return None;
         }
 
         // Read all project directories
-        for project_entry in fs::read_dir(&claude_dir).ok()? {
+        for project_entry in fs::read_dir(projects_dir).ok()? {
             let project_path = project_entry.ok()?.path();
 
             // Read all session files
@@ -119,16 +210,7 @@ impl SessionMapper {
     #[cfg(target_os = "linux")]
     fn find_terminal_for_process(pid: u32) -> Option<TerminalInfo> {
         // Read /proc/PID/status to get parent PID
-        let status_path = format!("/proc/{}/status", pid);
-        let status = fs::read_to_string(&status_path).ok()?;
-
-        let ppid = status
-            .lines()
-            .find(|line| line.starts_with("PPid:"))?
-            .split_whitespace()
-            .nth(1)?
-            .parse::<u32>()
-            .ok()?;
+        let ppid = crate::ProcessDetector::get_parent_pid(pid)?;
 
         // Read parent process command
         let parent_cmd_path = format!("/proc/{}/cmdline", ppid);
@@ -170,16 +252,40 @@ impl SessionMapper {
     }
 }
 
-#[derive(Debug)]
-struct SessionInfo {
-    session_id: String,
-    project_path: String,
+/// Expand `~`, resolve symlinks where possible and drop trailing slashes
+pub fn normalize_path(path: &str) -> String {
+    let expanded = match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .map(|home| home.join(rest).to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string()),
+        None => path.to_string(),
+    };
+
+    let resolved = fs::canonicalize(&expanded)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or(expanded);
+
+    if resolved.len() > 1 {
+        resolved.trim_end_matches('/').to_string()
+    } else {
+        resolved
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path("/no/such/dir/"), "/no/such/dir");
+        assert_eq!(normalize_path("/"), "/");
+
+        let home = dirs::home_dir().unwrap();
+        let home = fs::canonicalize(&home).unwrap_or(home);
+        assert_eq!(normalize_path("~/"), home.to_string_lossy());
+    }
+
     #[test]
     fn test_map_sessions() {
         let sessions = SessionMapper::map_sessions_to_processes().unwrap();
@@ -189,5 +295,10 @@ mod tests {
                 println!("  Terminal: {} (PID: {})", term.terminal_name, term.terminal_pid);
             }
         }
+
+        assert!(SessionMapper::map_processes(false).unwrap().iter().all(|e| e.session_id.is_some()));
+        for entry in SessionMapper::map_processes(true).unwrap() {
+            println!("Process: {} -> {:?}", entry.pid, entry.session_id);
+        }
     }
 }
@@ -0,0 +1,328 @@
+use ab_glyph::{Font, FontVec, PxScale, ScaleFont};
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+/// Environment variable naming a monospace TTF/OTF font to render snapshots with
+pub const SNAPSHOT_FONT_ENV: &str = "CLAUDE_SNAPSHOT_FONT";
+
+/// Monospace fonts tried, in order, when [`SNAPSHOT_FONT_ENV`] is not set
+const MONOSPACE_FONTS: &[&str] = &[
+    "/usr/share/fonts/truetype/dejavu/DejaVuSansMono.ttf",
+    "/usr/share/fonts/TTF/DejaVuSansMono.ttf",
+    "/usr/share/fonts/dejavu-sans-mono-fonts/DejaVuSansMono.ttf",
+    "/usr/share/fonts/truetype/liberation/LiberationMono-Regular.ttf",
+    "/System/Library/Fonts/Supplemental/Courier New.ttf",
+];
+
+/// Glyph height in pixels
+const FONT_SIZE: f32 = 16.0;
+
+/// Blank border around the rendered pane, in pixels
+const PADDING: u32 = 8;
+
+const DEFAULT_FG: [u8; 3] = [204, 204, 204];
+const DEFAULT_BG: [u8; 3] = [30, 30, 30];
+
+/// Colour as set by an SGR escape sequence
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TermColor {
+    #[default]
+    Default,
+    /// Entry of the 256-colour palette
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+/// One character of a pane with its resolved colours
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cell {
+    pub ch: char,
+    pub fg: [u8; 3],
+    pub bg: [u8; 3],
+}
+
+/// SGR state while walking captured pane text
+#[derive(Debug, Clone, Copy, Default)]
+struct Style {
+    fg: TermColor,
+    bg: TermColor,
+    bold: bool,
+    reverse: bool,
+}
+
+impl Style {
+    fn apply(&mut self, params: &[u16]) {
+        if params.is_empty() {
+            *self = Style::default();
+            return;
+        }
+
+        let mut params = params.iter().copied();
+        while let Some(param) = params.next() {
+            match param {
+                0 => *self = Style::default(),
+                1 => self.bold = true,
+                22 => self.bold = false,
+                7 => self.reverse = true,
+                27 => self.reverse = false,
+                30..=37 => self.fg = TermColor::Indexed((param - 30) as u8),
+                90..=97 => self.fg = TermColor::Indexed((param - 90 + 8) as u8),
+                39 => self.fg = TermColor::Default,
+                40..=47 => self.bg = TermColor::Indexed((param - 40) as u8),
+                100..=107 => self.bg = TermColor::Indexed((param - 100 + 8) as u8),
+                49 => self.bg = TermColor::Default,
+                38 => self.fg = Self::extended_color(&mut params),
+                48 => self.bg = Self::extended_color(&mut params),
+                _ => {}
+            }
+        }
+    }
+
+    /// Colour following a `38`/`48` parameter: `5;n` or `2;r;g;b`
+    fn extended_color(params: &mut impl Iterator<Item = u16>) -> TermColor {
+        match params.next() {
+            Some(5) => params.next().map_or(TermColor::Default, |n| TermColor::Indexed(n as u8)),
+            Some(2) => {
+                let mut channel = || params.next().unwrap_or(0) as u8;
+                TermColor::Rgb(channel(), channel(), channel())
+            }
+            _ => TermColor::Default,
+        }
+    }
+
+    fn cell(&self, ch: char) -> Cell {
+        // Bold brightens the eight basic colours, as most terminals do
+        let fg = match self.fg {
+            TermColor::Indexed(n) if self.bold && n < 8 => TermColor::Indexed(n + 8),
+            other => other,
+        };
+        let (fg, bg) = (resolve(fg, DEFAULT_FG), resolve(self.bg, DEFAULT_BG));
+
+        if self.reverse {
+            Cell { ch, fg: bg, bg: fg }
+        } else {
+            Cell { ch, fg, bg }
+        }
+    }
+}
+
+/// RGB value of a colour under the xterm palette
+fn resolve(color: TermColor, default: [u8; 3]) -> [u8; 3] {
+    const BASIC: [[u8; 3]; 16] = [
+        [0, 0, 0], [205, 0, 0], [0, 205, 0], [205, 205, 0],
+        [0, 0, 238], [205, 0, 205], [0, 205, 205], [229, 229, 229],
+        [127, 127, 127], [255, 0, 0], [0, 255, 0], [255, 255, 0],
+        [92, 92, 255], [255, 0, 255], [0, 255, 255], [255, 255, 255],
+    ];
+    const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    match color {
+        TermColor::Default => default,
+        TermColor::Rgb(r, g, b) => [r, g, b],
+        TermColor::Indexed(n @ 0..=15) => BASIC[n as usize],
+        TermColor::Indexed(n @ 16..=231) => {
+            let n = n - 16;
+            [CUBE[(n / 36) as usize], CUBE[(n / 6 % 6) as usize], CUBE[(n % 6) as usize]]
+        }
+        TermColor::Indexed(n) => {
+            let level = 8 + 10 * (n - 232);
+            [level, level, level]
+        }
+    }
+}
+
+/// A pane's contents as rows of coloured cells, for status reports
+#[derive(Debug, Clone, Default)]
+pub struct PaneSnapshot {
+    pub lines: Vec<Vec<Cell>>,
+}
+
+impl PaneSnapshot {
+    /// Parse pane text captured with escape sequences (`capture-pane -e`)
+    ///
+    /// Colour and reverse-video SGR sequences are interpreted; other escape
+    /// sequences are dropped.
+    pub fn parse(captured: &str) -> Self {
+        let mut style = Style::default();
+        let mut lines = Vec::new();
+
+        for text in captured.split('\n') {
+            let mut line = Vec::new();
+            let mut chars = text.chars().peekable();
+
+            while let Some(ch) = chars.next() {
+                if ch != '\x1b' {
+                    if !ch.is_control() {
+                        line.push(style.cell(ch));
+                    }
+                    continue;
+                }
+
+                match chars.next() {
+                    Some('[') => {
+                        let mut body = String::new();
+                        for c in chars.by_ref() {
+                            if ('\x40'..='\x7e').contains(&c) {
+                                if c == 'm' {
+                                    let params: Vec<u16> = body
+                                        .split([';', ':'])
+                                        .filter(|p| !p.is_empty())
+                                        .filter_map(|p| p.parse().ok())
+                                        .collect();
+                                    style.apply(&params);
+                                }
+                                break;
+                            }
+                            body.push(c);
+                        }
+                    }
+                    // OSC (e.g. hyperlinks), ended by BEL or ESC \
+                    Some(']') => {
+                        while let Some(c) = chars.next() {
+                            if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                                break;
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            lines.push(line);
+        }
+
+        PaneSnapshot { lines }
+    }
+
+    /// The snapshot's characters without colours
+    pub fn plain_text(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| line.iter().map(|cell| cell.ch).collect::<String>().trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Write the snapshot to `out`, returning the path actually written
+    ///
+    /// Renders a PNG with a monospace font from [`SNAPSHOT_FONT_ENV`] or a
+    /// known system location. Without a usable font, the plain text is written
+    /// next to `out` with a `.txt` extension instead.
+    pub fn save(&self, out: &Path) -> Result<PathBuf> {
+        match Self::load_font()? {
+            Some(font) => {
+                self.render_png(&font, out)?;
+                Ok(out.to_path_buf())
+            }
+            None => {
+                let fallback = out.with_extension("txt");
+                log::warn!(
+                    "No monospace font found (set {}); writing plain text to {}",
+                    SNAPSHOT_FONT_ENV,
+                    fallback.display()
+                );
+                std::fs::write(&fallback, self.plain_text() + "\n")
+                    .context(format!("Failed to write {}", fallback.display()))?;
+                Ok(fallback)
+            }
+        }
+    }
+
+    fn load_font() -> Result<Option<FontVec>> {
+        if let Some(path) = std::env::var_os(SNAPSHOT_FONT_ENV) {
+            let path = PathBuf::from(path);
+            let data = std::fs::read(&path).context(format!("Failed to read font {}", path.display()))?;
+            return Ok(Some(
+                FontVec::try_from_vec(data).context(format!("{} is not a usable font", path.display()))?,
+            ));
+        }
+
+        Ok(MONOSPACE_FONTS
+            .iter()
+            .filter_map(|path| std::fs::read(path).ok())
+            .find_map(|data| FontVec::try_from_vec(data).ok()))
+    }
+
+    fn render_png(&self, font: &FontVec, out: &Path) -> Result<()> {
+        let scale = PxScale::from(FONT_SIZE);
+        let scaled = font.as_scaled(scale);
+        let cell_width = scaled.h_advance(font.glyph_id('M')).ceil() as u32;
+        let cell_height = scaled.height().ceil() as u32;
+
+        let columns = self.lines.iter().map(Vec::len).max().unwrap_or(0).max(1) as u32;
+        let rows = self.lines.len().max(1) as u32;
+        let width = columns * cell_width + 2 * PADDING;
+        let height = rows * cell_height + 2 * PADDING;
+
+        let mut pixels = DEFAULT_BG.repeat((width * height) as usize);
+        let mut put = |x: u32, y: u32, color: [u8; 3], coverage: f32| {
+            if x >= width || y >= height {
+                return;
+            }
+            let at = ((y * width + x) * 3) as usize;
+            for (channel, value) in color.iter().enumerate() {
+                let base = pixels[at + channel] as f32;
+                pixels[at + channel] = (base + (*value as f32 - base) * coverage).round() as u8;
+            }
+        };
+
+        for (row, line) in self.lines.iter().enumerate() {
+            let top = PADDING + row as u32 * cell_height;
+            for (column, cell) in line.iter().enumerate() {
+                let left = PADDING + column as u32 * cell_width;
+
+                if cell.bg != DEFAULT_BG {
+                    for y in top..top + cell_height {
+                        for x in left..left + cell_width {
+                            put(x, y, cell.bg, 1.0);
+                        }
+                    }
+                }
+
+                let glyph = font
+                    .glyph_id(cell.ch)
+                    .with_scale_and_position(scale, ab_glyph::point(left as f32, top as f32 + scaled.ascent()));
+                if let Some(outline) = font.outline_glyph(glyph) {
+                    let bounds = outline.px_bounds();
+                    outline.draw(|x, y, coverage| {
+                        let (x, y) = (bounds.min.x as i64 + x as i64, bounds.min.y as i64 + y as i64);
+                        if x >= 0 && y >= 0 {
+                            put(x as u32, y as u32, cell.fg, coverage.min(1.0));
+                        }
+                    });
+                }
+            }
+        }
+
+        let file = File::create(out).context(format!("Failed to create {}", out.display()))?;
+        let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&pixels))
+            .context(format!("Failed to write PNG {}", out.display()))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sgr_colors() {
+        let captured = "\x1b[31mred\x1b[0m \x1b[1;32mok\x1b[39m\n\x1b[38;5;196;48;2;1;2;3mx\x1b]8;;http://a\x07\x1b[7my";
+        let snapshot = PaneSnapshot::parse(captured);
+
+        assert_eq!(snapshot.plain_text(), "red ok\nxy");
+        assert_eq!(snapshot.lines[0][0].fg, [205, 0, 0]);
+        assert_eq!(snapshot.lines[0][3].fg, DEFAULT_FG);
+        assert_eq!(snapshot.lines[0][4].fg, [0, 255, 0]);
+        assert_eq!(snapshot.lines[1][0].fg, [255, 0, 0]);
+        assert_eq!(snapshot.lines[1][0].bg, [1, 2, 3]);
+        assert_eq!(snapshot.lines[1][1], Cell { ch: 'y', fg: [1, 2, 3], bg: [255, 0, 0] });
+    }
+}
@@ -0,0 +1,314 @@
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Contents of state files that could not be written anywhere, keyed by file name
+static MEMORY: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+
+/// Warnings already printed in this process
+static WARNED: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+
+/// A JSON state file kept in the home directory
+///
+/// When the home directory is read-only or missing, state moves to a private
+/// per-user directory (`$XDG_RUNTIME_DIR/claude-injector`, else
+/// `$TMPDIR/claude-injector-<uid>`), and if that fails too the contents are
+/// kept in memory for the rest of the process, so a single invocation can
+/// still spawn and inject.
+pub struct StateFile {
+    name: String,
+    candidates: Vec<PathBuf>,
+}
+
+impl StateFile {
+    /// State file `name` in the home directory, with a private per-user directory as fallback
+    pub fn in_home(name: &str) -> Self {
+        let mut candidates = Vec::new();
+        if let Some(home) = dirs::home_dir() {
+            candidates.push(home.join(name));
+        }
+        if let Some(dir) = fallback_dir() {
+            candidates.push(dir.join(name));
+        }
+
+        Self::with_candidates(name, candidates)
+    }
+
+    fn with_candidates(name: &str, candidates: Vec<PathBuf>) -> Self {
+        Self {
+            name: name.to_string(),
+            candidates,
+        }
+    }
+
+    /// Read the copy that [`StateFile::write`] would write, or `None` if there is none yet
+    ///
+    /// A fallback copy is only read while the locations before it cannot be
+    /// written, and only if it belongs to the current user.
+    pub fn read(&self) -> Result<Option<String>> {
+        if let Some(content) = memory().lock().unwrap().get(&self.name) {
+            return Ok(Some(content.clone()));
+        }
+
+        let Some((index, path)) = self
+            .candidates
+            .iter()
+            .enumerate()
+            .find(|(_, path)| writable(path))
+            .or_else(|| self.candidates.first().map(|path| (0, path)))
+        else {
+            return Ok(None);
+        };
+
+        let metadata = match fs::metadata(path) {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        if index > 0 && metadata.uid() != unsafe { libc::getuid() } {
+            warn_once(format!("⚠️  Ignoring {}: owned by another user", path.display()));
+            return Ok(None);
+        }
+
+        Ok(Some(fs::read_to_string(path)?))
+    }
+
+    /// Write to the first location that accepts it, or keep the contents in memory
+    pub fn write(&self, content: &str) -> Result<()> {
+        for (index, path) in self.candidates.iter().enumerate() {
+            match write_atomic(path, content) {
+                Ok(()) => {
+                    if index == 0 {
+                        // A fallback copy would shadow this one once it is older
+                        for stale in &self.candidates[1..] {
+                            let _ = fs::remove_file(stale);
+                        }
+                    } else {
+                        warn_once(format!(
+                            "⚠️  Cannot write {}; using {} instead",
+                            self.candidates[0].display(),
+                            path.display()
+                        ));
+                    }
+                    return Ok(());
+                }
+                Err(e) => log::debug!("Cannot write {}: {}", path.display(), e),
+            }
+        }
+
+        warn_once(format!(
+            "⚠️  Cannot write {} anywhere; keeping it in memory for this run only",
+            self.name
+        ));
+        memory().lock().unwrap().insert(self.name.clone(), content.to_string());
+        Ok(())
+    }
+
+    /// Hold an exclusive lock for a read-modify-write of this file
+    ///
+    /// The lock lives beside the first location that can hold one; when none
+    /// can, the contents are in memory anyway and the lock is a no-op.
+    pub fn lock(&self) -> Result<FileLock> {
+        for path in &self.candidates {
+            match FileLock::acquire(path) {
+                Ok(lock) => return Ok(lock),
+                Err(e) => log::debug!("Cannot lock {}: {}", path.display(), e),
+            }
+        }
+        Ok(FileLock { _file: None })
+    }
+}
+
+/// An advisory `flock` on `<path>.lock`, released when dropped
+///
+/// Only processes that take the same lock are kept out, which is every
+/// read-modify-write of a state file in this crate.
+pub struct FileLock {
+    _file: Option<fs::File>,
+}
+
+impl FileLock {
+    /// Block until the lock for `path` is ours
+    pub fn acquire(path: &Path) -> std::io::Result<Self> {
+        let mut lock_path = path.as_os_str().to_owned();
+        lock_path.push(".lock");
+
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(lock_path)?;
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        Ok(Self { _file: Some(file) })
+    }
+}
+
+/// Replace `path` with `content` through a temporary file and a rename
+///
+/// Readers see either the old or the new contents, never a partial write.
+pub fn write_atomic(path: &Path, content: &str) -> std::io::Result<()> {
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(format!(".tmp.{}", std::process::id()));
+    let tmp_path = PathBuf::from(tmp_path);
+
+    fs::write(&tmp_path, content)?;
+    fs::rename(&tmp_path, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp_path);
+    })
+}
+
+/// Whether a state file can be (re)written at `path`, which replaces it by rename
+fn writable(path: &Path) -> bool {
+    let Some(dir) = path.parent() else {
+        return false;
+    };
+    let Ok(dir) = std::ffi::CString::new(dir.as_os_str().as_bytes()) else {
+        return false;
+    };
+    unsafe { libc::access(dir.as_ptr(), libc::W_OK | libc::X_OK) == 0 }
+}
+
+/// Private per-user directory for state that cannot live in the home directory
+fn fallback_dir() -> Option<PathBuf> {
+    let dir = match std::env::var_os("XDG_RUNTIME_DIR").map(PathBuf::from) {
+        Some(runtime) if runtime.is_absolute() => runtime.join("claude-injector"),
+        _ => std::env::temp_dir().join(format!("claude-injector-{}", unsafe { libc::getuid() })),
+    };
+
+    match private_dir(&dir) {
+        Ok(()) => Some(dir),
+        Err(e) => {
+            log::debug!("Not using {} for state: {}", dir.display(), e);
+            None
+        }
+    }
+}
+
+/// Create `dir` with mode 0700, or check that an existing one is ours and not shared
+fn private_dir(dir: &Path) -> std::io::Result<()> {
+    match fs::DirBuilder::new().mode(0o700).create(dir) {
+        Ok(()) => return Ok(()),
+        Err(e) if e.kind() != std::io::ErrorKind::AlreadyExists => return Err(e),
+        Err(_) => {}
+    }
+
+    // symlink_metadata, so a planted link to someone else's directory is not followed
+    let metadata = fs::symlink_metadata(dir)?;
+    if !metadata.is_dir() || metadata.uid() != unsafe { libc::getuid() } {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            "not a directory owned by the current user",
+        ));
+    }
+    if metadata.permissions().mode() & 0o077 != 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            "accessible by other users",
+        ));
+    }
+    Ok(())
+}
+
+fn memory() -> &'static Mutex<HashMap<String, String>> {
+    MEMORY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn warn_once(message: String) {
+    let warned = WARNED.get_or_init(|| Mutex::new(HashSet::new()));
+    if warned.lock().unwrap().insert(message.clone()) {
+        log::warn!("{}", message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_falls_back_to_next_location_then_memory() {
+        let name = format!("claude-state-file-test-{}.json", std::process::id());
+        let unwritable = PathBuf::from("/nonexistent-dir").join(&name);
+        let fallback = std::env::temp_dir().join(&name);
+
+        let file = StateFile::with_candidates(&name, vec![unwritable.clone(), fallback.clone()]);
+        assert!(file.read().unwrap().is_none());
+        file.write("{\"on\": \"disk\"}").unwrap();
+        assert_eq!(fs::read_to_string(&fallback).unwrap(), "{\"on\": \"disk\"}");
+        assert_eq!(file.read().unwrap().unwrap(), "{\"on\": \"disk\"}");
+        fs::remove_file(&fallback).unwrap();
+
+        let file = StateFile::with_candidates(&name, vec![unwritable]);
+        file.write("{\"in\": \"memory\"}").unwrap();
+        assert_eq!(file.read().unwrap().unwrap(), "{\"in\": \"memory\"}");
+        assert!(!fallback.exists());
+    }
+
+    #[test]
+    fn test_fallback_copy_is_ignored_while_home_is_writable() {
+        let name = format!("claude-state-file-shadow-test-{}.json", std::process::id());
+        let dir = std::env::temp_dir().join(format!("claude-state-file-shadow-{}", std::process::id()));
+        let (home, fallback) = (dir.join("home"), dir.join("fallback"));
+        fs::create_dir_all(&home).unwrap();
+        fs::create_dir_all(&fallback).unwrap();
+
+        // Newer than the home copy, as a planted or leftover file would be
+        fs::write(home.join(&name), "home").unwrap();
+        fs::write(fallback.join(&name), "planted").unwrap();
+
+        let file = StateFile::with_candidates(&name, vec![home.join(&name), fallback.join(&name)]);
+        assert_eq!(file.read().unwrap().unwrap(), "home");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_private_dir_rejects_shared_directories() {
+        let dir = std::env::temp_dir().join(format!("claude-state-file-private-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        private_dir(&dir).unwrap();
+        assert_eq!(fs::metadata(&dir).unwrap().permissions().mode() & 0o777, 0o700);
+        private_dir(&dir).unwrap();
+
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o777)).unwrap();
+        assert!(private_dir(&dir).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_lock_serialises_read_modify_write() {
+        let name = format!("claude-state-file-lock-test-{}.json", std::process::id());
+        let path = std::env::temp_dir().join(&name);
+        let file = std::sync::Arc::new(StateFile::with_candidates(&name, vec![path.clone()]));
+        file.write("0").unwrap();
+
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let file = file.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..10 {
+                        let _lock = file.lock().unwrap();
+                        let count: u32 = file.read().unwrap().unwrap().parse().unwrap();
+                        file.write(&(count + 1).to_string()).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        assert_eq!(file.read().unwrap().unwrap(), "80");
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(format!("{}.lock", path.display())).unwrap();
+    }
+}
@@ -0,0 +1,244 @@
+use anyhow::Result;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use crate::config::InjectorConfig;
+use crate::events::WorkerEvent;
+use crate::tmux_spawner::TmuxSpawner;
+use crate::worker_registry::{WorkerRegistry, WorkerStatus};
+
+/// Default number of trailing pane lines hashed for idle detection
+pub const DEFAULT_IDLE_LINES: usize = 20;
+
+/// Default consecutive unchanged observations before a worker counts as idle
+pub const DEFAULT_IDLE_STABLE_CYCLES: u32 = 2;
+
+/// Default seconds between supervisor passes
+pub const DEFAULT_IDLE_POLL_INTERVAL_SECS: u64 = 10;
+
+/// Tells idle workers from working ones by hashing the tail of their pane
+pub struct IdleDetector {
+    lines: usize,
+    stable_cycles: u32,
+    /// worker name -> (last pane hash, consecutive unchanged observations)
+    observed: HashMap<String, (u64, u32)>,
+}
+
+impl IdleDetector {
+    pub fn new(lines: usize, stable_cycles: u32) -> Self {
+        Self {
+            lines,
+            stable_cycles,
+            observed: HashMap::new(),
+        }
+    }
+
+    /// Number of pane lines to capture per observation
+    pub fn lines(&self) -> usize {
+        self.lines
+    }
+
+    /// Record a pane snapshot and return the status it implies, if any yet
+    pub fn observe(&mut self, name: &str, pane: &str) -> Option<WorkerStatus> {
+        let mut hasher = DefaultHasher::new();
+        pane.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        match self.observed.get_mut(name) {
+            None => {
+                self.observed.insert(name.to_string(), (hash, 0));
+                None
+            }
+            Some((last_hash, unchanged)) if *last_hash == hash => {
+                *unchanged += 1;
+                if *unchanged >= self.stable_cycles {
+                    Some(WorkerStatus::Idle)
+                } else {
+                    None
+                }
+            }
+            Some(entry) => {
+                *entry = (hash, 0);
+                Some(WorkerStatus::Working)
+            }
+        }
+    }
+
+    /// Drop tracking state for a worker
+    pub fn forget(&mut self, name: &str) {
+        self.observed.remove(name);
+    }
+}
+
+impl Default for IdleDetector {
+    fn default() -> Self {
+        Self::new(DEFAULT_IDLE_LINES, DEFAULT_IDLE_STABLE_CYCLES)
+    }
+}
+
+/// Follows worker panes across captures and yields only lines not seen before
+#[derive(Default)]
+pub struct PaneTail {
+    /// worker name -> lines from the previous capture
+    previous: HashMap<String, Vec<String>>,
+}
+
+impl PaneTail {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a capture and return the lines that scrolled in since the last one
+    ///
+    /// The longest suffix of the previous capture that starts the new capture is
+    /// treated as already printed. The first capture of a worker is returned whole.
+    pub fn update(&mut self, name: &str, pane: &str) -> Vec<String> {
+        let current: Vec<String> = pane.lines().map(|line| line.to_string()).collect();
+        let previous = self.previous.get(name).map(Vec::as_slice).unwrap_or(&[]);
+
+        let overlap = (0..=previous.len().min(current.len()))
+            .rev()
+            .find(|&k| previous[previous.len() - k..] == current[..k])
+            .unwrap_or(0);
+
+        let fresh = current[overlap..].to_vec();
+        self.previous.insert(name.to_string(), current);
+        fresh
+    }
+
+    /// Drop tracking state for a worker
+    pub fn forget(&mut self, name: &str) {
+        self.previous.remove(name);
+    }
+}
+
+/// Periodic housekeeping for the worker registry
+///
+/// Each tick stops workers past their TTL, reconciles the registry against
+/// live tmux sessions, flags stuck workers, updates idle/working statuses
+/// and reaps stopped workers.
+pub struct WorkerSupervisor {
+    idle: IdleDetector,
+    starting_timeout_secs: u64,
+}
+
+impl WorkerSupervisor {
+    pub fn new(config: &InjectorConfig) -> Self {
+        Self {
+            idle: IdleDetector::new(config.idle_lines.max(1), config.idle_stable_cycles.max(1)),
+            starting_timeout_secs: config.starting_timeout_secs,
+        }
+    }
+
+    /// Run one housekeeping pass, returning the events it produced
+    pub fn tick(&mut self) -> Result<Vec<WorkerEvent>> {
+        let mut registry = WorkerRegistry::load()?;
+        let mut events = Vec::new();
+
+        for name in registry.sweep_expired()? {
+            self.idle.forget(&name);
+            events.push(WorkerEvent::Expired { name });
+        }
+
+        // Names must match registry keys before statuses are snapshotted by name
+        registry.repair_mismatches()?;
+
+        let previous: HashMap<String, WorkerStatus> = registry
+            .list_all()
+            .into_iter()
+            .map(|w| (w.name.clone(), w.status.clone()))
+            .collect();
+
+        for name in registry.reconcile()? {
+            events.push(WorkerEvent::StatusChanged {
+                from: previous[&name].clone(),
+                name,
+                to: WorkerStatus::Stopped,
+            });
+        }
+
+        for name in registry.sweep_stuck_starting(self.starting_timeout_secs)? {
+            events.push(WorkerEvent::StatusChanged {
+                name,
+                from: WorkerStatus::Starting,
+                to: WorkerStatus::Error,
+            });
+        }
+
+        let active: Vec<(String, String, WorkerStatus)> = registry
+            .list_all()
+            .into_iter()
+            .filter(|w| {
+                matches!(
+                    w.status,
+                    WorkerStatus::Ready | WorkerStatus::Working | WorkerStatus::Idle
+                )
+            })
+            .map(|w| (w.name.clone(), w.tmux_session.clone(), w.status.clone()))
+            .collect();
+
+        for (name, tmux_session, status) in active {
+            let pane = match TmuxSpawner::capture_pane(&tmux_session, Some(self.idle.lines())) {
+                Ok(pane) => pane,
+                Err(e) => {
+                    log::warn!("Failed to capture pane for worker {}: {}", name, e);
+                    continue;
+                }
+            };
+
+            if let Some(observed) = self.idle.observe(&name, &pane) {
+                if observed != status {
+                    registry.update_status(&name, observed.clone())?;
+                    events.push(WorkerEvent::StatusChanged {
+                        name,
+                        from: status,
+                        to: observed,
+                    });
+                }
+            }
+        }
+
+        for name in registry.reap_stopped()? {
+            log::info!("Reaped stopped worker {}", name);
+            self.idle.forget(&name);
+            events.push(WorkerEvent::Reaped { name });
+        }
+
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idle_detector() {
+        let mut detector = IdleDetector::new(20, 2);
+
+        // First sighting establishes a baseline
+        assert_eq!(detector.observe("w", "thinking..."), None);
+
+        // Output changed -> working
+        assert_eq!(detector.observe("w", "thinking... done"), Some(WorkerStatus::Working));
+
+        // Unchanged once is not enough, twice is idle
+        assert_eq!(detector.observe("w", "thinking... done"), None);
+        assert_eq!(detector.observe("w", "thinking... done"), Some(WorkerStatus::Idle));
+    }
+
+    #[test]
+    fn test_pane_tail() {
+        let mut tail = PaneTail::new();
+
+        assert_eq!(tail.update("w", "a\nb\nc"), vec!["a", "b", "c"]);
+        assert!(tail.update("w", "a\nb\nc").is_empty());
+
+        // Two lines scrolled in
+        assert_eq!(tail.update("w", "c\nd\ne"), vec!["d", "e"]);
+
+        // Unrelated content is printed in full
+        assert_eq!(tail.update("w", "x\ny"), vec!["x", "y"]);
+    }
+}
@@ -1,6 +1,342 @@
 use anyhow::{Context, Result};
 use std::process::Command;
 
+use crate::config::InjectorConfig;
+use crate::error::InjectorError;
+
+/// Format string passed to `tmux display-message` to describe a pane
+const PANE_INFO_FORMAT: &str = "#{pane_id}\t#{pane_pid}\t#{pane_current_command}\t#{pane_in_mode}\t#{pane_current_path}";
+
+/// Pane commands that indicate Claude is running (the CLI may show up as node)
+const CLAUDE_PANE_COMMANDS: &[&str] = &["claude", "node"];
+
+/// tmux errors meaning the targeted session, window or pane no longer exists
+const SESSION_GONE_ERRORS: &[&str] = &["can't find session", "can't find window", "can't find pane", "no server running"];
+
+/// Snapshot of a tmux pane's state
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaneInfo {
+    pub pane_id: String,
+    pub pane_pid: u32,
+    pub current_command: String,
+    /// True when the pane is in copy-mode (or another mode) and would swallow keys
+    pub in_mode: bool,
+    /// Working directory of the pane's process
+    pub current_path: String,
+}
+
+/// Status-line text Claude shows only while a response is in progress
+const GENERATING_MARKERS: &[&str] = &["esc to interrupt"];
+
+/// Spinner glyphs Claude cycles through in front of its "Thinking…" status line
+///
+/// Claude's plain `*` frame is left out: it also starts markdown bullets, and
+/// that frame is still caught by [`GENERATING_MARKERS`].
+const SPINNER_GLYPHS: &[char] = &['·', '✢', '✳', '✶', '✻', '✽'];
+
+/// Environment that makes Claude read `CLAUDE.md` from `--add-dir` directories
+pub const PROJECT_ROOT_ENV: (&str, &str) = ("CLAUDE_CODE_ADDITIONAL_DIRECTORIES_CLAUDE_MD", "1");
+
+/// How many lines from the bottom of a pane are checked for a generating indicator
+const PANE_STATE_LINES: usize = 15;
+
+/// Whether Claude is busy producing a response in a pane
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaneState {
+    /// Claude is waiting for input
+    Idle,
+    /// Claude is thinking or streaming a response
+    Generating,
+}
+
+impl PaneState {
+    /// Classify captured pane text by looking for Claude's spinner line near the bottom
+    pub fn detect(pane: &str) -> Self {
+        let lines: Vec<&str> = pane.lines().collect();
+        let start = lines.len().saturating_sub(PANE_STATE_LINES);
+
+        let generating = lines[start..].iter().any(|line| {
+            let line = line.trim();
+            let lower = line.to_lowercase();
+            GENERATING_MARKERS.iter().any(|marker| lower.contains(marker))
+                || (line.starts_with(SPINNER_GLYPHS) && line.contains('…'))
+        });
+
+        if generating {
+            PaneState::Generating
+        } else {
+            PaneState::Idle
+        }
+    }
+}
+
+/// Marker Claude is asked to echo back during an injection self-test
+pub const SELF_TEST_TOKEN: &str = "INJECTOR-SELF-TEST-OK";
+
+/// Result of injecting the self-test prompt into a fresh session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelfTestOutcome {
+    /// The prompt reached Claude and Claude answered
+    Passed,
+    /// The prompt shows up in the pane but no answer appeared
+    NoResponse,
+    /// The prompt never showed up in the pane
+    NotDelivered,
+}
+
+impl SelfTestOutcome {
+    /// Judge a captured pane: the token appears once in the echoed prompt and again in the reply
+    pub fn from_pane(pane: &str) -> Self {
+        match pane.matches(SELF_TEST_TOKEN).count() {
+            0 => SelfTestOutcome::NotDelivered,
+            1 => SelfTestOutcome::NoResponse,
+            _ => SelfTestOutcome::Passed,
+        }
+    }
+}
+
+/// What to do when the target pane is in copy-mode at injection time
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CopyModePolicy {
+    /// Leave copy-mode (`send-keys -X cancel`) and inject normally
+    #[default]
+    Exit,
+    /// Refuse to inject and return an error
+    Error,
+}
+
+/// What to do when a worker is messaged before its minimum interval has passed
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum RateLimitPolicy {
+    /// Sleep until the interval has passed, then inject
+    #[default]
+    Delay,
+    /// Refuse with [`InjectorError::RateLimited`]
+    Reject,
+}
+
+/// Minimum time between messages to the same worker, based on its `last_message_at`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub min_interval: std::time::Duration,
+    pub policy: RateLimitPolicy,
+}
+
+impl RateLimit {
+    /// Time left before a worker last messaged at `last_message_at` may be messaged at `now` (unix seconds)
+    pub fn remaining(&self, last_message_at: Option<u64>, now: u64) -> std::time::Duration {
+        let Some(last) = last_message_at else {
+            return std::time::Duration::ZERO;
+        };
+        let elapsed = std::time::Duration::from_secs(now.saturating_sub(last));
+        self.min_interval.saturating_sub(elapsed)
+    }
+
+    /// `min_interval`, else `min_inject_interval_secs` from the config; `None` when that is zero
+    pub fn configured(
+        config: &InjectorConfig,
+        min_interval: Option<std::time::Duration>,
+        policy: RateLimitPolicy,
+    ) -> Option<Self> {
+        let min_interval = min_interval.unwrap_or(std::time::Duration::from_secs(config.min_inject_interval_secs));
+        (!min_interval.is_zero()).then_some(Self { min_interval, policy })
+    }
+}
+
+/// Options controlling how a message is injected into a tmux session
+#[derive(Debug, Clone, Default)]
+pub struct TmuxInjectOptions {
+    pub copy_mode: CopyModePolicy,
+    /// Wait up to this long for Claude to stop generating before sending
+    pub wait_idle: Option<std::time::Duration>,
+    /// Keep messages to a registered worker at least this far apart
+    pub rate_limit: Option<RateLimit>,
+    /// Send messages that are blank after trimming (a bare Enter) instead of refusing them
+    pub allow_empty: bool,
+}
+
+impl TmuxInjectOptions {
+    /// Default options with the configured rate limit, for messages to workers
+    pub fn for_worker(config: &InjectorConfig) -> Self {
+        Self {
+            rate_limit: RateLimit::configured(config, None, RateLimitPolicy::Delay),
+            ..Self::default()
+        }
+    }
+}
+
+/// Installed tmux version (e.g. `tmux 3.3a` -> 3.3)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TmuxVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl TmuxVersion {
+    /// `new-session -e` (environment) was added in tmux 3.2
+    pub const ENV_FLAG: TmuxVersion = TmuxVersion { major: 3, minor: 2 };
+
+    /// `new-session -c` (start directory) was added in tmux 1.9
+    pub const START_DIR_FLAG: TmuxVersion = TmuxVersion { major: 1, minor: 9 };
+
+    /// Newer than any release, for development builds and unrecognized versions
+    pub const LATEST: TmuxVersion = TmuxVersion { major: u32::MAX, minor: 0 };
+
+    /// Parse `tmux -V` output such as `tmux 3.3a`, `tmux next-3.4` or `tmux master`
+    pub fn parse(output: &str) -> Option<Self> {
+        let version = output.trim().strip_prefix("tmux ")?;
+        let version = version.strip_prefix("next-").unwrap_or(version);
+
+        // Development builds are newer than any release
+        if version == "master" {
+            return Some(Self::LATEST);
+        }
+
+        let (major, rest) = version.split_once('.')?;
+        let minor: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+
+        Some(Self {
+            major: major.parse().ok()?,
+            minor: minor.parse().ok()?,
+        })
+    }
+
+    pub fn supports_env_flag(&self) -> bool {
+        *self >= Self::ENV_FLAG
+    }
+}
+
+impl std::fmt::Display for TmuxVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// Output of a command run with [`TmuxSpawner::exec_in_new_window`]
+#[derive(Debug, Clone)]
+pub struct ExecOutput {
+    pub output: String,
+    pub exit_code: i32,
+}
+
+/// A Claude pane split into conversation output and UI furniture, see [`parse_pane`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PaneContent {
+    /// Claude's replies (`⏺` blocks, tool calls included), oldest first
+    pub messages: Vec<String>,
+    /// Text typed into the input box, without its border and `>` prompt
+    pub input_box: String,
+    /// Spinner line while generating, otherwise the hint line under the input box
+    pub status: Option<String>,
+}
+
+/// Prefix Claude puts in front of each reply and tool call
+const MESSAGE_MARKER: char = '⏺';
+
+/// Prefix of the echoed user prompt in the conversation
+const PROMPT_MARKER: char = '>';
+
+/// Leading characters of a prompt looked for in its echo, see [`prompt_echo`]
+const PROMPT_ANCHOR_CHARS: usize = 40;
+
+/// Trailing scrollback lines that mark where new output starts in [`TmuxSpawner::inject_and_capture_delta`]
+const DELTA_ANCHOR_LINES: usize = 3;
+
+/// Separate Claude's replies from its input box and status lines
+///
+/// The input box is the last bordered block (`╭…╰`, or two `───` rules);
+/// whatever is below it is the hint line and a spinner line directly above it
+/// is the status. Echoed prompts (`> …`) and the welcome banner are dropped.
+pub fn parse_pane(text: &str) -> PaneContent {
+    let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+
+    let mut content = PaneContent::default();
+    let mut conversation = &lines[..];
+    if let Some((top, bottom)) = input_box_bounds(&lines) {
+        content.input_box = lines[top + 1..bottom]
+            .iter()
+            .map(|line| line.trim().trim_matches('│').trim())
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim_start_matches(PROMPT_MARKER)
+            .trim()
+            .to_string();
+
+        let footer: Vec<&str> = lines[bottom + 1..].iter().map(|line| line.trim()).filter(|line| !line.is_empty()).collect();
+        content.status = (!footer.is_empty()).then(|| footer.join(" "));
+        conversation = &lines[..top];
+    }
+
+    if let Some(spinner) = conversation.iter().rposition(|line| !line.trim().is_empty()) {
+        if PaneState::detect(conversation[spinner]) == PaneState::Generating {
+            content.status = Some(conversation[spinner].trim().to_string());
+            conversation = &conversation[..spinner];
+        }
+    }
+
+    let mut current: Option<Vec<&str>> = None;
+    let mut is_reply = false;
+    for line in conversation {
+        let starts_reply = line.starts_with(MESSAGE_MARKER);
+        if starts_reply || line.starts_with(PROMPT_MARKER) {
+            if let Some(block) = current.take().filter(|_| is_reply) {
+                content.messages.push(block.join("\n").trim_end().to_string());
+            }
+            is_reply = starts_reply;
+            current = Some(vec![line.trim_start_matches([MESSAGE_MARKER, PROMPT_MARKER]).trim_start()]);
+        } else if let Some(ref mut block) = current {
+            block.push(line.strip_prefix("  ").unwrap_or(line));
+        }
+    }
+    if let Some(block) = current.filter(|_| is_reply) {
+        content.messages.push(block.join("\n").trim_end().to_string());
+    }
+
+    content
+}
+
+/// Line indexes of the input box's top and bottom border, see [`parse_pane`]
+fn input_box_bounds(lines: &[&str]) -> Option<(usize, usize)> {
+    let is_rule = |line: &str| line.trim().chars().count() >= 3 && line.trim().chars().all(|c| c == '─');
+
+    let bottom = lines.iter().rposition(|line| line.trim_start().starts_with('╰') || is_rule(line))?;
+    let top = lines[..bottom]
+        .iter()
+        .rposition(|line| line.trim_start().starts_with('╭') || is_rule(line))?;
+    Some((top, bottom))
+}
+
+/// Index of the echo of `message` in a Claude pane's conversation
+///
+/// Matched on the start of the message's first line, as long prompts wrap;
+/// when no echo matches (e.g. Claude collapsed a pasted prompt), the last
+/// echoed prompt is taken instead.
+fn prompt_echo(lines: &[&str], message: &str) -> Option<usize> {
+    let conversation = match input_box_bounds(lines) {
+        Some((top, _)) => &lines[..top],
+        None => lines,
+    };
+    let anchor: String = message
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default()
+        .chars()
+        .take(PROMPT_ANCHOR_CHARS)
+        .collect();
+
+    let echoes = |line: &&str| line.starts_with(PROMPT_MARKER);
+    conversation
+        .iter()
+        .rposition(|line| echoes(line) && line.trim_start_matches(PROMPT_MARKER).trim_start().starts_with(&anchor))
+        .or_else(|| conversation.iter().rposition(echoes))
+}
+
+/// Quote a string for POSIX `sh`
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
 /// Tmux-based Claude spawner - Creates visible, injectable sessions
 pub struct TmuxSpawner;
 
@@ -14,21 +350,35 @@ impl TmuxSpawner {
     }
 
     /// Spawn Claude in a new tmux session with automation settings
-    pub fn spawn_session(session_name: &str, working_dir: &str) -> Result<String> {
+    ///
+    /// `claude_args` are passed to `claude` after the automation flags. A
+    /// `project_root` other than `working_dir` is loaded as project context,
+    /// see [`TmuxSpawner::project_root_args`].
+    pub fn spawn_session(
+        session_name: &str,
+        working_dir: &str,
+        claude_args: &[String],
+        project_root: Option<&str>,
+    ) -> Result<String> {
         if !Self::is_available() {
-            anyhow::bail!("tmux is not installed. Install with: sudo apt install tmux");
+            return Err(InjectorError::TmuxUnavailable.into());
+        }
+
+        let mut claude_args = claude_args.to_vec();
+        let mut env = Vec::new();
+        if let Some(root) = project_root {
+            claude_args.extend(Self::project_root_args(root)?);
+            env.push(PROJECT_ROOT_ENV);
         }
 
         // Create a new tmux session running Claude with automation flags
+        // (skip permission prompts unless safe mode is on)
+        let command = crate::claude_command(&[crate::DANGEROUS_SKIP_PERMISSIONS], &claude_args)?;
+        let command: Vec<&str> = command.iter().map(String::as_str).collect();
+        let args = Self::new_session_args(session_name, working_dir, &env, &command)?;
+
         let output = Command::new("tmux")
-            .args(&[
-                "new-session",
-                "-d",              // Detached (background)
-                "-s", session_name, // Session name
-                "-c", working_dir,  // Working directory
-                "claude",          // Claude command
-                "--dangerously-skip-permissions"  // Skip permission prompts for automation
-            ])
+            .args(&args)
             .output()
             .context("Failed to create tmux session")?;
 
@@ -40,29 +390,157 @@ impl TmuxSpawner {
         Ok(format!("Tmux session '{}' created with automation enabled", session_name))
     }
 
+    /// Delay or refuse a message to the worker in `session_name` that comes too soon
+    ///
+    /// Sessions that belong to no registered worker are not limited.
+    fn enforce_rate_limit(session_name: &str, limit: &RateLimit) -> Result<()> {
+        let registry = crate::WorkerRegistry::load()?;
+        let Some(worker) = registry
+            .get(session_name)
+            .or_else(|| registry.list_all().into_iter().find(|w| w.tmux_session == session_name))
+        else {
+            return Ok(());
+        };
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs();
+        let remaining = limit.remaining(worker.last_message_at, now);
+        if remaining.is_zero() {
+            return Ok(());
+        }
+
+        match limit.policy {
+            RateLimitPolicy::Delay => {
+                log::info!("Worker {} was messaged recently, waiting {:?}", worker.name, remaining);
+                std::thread::sleep(remaining);
+                Ok(())
+            }
+            RateLimitPolicy::Reject => Err(InjectorError::RateLimited {
+                name: worker.name.clone(),
+                retry_after: remaining,
+            }
+            .into()),
+        }
+    }
+
+    /// Claude arguments that load a project root's context from another working directory
+    ///
+    /// Claude reads `CLAUDE.md` and settings from its working directory upwards,
+    /// so a root elsewhere is added with `--add-dir`; run Claude with
+    /// [`PROJECT_ROOT_ENV`] set so it reads that directory's `CLAUDE.md` too.
+    pub fn project_root_args(project_root: &str) -> Result<Vec<String>> {
+        let root = std::fs::canonicalize(project_root)
+            .context(format!("Project root does not exist: {}", project_root))?;
+        if !root.is_dir() {
+            anyhow::bail!("Project root is not a directory: {}", root.display());
+        }
+
+        Ok(vec!["--add-dir".to_string(), root.to_string_lossy().into_owned()])
+    }
+
+    /// Detect the installed tmux version
+    ///
+    /// Output that does not look like a version (a distribution or fork build,
+    /// say) is taken as [`TmuxVersion::LATEST`] with a warning, so the modern
+    /// argument forms are used.
+    pub fn version() -> Result<TmuxVersion> {
+        let output = Command::new("tmux")
+            .arg("-V")
+            .output()
+            .context("tmux is not installed. Install with: sudo apt install tmux")?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(TmuxVersion::parse(&stdout).unwrap_or_else(|| {
+            log::warn!("Unrecognized tmux version {:?}; assuming a recent tmux", stdout.trim());
+            TmuxVersion::LATEST
+        }))
+    }
+
+    /// Build `tmux new-session` arguments for the installed tmux version
+    ///
+    /// Environment variables are passed with `-e` on tmux 3.2+, and through an
+    /// `env KEY=VAL` wrapper around the command on older versions.
+    pub fn new_session_args(
+        session_name: &str,
+        working_dir: &str,
+        env: &[(&str, &str)],
+        command: &[&str],
+    ) -> Result<Vec<String>> {
+        Self::build_new_session_args(Self::version()?, session_name, working_dir, env, command)
+    }
+
+    fn build_new_session_args(
+        version: TmuxVersion,
+        session_name: &str,
+        working_dir: &str,
+        env: &[(&str, &str)],
+        command: &[&str],
+    ) -> Result<Vec<String>> {
+        if version < TmuxVersion::START_DIR_FLAG {
+            anyhow::bail!(
+                "tmux {} is too old: version {} or newer is required",
+                version,
+                TmuxVersion::START_DIR_FLAG
+            );
+        }
+
+        let mut args: Vec<String> = vec![
+            "new-session".to_string(),
+            "-d".to_string(),                                  // Detached (background)
+            "-s".to_string(), session_name.to_string(),        // Session name
+            "-c".to_string(), working_dir.to_string(),         // Working directory
+        ];
+
+        let env_pairs = env.iter().map(|(key, value)| format!("{}={}", key, value));
+
+        if version.supports_env_flag() {
+            for pair in env_pairs {
+                args.push("-e".to_string());
+                args.push(pair);
+            }
+        } else if !env.is_empty() {
+            log::debug!("tmux {} lacks new-session -e, wrapping command with env", version);
+            args.push("env".to_string());
+            args.extend(env_pairs);
+        }
+
+        args.extend(command.iter().map(|arg| arg.to_string()));
+        Ok(args)
+    }
+
     /// Spawn Claude worker with agent type and automatic registration
+    ///
+    /// With `resume`, the worker continues that Claude conversation
+    /// (`claude --resume <session-id>`) instead of starting a fresh one; the
+    /// session must exist.
     pub fn spawn_worker(
         name: &str,
         agent_type: &str,
         working_dir: &str,
         task_id: Option<String>,
+        ttl_secs: Option<u64>,
+        resume: Option<String>,
+        project_root: Option<&str>,
     ) -> Result<crate::WorkerInfo> {
+        let mut claude_args = Vec::new();
+        if let Some(ref session_id) = resume {
+            if crate::SessionDetector::new()?.find_session_file(session_id)?.is_none() {
+                return Err(anyhow::Error::new(InjectorError::SessionNotFound(session_id.clone()))
+                    .context(format!("Cannot resume session {} in worker '{}'", session_id, name)));
+            }
+            claude_args = vec!["--resume".to_string(), session_id.clone()];
+        }
+
         // Spawn the tmux session
-        Self::spawn_session(name, working_dir)?;
+        Self::spawn_session(name, working_dir, &claude_args, project_root)?;
 
         // Create worker info
         let worker = crate::WorkerInfo {
-            name: name.to_string(),
-            agent_type: agent_type.to_string(),
             task_id,
-            tmux_session: name.to_string(),
-            working_dir: working_dir.to_string(),
-            spawned_at: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
-            status: crate::WorkerStatus::Starting,
-            messages_sent: 0,
+            ttl_secs,
+            resumed_from: resume,
+            ..crate::WorkerInfo::new(name, agent_type, working_dir, crate::WorkerStatus::Starting)
         };
 
         // Register in registry
@@ -73,54 +551,553 @@ impl TmuxSpawner {
     }
 
     /// Inject message into a tmux session
+    ///
+    /// `session_name` may also be a full `session:window.pane` target (or a pane
+    /// id such as `%3`); see [`TmuxSpawner::resolve_target`].
     pub fn inject_message(session_name: &str, message: &str) -> Result<()> {
+        Self::inject_message_with_options(session_name, message, &TmuxInjectOptions::default())
+    }
+
+    /// Inject message into a tmux session with explicit options
+    pub fn inject_message_with_options(
+        session_name: &str,
+        message: &str,
+        options: &TmuxInjectOptions,
+    ) -> Result<()> {
+        let started = std::time::Instant::now();
+        if !options.allow_empty {
+            crate::safety::check_not_empty(message)?;
+        }
+
+        if let Some(ref limit) = options.rate_limit {
+            let session = session_name.split(':').next().unwrap_or(session_name);
+            Self::enforce_rate_limit(session, limit)?;
+        }
+
+        let target = Self::resolve_target(session_name)?;
+
+        if let Some(timeout) = options.wait_idle {
+            Self::wait_until_idle(&target, timeout)?;
+        }
+
+        // Keys sent to a pane in copy-mode go to the copy-mode buffer, not Claude
+        let pane = Self::pane_info(&target)?;
+        if pane.in_mode {
+            match options.copy_mode {
+                CopyModePolicy::Exit => Self::exit_copy_mode(&target)?,
+                CopyModePolicy::Error => anyhow::bail!(
+                    "Tmux session '{}' is in copy-mode; exit it (press q) before injecting",
+                    session_name
+                ),
+            }
+        }
+
         // Send the message text with -l flag (literal, no key parsing)
         let output = Command::new("tmux")
-            .args(&[
-                "send-keys",
-                "-l",           // Literal flag - treats input as plain text
-                "-t", session_name,
-                message,
-            ])
+            .args(Self::send_literal_args(&target, message))
             .output()
             .context("Failed to send message text")?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("Failed to inject message text: {}", stderr);
+            return Err(Self::command_error(session_name, "Failed to inject message text", &output.stderr));
         }
 
         // Send Enter key separately (without -l flag so it's interpreted as a key)
         let output = Command::new("tmux")
-            .args(&[
+            .args([
                 "send-keys",
-                "-t", session_name,
+                "-t", &target,
                 "Enter"
             ])
             .output()
             .context("Failed to send Enter key")?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("Failed to send Enter key: {}", stderr);
+            return Err(Self::command_error(session_name, "Failed to send Enter key", &output.stderr));
+        }
+
+        crate::events::emit(crate::InjectStats::since(started, message.len() + 1).event(session_name, None));
+
+        Ok(())
+    }
+
+    /// Error for a failed tmux command, [`InjectorError::SessionGone`] when its target vanished
+    ///
+    /// Lets callers tell a session that died after being checked apart from
+    /// other tmux failures.
+    pub fn command_error(target: &str, action: &str, stderr: &[u8]) -> anyhow::Error {
+        let stderr = String::from_utf8_lossy(stderr);
+        if !Self::is_session_gone(&stderr) {
+            return anyhow::anyhow!("{}: {}", action, stderr.trim());
+        }
+
+        let session = target.split(':').next().unwrap_or(target);
+        anyhow::Error::new(InjectorError::SessionGone(session.to_string())).context(action.to_string())
+    }
+
+    /// Whether tmux stderr says the targeted session, window or pane does not exist
+    pub fn is_session_gone(stderr: &str) -> bool {
+        SESSION_GONE_ERRORS.iter().any(|message| stderr.contains(message))
+    }
+
+    /// Detect whether Claude is currently generating in a session's pane
+    pub fn pane_state(session_name: &str) -> Result<PaneState> {
+        Ok(PaneState::detect(&Self::capture_pane(session_name, None)?))
+    }
+
+    /// Inject a message once Claude has finished generating
+    ///
+    /// Polls the pane until it is idle, failing if that takes longer than `timeout`.
+    pub fn inject_when_idle(session_name: &str, message: &str, timeout: std::time::Duration) -> Result<()> {
+        let options = TmuxInjectOptions {
+            wait_idle: Some(timeout),
+            ..Default::default()
+        };
+        Self::inject_message_with_options(session_name, message, &options)
+    }
+
+    /// Block until Claude's pane is idle, failing after `timeout`
+    fn wait_until_idle(session_name: &str, timeout: std::time::Duration) -> Result<()> {
+        let started = std::time::Instant::now();
+
+        while Self::pane_state(session_name)? == PaneState::Generating {
+            if started.elapsed() >= timeout {
+                anyhow::bail!("Claude in '{}' is still generating after {:?}", session_name, timeout);
+            }
+            log::debug!("Waiting for Claude in '{}' to finish generating", session_name);
+            std::thread::sleep(std::time::Duration::from_millis(500));
+        }
+
+        Ok(())
+    }
+
+    /// Check that injection works by asking Claude to echo [`SELF_TEST_TOKEN`]
+    ///
+    /// Waits for Claude to start, injects the prompt, then watches the pane until
+    /// the answer appears or `timeout` elapses.
+    pub fn self_test(session_name: &str, timeout: std::time::Duration) -> Result<SelfTestOutcome> {
+        let started = std::time::Instant::now();
+
+        while !Self::is_claude_running(session_name) {
+            if started.elapsed() >= timeout {
+                return Err(anyhow::Error::new(InjectorError::Timeout {
+                    limit: timeout,
+                    partial_output: Self::capture_pane(session_name, None).unwrap_or_default(),
+                })
+                .context(format!("Claude did not start in '{}'", session_name)));
+            }
+            std::thread::sleep(std::time::Duration::from_millis(500));
+        }
+
+        let prompt = format!("Injection self-test: reply with only the word {}", SELF_TEST_TOKEN);
+        Self::inject_when_idle(session_name, &prompt, timeout.saturating_sub(started.elapsed()))?;
+
+        loop {
+            let outcome = SelfTestOutcome::from_pane(&Self::capture_pane(session_name, Some(200))?);
+            if outcome == SelfTestOutcome::Passed || started.elapsed() >= timeout {
+                return Ok(outcome);
+            }
+            std::thread::sleep(std::time::Duration::from_millis(500));
+        }
+    }
+
+    /// Arguments for `tmux send-keys` that type `text` exactly as given
+    ///
+    /// `--` stops text starting with `-` from being read as flags, and a trailing
+    /// `;` is escaped so tmux does not take it as a command separator.
+    pub fn send_literal_args(target: &str, text: &str) -> Vec<String> {
+        let text = match text.strip_suffix(';') {
+            Some(rest) => format!("{}\\;", rest),
+            None => text.to_string(),
+        };
+
+        vec![
+            "send-keys".to_string(),
+            "-l".to_string(), // Literal flag - treats input as plain text
+            "-t".to_string(),
+            target.to_string(),
+            "--".to_string(),
+            text,
+        ]
+    }
+
+    /// Pane to inject into for a session name or explicit target
+    ///
+    /// Explicit targets (`session:window.pane`, `%id`) are used as given. For a
+    /// bare session name, the pane running Claude is picked so a split session
+    /// with another pane active still gets the message; sessions without a
+    /// Claude pane fall back to their active pane.
+    pub fn resolve_target(target: &str) -> Result<String> {
+        if target.contains(':') || target.starts_with('%') {
+            return Ok(target.to_string());
+        }
+
+        Ok(Self::find_claude_pane(target)?.unwrap_or_else(|| target.to_string()))
+    }
+
+    /// Id of the pane running Claude in a session, preferring the active pane
+    pub fn find_claude_pane(session_name: &str) -> Result<Option<String>> {
+        let output = Command::new("tmux")
+            .args(["list-panes", "-s", "-t", session_name, "-F", "#{pane_id}\t#{pane_current_command}\t#{pane_active}"])
+            .output()
+            .context("Failed to list tmux panes")?;
+
+        if !output.status.success() {
+            return Err(InjectorError::TmuxSessionNotFound(session_name.to_string()).into());
+        }
+
+        Ok(Self::pick_claude_pane(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Pick the Claude pane from `list-panes` lines of `id<TAB>command<TAB>active`
+    fn pick_claude_pane(list: &str) -> Option<String> {
+        let panes: Vec<(&str, &str, bool)> = list
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.split('\t');
+                Some((parts.next()?, parts.next()?, parts.next()? == "1"))
+            })
+            .filter(|(_, command, _)| CLAUDE_PANE_COMMANDS.contains(command))
+            .collect();
+
+        panes
+            .iter()
+            .find(|(_, _, active)| *active)
+            .or_else(|| panes.first())
+            .map(|(id, _, _)| id.to_string())
+    }
+
+    /// Get information about the active pane of a tmux session
+    pub fn pane_info(session_name: &str) -> Result<PaneInfo> {
+        let output = Command::new("tmux")
+            .args(["display-message", "-p", "-t", session_name, PANE_INFO_FORMAT])
+            .output()
+            .context("Failed to query tmux pane info")?;
+
+        if !output.status.success() {
+            return Err(Self::command_error(
+                session_name,
+                &format!("Failed to query pane info for '{}'", session_name),
+                &output.stderr,
+            ));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Self::parse_pane_info(stdout.trim_end())
+            .context(format!("Unexpected pane info output: {}", stdout.trim_end()))
+    }
+
+    fn parse_pane_info(line: &str) -> Option<PaneInfo> {
+        let parts: Vec<&str> = line.split('\t').collect();
+
+        if parts.len() < 4 {
+            return None;
+        }
+
+        Some(PaneInfo {
+            pane_id: parts[0].to_string(),
+            pane_pid: parts[1].parse().ok()?,
+            current_command: parts[2].to_string(),
+            in_mode: parts[3] == "1",
+            current_path: parts[4..].join("\t"),
+        })
+    }
+
+    /// Leave copy-mode in a tmux session's active pane
+    pub fn exit_copy_mode(session_name: &str) -> Result<()> {
+        let output = Command::new("tmux")
+            .args(["send-keys", "-t", session_name, "-X", "cancel"])
+            .output()
+            .context("Failed to exit copy-mode")?;
+
+        if !output.status.success() {
+            return Err(Self::command_error(session_name, "Failed to exit copy-mode", &output.stderr));
         }
 
         Ok(())
     }
 
+    /// Capture the visible contents of a session's pane
+    ///
+    /// With `lines`, the capture starts that many lines back into the scrollback.
+    /// Trailing blank lines are stripped.
+    pub fn capture_pane(session_name: &str, lines: Option<usize>) -> Result<String> {
+        Self::capture(session_name, lines, false)
+    }
+
+    /// Capture the visible contents of a pane with its colour escape sequences kept
+    pub fn capture_pane_styled(target: &str) -> Result<String> {
+        Self::capture(target, None, true)
+    }
+
+    fn capture(session_name: &str, lines: Option<usize>, escapes: bool) -> Result<String> {
+        if !Self::session_exists(session_name) {
+            return Err(InjectorError::TmuxSessionNotFound(session_name.to_string()).into());
+        }
+
+        let mut args = vec!["capture-pane".to_string(), "-p".to_string(), "-t".to_string(), session_name.to_string()];
+        if escapes {
+            args.push("-e".to_string());
+        }
+        if let Some(lines) = lines {
+            args.push("-S".to_string());
+            args.push(format!("-{}", lines));
+        }
+
+        let output = Command::new("tmux")
+            .args(&args)
+            .output()
+            .context("Failed to capture tmux pane")?;
+
+        if !output.status.success() {
+            return Err(Self::command_error(
+                session_name,
+                &format!("Failed to capture pane for '{}'", session_name),
+                &output.stderr,
+            ));
+        }
+
+        let captured = String::from_utf8_lossy(&output.stdout);
+        Ok(captured.trim_end_matches(['\n', ' ']).to_string())
+    }
+
+    /// Inject a message and return only Claude's reply to it
+    ///
+    /// Records the scrollback before injecting, then polls until the pane has
+    /// changed, stops changing and Claude is no longer generating (or
+    /// `settle_timeout` elapses). Returns the replies after the prompt's echo,
+    /// as parsed by [`parse_pane`]; for panes without Claude's UI, the lines
+    /// after the earlier scrollback's last lines are returned. Both are found
+    /// by content, so they still work once the scrollback is at tmux's
+    /// `history-limit`.
+    pub fn inject_and_capture_delta(
+        session_name: &str,
+        message: &str,
+        options: &TmuxInjectOptions,
+        settle_timeout: std::time::Duration,
+    ) -> Result<String> {
+        let scrollback = Self::capture_scrollback(session_name)?;
+        Self::inject_message_with_options(session_name, message, options)?;
+
+        let started = std::time::Instant::now();
+        let mut last = Vec::new();
+        loop {
+            std::thread::sleep(std::time::Duration::from_millis(500));
+
+            let current = Self::capture_scrollback(session_name)?;
+            let settled = current != scrollback
+                && current == last
+                && Self::pane_state(session_name)? == PaneState::Idle;
+
+            if settled || started.elapsed() >= settle_timeout {
+                return Ok(Self::new_replies(message, &scrollback, &current));
+            }
+            last = current;
+        }
+    }
+
+    /// Lines of a session's whole scrollback, without trailing blank lines
+    fn capture_scrollback(session_name: &str) -> Result<Vec<String>> {
+        let output = Command::new("tmux")
+            .args(["capture-pane", "-p", "-S", "-", "-t", session_name])
+            .output()
+            .context("Failed to capture tmux pane")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("Failed to capture pane for '{}': {}", session_name, stderr);
+        }
+
+        let mut lines: Vec<String> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.trim_end().to_string())
+            .collect();
+        while lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+        }
+
+        Ok(lines)
+    }
+
+    /// Replies after the echo of `message`, or lines added after `before` without Claude's UI
+    fn new_replies(message: &str, before: &[String], current: &[String]) -> String {
+        let lines: Vec<&str> = current.iter().map(String::as_str).collect();
+        if parse_pane(&current.join("\n")).messages.is_empty() {
+            return Self::delta_lines(before, current);
+        }
+
+        match prompt_echo(&lines, message) {
+            Some(echo) => parse_pane(&lines[echo + 1..].join("\n")).messages.join("\n\n"),
+            None => String::new(),
+        }
+    }
+
+    /// Lines after the last occurrence of `before`'s final lines
+    ///
+    /// Everything is returned if those lines are gone, e.g. because the pane
+    /// was cleared meanwhile.
+    fn delta_lines(before: &[String], current: &[String]) -> String {
+        let tail = &before[before.len().saturating_sub(DELTA_ANCHOR_LINES)..];
+        if tail.is_empty() {
+            return current.join("\n");
+        }
+
+        let start = current
+            .windows(tail.len())
+            .rposition(|window| window == tail)
+            .map_or(0, |at| at + tail.len());
+        current[start..].join("\n")
+    }
+
+    /// Run a shell command in a new background window of a session and collect its output
+    ///
+    /// The window is created with `-d` so the session's active pane (Claude) is left
+    /// untouched. Output (stdout and stderr) and the exit code are read back from
+    /// temporary files once the command finishes or `timeout` elapses.
+    pub fn exec_in_new_window(
+        session_name: &str,
+        working_dir: &str,
+        command: &str,
+        timeout: std::time::Duration,
+    ) -> Result<ExecOutput> {
+        if !Self::session_exists(session_name) {
+            return Err(InjectorError::TmuxSessionNotFound(session_name.to_string()).into());
+        }
+
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let dir = std::env::temp_dir().join(format!("claude-injector-exec-{}-{}", std::process::id(), nanos));
+        std::fs::create_dir_all(&dir).context(format!("Failed to create {}", dir.display()))?;
+
+        let output_path = dir.join("output");
+        let status_path = dir.join("status");
+        let script = format!(
+            "sh -c {} > {} 2>&1; echo $? > {}",
+            shell_quote(command),
+            shell_quote(&output_path.to_string_lossy()),
+            shell_quote(&status_path.to_string_lossy()),
+        );
+
+        let output = Command::new("tmux")
+            .args([
+                "new-window", "-d", "-P", "-F", "#{window_id}",
+                "-t", &format!("{}:", session_name),
+                "-n", "worker-exec",
+                "-c", working_dir,
+                &script,
+            ])
+            .output()
+            .context("Failed to open tmux window")?;
+
+        if !output.status.success() {
+            let _ = std::fs::remove_dir_all(&dir);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("Failed to open window in '{}': {}", session_name, stderr);
+        }
+        let window_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+        let started = std::time::Instant::now();
+        let exit_code = loop {
+            if let Ok(status) = std::fs::read_to_string(&status_path) {
+                if let Ok(code) = status.trim().parse::<i32>() {
+                    break code;
+                }
+            }
+
+            if started.elapsed() >= timeout {
+                let _ = Command::new("tmux").args(["kill-window", "-t", &window_id]).output();
+                let partial_output = std::fs::read_to_string(&output_path).unwrap_or_default();
+                let _ = std::fs::remove_dir_all(&dir);
+                return Err(anyhow::Error::new(InjectorError::Timeout { limit: timeout, partial_output })
+                    .context(format!("Command did not finish: {}", command)));
+            }
+
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        };
+
+        let output = std::fs::read_to_string(&output_path).unwrap_or_default();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        Ok(ExecOutput { output, exit_code })
+    }
+
+    /// Find the tmux session whose pane hosts `pid` (directly or as a descendant)
+    pub fn find_session_for_pid(pid: u32) -> Result<Option<String>> {
+        Ok(Self::find_pane_hosting(pid)?.map(|(session_name, _)| session_name))
+    }
+
+    /// Find the id of the tmux pane that hosts `pid` (directly or as a descendant)
+    pub fn find_pane_for_pid(pid: u32) -> Result<Option<String>> {
+        Ok(Self::find_pane_hosting(pid)?.map(|(_, pane_id)| pane_id))
+    }
+
+    /// Session name and pane id of the pane hosting `pid`
+    fn find_pane_hosting(pid: u32) -> Result<Option<(String, String)>> {
+        let output = Command::new("tmux")
+            .args(["list-panes", "-a", "-F", "#{session_name}\t#{pane_id}\t#{pane_pid}"])
+            .output()
+            .context("Failed to list tmux panes")?;
+
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        for line in stdout.lines() {
+            let mut parts = line.split('\t');
+            let (Some(session_name), Some(pane_id), Some(pane_pid)) = (parts.next(), parts.next(), parts.next()) else {
+                continue;
+            };
+            let Ok(pane_pid) = pane_pid.parse::<u32>() else {
+                continue;
+            };
+
+            if crate::ProcessDetector::is_self_or_descendant(pid, pane_pid) {
+                return Ok(Some((session_name.to_string(), pane_id.to_string())));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Resolve the Claude session running in a tmux session's active pane
+    pub fn resolve_session_id(session_name: &str) -> Result<Option<String>> {
+        let pane = Self::pane_info(session_name)?;
+        let sessions = crate::SessionMapper::map_sessions_to_processes()?;
+        Ok(Self::session_for_pane(pane.pane_pid, &sessions))
+    }
+
+    /// The session whose Claude process is the pane's process or runs below it
+    fn session_for_pane(pane_pid: u32, sessions: &[crate::RunningClaudeSession]) -> Option<String> {
+        sessions
+            .iter()
+            .find(|s| crate::ProcessDetector::is_self_or_descendant(s.pid, pane_pid))
+            .map(|s| s.session_id.clone())
+    }
+
     /// Check if a tmux session exists
     pub fn session_exists(session_name: &str) -> bool {
         Command::new("tmux")
-            .args(&["has-session", "-t", session_name])
+            .args(["has-session", "-t", session_name])
             .output()
             .map(|o| o.status.success())
             .unwrap_or(false)
     }
 
+    /// Check if a tmux session exists and its active pane is running Claude
+    pub fn is_claude_running(session_name: &str) -> bool {
+        Self::session_exists(session_name)
+            && Self::pane_info(session_name)
+                .map(|pane| CLAUDE_PANE_COMMANDS.contains(&pane.current_command.as_str()))
+                .unwrap_or(false)
+    }
+
     /// List all tmux sessions
     pub fn list_sessions() -> Result<Vec<String>> {
         let output = Command::new("tmux")
-            .args(&["list-sessions", "-F", "#{session_name}"])
+            .args(["list-sessions", "-F", "#{session_name}"])
             .output()
             .context("Failed to list tmux sessions")?;
 
@@ -144,7 +1121,7 @@ impl TmuxSpawner {
     /// Kill a tmux session
     pub fn kill_session(session_name: &str) -> Result<()> {
         Command::new("tmux")
-            .args(&["kill-session", "-t", session_name])
+            .args(["kill-session", "-t", session_name])
             .output()
             .context("Failed to kill tmux session")?;
 
@@ -154,7 +1131,7 @@ impl TmuxSpawner {
     /// Send Ctrl+C to a session
     pub fn send_interrupt(session_name: &str) -> Result<()> {
         Command::new("tmux")
-            .args(&["send-keys", "-t", session_name, "C-c"])
+            .args(["send-keys", "-t", session_name, "C-c"])
             .output()?;
         Ok(())
     }
@@ -169,6 +1146,370 @@ mod tests {
         println!("Tmux available: {}", TmuxSpawner::is_available());
     }
 
+    #[test]
+    fn test_parse_pane_info() {
+        let info = TmuxSpawner::parse_pane_info("%3\t4242\tclaude\t1").unwrap();
+        assert_eq!(info.pane_id, "%3");
+        assert_eq!(info.pane_pid, 4242);
+        assert_eq!(info.current_command, "claude");
+        assert!(info.in_mode);
+
+        let info = TmuxSpawner::parse_pane_info("%0\t17\tbash\t0\t/work/app").unwrap();
+        assert!(!info.in_mode);
+        assert_eq!(info.current_path, "/work/app");
+
+        assert!(TmuxSpawner::parse_pane_info("garbage").is_none());
+    }
+
+    #[test]
+    fn test_detect_pane_state() {
+        let thinking = "> fix the tests\n\n✻ Pondering… (12s · ↑ 1.2k tokens · esc to interrupt)\n\n╭──╮\n│ > │\n╰──╯";
+        assert_eq!(PaneState::detect(thinking), PaneState::Generating);
+
+        let streaming = "● Reading files\n✢ Working…\n";
+        assert_eq!(PaneState::detect(streaming), PaneState::Generating);
+
+        let idle = "● Done. All tests pass.\n\n╭──╮\n│ > │\n╰──╯\n  ? for shortcuts";
+        assert_eq!(PaneState::detect(idle), PaneState::Idle);
+
+        let bullets = "● Next steps:\n* Update the docs…\n* Rerun the suite\n╭──╮\n│ > │\n╰──╯";
+        assert_eq!(PaneState::detect(bullets), PaneState::Idle);
+    }
+
+    #[test]
+    fn test_self_test_outcome() {
+        let echoed = format!("> Injection self-test: reply with only the word {}", SELF_TEST_TOKEN);
+        assert_eq!(SelfTestOutcome::from_pane("╭──╮\n│ > │"), SelfTestOutcome::NotDelivered);
+        assert_eq!(SelfTestOutcome::from_pane(&echoed), SelfTestOutcome::NoResponse);
+
+        let answered = format!("{}\n\n● {}", echoed, SELF_TEST_TOKEN);
+        assert_eq!(SelfTestOutcome::from_pane(&answered), SelfTestOutcome::Passed);
+    }
+
+    #[test]
+    fn test_parse_tmux_version() {
+        assert_eq!(TmuxVersion::parse("tmux 3.3a"), Some(TmuxVersion { major: 3, minor: 3 }));
+        assert_eq!(TmuxVersion::parse("tmux next-3.4\n"), Some(TmuxVersion { major: 3, minor: 4 }));
+        assert_eq!(TmuxVersion::parse("tmux 2.9"), Some(TmuxVersion { major: 2, minor: 9 }));
+        assert!(TmuxVersion::parse("tmux master").unwrap().supports_env_flag());
+        assert!(!TmuxVersion::parse("tmux 3.1c").unwrap().supports_env_flag());
+        assert!(TmuxVersion::parse("screen 4.0").is_none());
+    }
+
+    #[test]
+    fn test_new_session_env_fallback() {
+        let env = [("CCLAUDE_AGENT", "coding-agent")];
+        let command = ["claude"];
+
+        let modern = TmuxVersion { major: 3, minor: 2 };
+        let args = TmuxSpawner::build_new_session_args(modern, "s", "/tmp", &env, &command).unwrap();
+        assert_eq!(args[6..], ["-e", "CCLAUDE_AGENT=coding-agent", "claude"]);
+
+        let old = TmuxVersion { major: 2, minor: 9 };
+        let args = TmuxSpawner::build_new_session_args(old, "s", "/tmp", &env, &command).unwrap();
+        assert_eq!(args[6..], ["env", "CCLAUDE_AGENT=coding-agent", "claude"]);
+
+        let ancient = TmuxVersion { major: 1, minor: 8 };
+        assert!(TmuxSpawner::build_new_session_args(ancient, "s", "/tmp", &env, &command).is_err());
+    }
+
+    #[test]
+    fn test_pick_claude_pane() {
+        let panes = "%1\tbash\t1\n%2\tclaude\t0\n%3\tnode\t0\n";
+        assert_eq!(TmuxSpawner::pick_claude_pane(panes).as_deref(), Some("%2"));
+
+        let panes = "%1\tclaude\t0\n%2\tclaude\t1\n";
+        assert_eq!(TmuxSpawner::pick_claude_pane(panes).as_deref(), Some("%2"));
+
+        assert_eq!(TmuxSpawner::pick_claude_pane("%1\tvim\t1\n"), None);
+    }
+
+    #[test]
+    fn test_command_error_detects_vanished_session() {
+        let gone = TmuxSpawner::command_error("w1:0.1", "Failed to send Enter key", b"can't find pane: %4\n");
+        assert!(matches!(InjectorError::find_in(&gone), Some(InjectorError::SessionGone(name)) if name == "w1"));
+        assert!(TmuxSpawner::is_session_gone("no server running on /tmp/tmux-1000/default"));
+
+        let other = TmuxSpawner::command_error("w1", "Failed to send Enter key", b"unknown key: Entr\n");
+        assert!(InjectorError::find_in(&other).is_none());
+        assert_eq!(other.to_string(), "Failed to send Enter key: unknown key: Entr");
+    }
+
+    #[test]
+    fn test_rate_limit_remaining() {
+        let limit = RateLimit {
+            min_interval: std::time::Duration::from_secs(10),
+            policy: RateLimitPolicy::Reject,
+        };
+
+        assert_eq!(limit.remaining(None, 100), std::time::Duration::ZERO);
+        assert_eq!(limit.remaining(Some(95), 100), std::time::Duration::from_secs(5));
+        assert_eq!(limit.remaining(Some(80), 100), std::time::Duration::ZERO);
+    }
+
+    #[test]
+    fn test_worker_options_use_configured_rate_limit() {
+        let mut config = InjectorConfig::default();
+        assert_eq!(TmuxInjectOptions::for_worker(&config).rate_limit, None);
+
+        config.min_inject_interval_secs = 5;
+        assert_eq!(
+            TmuxInjectOptions::for_worker(&config).rate_limit,
+            Some(RateLimit {
+                min_interval: std::time::Duration::from_secs(5),
+                policy: RateLimitPolicy::Delay,
+            })
+        );
+
+        // An explicit interval overrides the config, and zero turns the limit off
+        let limit = RateLimit::configured(&config, Some(std::time::Duration::from_secs(2)), RateLimitPolicy::Reject);
+        assert_eq!(limit.map(|l| l.min_interval), Some(std::time::Duration::from_secs(2)));
+        assert_eq!(RateLimit::configured(&config, Some(std::time::Duration::ZERO), RateLimitPolicy::Reject), None);
+    }
+
+    #[test]
+    fn test_project_root_args() {
+        let root = std::env::temp_dir().canonicalize().unwrap();
+        let args = TmuxSpawner::project_root_args(&root.to_string_lossy()).unwrap();
+        assert_eq!(args, ["--add-dir".to_string(), root.to_string_lossy().into_owned()]);
+
+        assert!(TmuxSpawner::project_root_args("/nonexistent/project").is_err());
+    }
+
+    #[test]
+    fn test_exec_in_new_window() {
+        if TmuxSpawner::version().is_err() {
+            return;
+        }
+
+        let session = format!("claude-injector-exec-test-{}", std::process::id());
+        let status = Command::new("tmux")
+            .args(["new-session", "-d", "-s", &session, "-c", "/tmp", "sleep 30"])
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let result = TmuxSpawner::exec_in_new_window(
+            &session,
+            "/tmp",
+            "echo 'it''s' here; pwd; exit 3",
+            std::time::Duration::from_secs(10),
+        );
+        TmuxSpawner::kill_session(&session).unwrap();
+
+        let result = result.unwrap();
+        assert_eq!(result.output, "its here\n/tmp\n");
+        assert_eq!(result.exit_code, 3);
+    }
+
+    #[test]
+    fn test_send_literal_args_are_sent_verbatim() {
+        let args = TmuxSpawner::send_literal_args("s", "-x; rm -rf");
+        assert_eq!(args[4..], ["--", "-x; rm -rf"]);
+        assert_eq!(TmuxSpawner::send_literal_args("s", "done;")[5], "done\\;");
+
+        if TmuxSpawner::version().is_err() {
+            return;
+        }
+
+        let session = format!("claude-injector-literal-test-{}", std::process::id());
+        let status = Command::new("tmux")
+            .args(["new-session", "-d", "-s", &session, "cat"])
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        for text in ["-x; rm -rf", "--help", "end;"] {
+            let output = Command::new("tmux").args(TmuxSpawner::send_literal_args(&session, text)).output().unwrap();
+            assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+            Command::new("tmux").args(["send-keys", "-t", &session, "Enter"]).output().unwrap();
+        }
+        std::thread::sleep(std::time::Duration::from_millis(300));
+
+        let pane = TmuxSpawner::capture_pane(&session, None);
+        TmuxSpawner::kill_session(&session).unwrap();
+
+        // cat echoes each line back, so every prompt appears twice
+        let pane = pane.unwrap();
+        assert!(pane.ends_with("end;"), "trailing blank lines kept: {:?}", pane);
+        assert_eq!(pane.matches("-x; rm -rf").count(), 2, "{}", pane);
+        assert_eq!(pane.matches("--help").count(), 2, "{}", pane);
+        assert_eq!(pane.matches("end;").count(), 2, "{}", pane);
+
+        let err = TmuxSpawner::capture_pane(&session, Some(10)).unwrap_err();
+        assert!(matches!(InjectorError::find_in(&err), Some(InjectorError::TmuxSessionNotFound(_))));
+    }
+
+    #[test]
+    fn test_session_for_pane() {
+        let running = |id: &str, pid: u32| crate::RunningClaudeSession {
+            session_id: id.to_string(),
+            pid,
+            project_path: "/tmp".to_string(),
+            command: "claude".to_string(),
+            terminal_info: None,
+        };
+
+        // The test process stands in for Claude running below its parent's pane
+        let own_pid = std::process::id();
+        let parent = crate::ProcessDetector::get_parent_pid(own_pid).unwrap();
+        let sessions = vec![running("elsewhere", u32::MAX), running("in-pane", own_pid)];
+
+        assert_eq!(TmuxSpawner::session_for_pane(parent, &sessions).as_deref(), Some("in-pane"));
+        assert_eq!(TmuxSpawner::session_for_pane(parent, &sessions[..1]), None);
+    }
+
+    #[test]
+    fn test_parse_pane() {
+        let pane = "\
+╭───────────────────╮
+│ ✻ Welcome to Claude Code! │
+╰───────────────────╯
+
+> list the files
+  please
+
+⏺ Bash(ls)
+  ⎿  Cargo.toml
+     src
+
+⏺ There are two entries:
+  - Cargo.toml
+  - src
+
+✻ Thinking… (3s · esc to interrupt)
+
+╭───────────────────╮
+│ > next question   │
+╰───────────────────╯
+  ? for shortcuts
+";
+        let content = parse_pane(pane);
+        assert_eq!(content.messages, ["Bash(ls)\n⎿  Cargo.toml\n   src", "There are two entries:\n- Cargo.toml\n- src"]);
+        assert_eq!(content.input_box, "next question");
+        assert_eq!(content.status.as_deref(), Some("✻ Thinking… (3s · esc to interrupt)"));
+
+        let ruled = "> hi\n\n⏺ Hello!\n\n──────────\n> \n──────────\n  ⏵⏵ bypass permissions on\n";
+        let content = parse_pane(ruled);
+        assert_eq!(content.messages, ["Hello!"]);
+        assert_eq!(content.input_box, "");
+        assert_eq!(content.status.as_deref(), Some("⏵⏵ bypass permissions on"));
+    }
+
+    #[test]
+    fn test_new_replies_follow_the_prompt_echo() {
+        let lines = |text: &str| -> Vec<String> { text.lines().map(str::to_string).collect() };
+        let before = lines("> one\n\n⏺ Reply one\n\n> two\n\n⏺ Reply two\n\n──────────\n> \n──────────");
+
+        // Older replies scrolled off the top while the new one arrived
+        let current = lines("⏺ Reply two\n\n> three is a longer question\n  that wraps\n\n⏺ Reply three\n\n──────────\n> \n──────────");
+        assert_eq!(TmuxSpawner::new_replies("three is a longer question that wraps", &before, &current), "Reply three");
+
+        // A repeated prompt still picks the latest exchange
+        let current = lines("> two\n\n⏺ Reply two\n\n> two\n\n⏺ Again\n\n⏺ Bash(ls)\n\n──────────\n> \n──────────");
+        assert_eq!(TmuxSpawner::new_replies("two", &before, &current), "Again\n\nBash(ls)");
+
+        // Claude has not answered yet
+        let current = lines("> one\n\n⏺ Reply one\n\n> four\n\n──────────\n> \n──────────");
+        assert_eq!(TmuxSpawner::new_replies("four", &before, &current), "");
+    }
+
+    #[test]
+    fn test_inject_and_capture_delta() {
+        if !TmuxSpawner::is_available() {
+            return;
+        }
+
+        let session = format!("claude-injector-delta-{}", std::process::id());
+        let status = Command::new("tmux")
+            .args(["new-session", "-d", "-s", &session, "cat"])
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        // Poll rather than sleep: under the parallel suite the pane can take a while to come up
+        let wait_for = |ready: &dyn Fn(&str) -> bool| {
+            let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+            while std::time::Instant::now() < deadline {
+                if TmuxSpawner::capture_pane(&session, None).is_ok_and(|pane| ready(&pane)) {
+                    return;
+                }
+                std::thread::sleep(std::time::Duration::from_millis(50));
+            }
+            panic!("tmux pane {} never became ready", session);
+        };
+
+        wait_for(&|_| true);
+        let mut events = crate::events::subscribe();
+        TmuxSpawner::inject_message(&session, "first").unwrap();
+        wait_for(&|pane| pane.matches("first").count() == 2);
+
+        // Other tests inject concurrently, so look for this session's event among theirs
+        let injected = std::iter::from_fn(|| events.try_recv().ok()).find_map(|event| match event {
+            crate::events::WorkerEvent::Injected { name, correlation_id, bytes, .. } if name == session => {
+                Some((correlation_id, bytes))
+            }
+            _ => None,
+        });
+        assert_eq!(injected, Some((None, "first\n".len())));
+
+        // The earlier exchange stays out of the delta; the echo and cat's reply are in it
+        let delta = TmuxSpawner::inject_and_capture_delta(&session, "second", &TmuxInjectOptions::default(), std::time::Duration::from_secs(5));
+        TmuxSpawner::kill_session(&session).unwrap();
+        assert_eq!(delta.unwrap(), "second\nsecond");
+
+        let lines = |text: &str| -> Vec<String> { text.lines().map(str::to_string).collect() };
+        assert_eq!(TmuxSpawner::delta_lines(&lines("a"), &lines("a\nb\nc")), "b\nc");
+        assert_eq!(TmuxSpawner::delta_lines(&lines(""), &lines("a\nb\nc")), "a\nb\nc");
+        assert_eq!(TmuxSpawner::delta_lines(&lines("x\ny"), &lines("a\nb\nc")), "a\nb\nc");
+        // Old lines scrolled off the top: the anchor is still found by content
+        assert_eq!(TmuxSpawner::delta_lines(&lines("a\nb\nc\nd"), &lines("b\nc\nd\ne\nf")), "e\nf");
+    }
+
+    #[test]
+    fn test_inject_and_capture_delta_at_history_limit() {
+        if !TmuxSpawner::is_available() {
+            return;
+        }
+
+        // history-limit only applies to panes created after it is set, so the
+        // session's first window is replaced by one that fills its scrollback
+        let session = format!("claude-injector-history-{}", std::process::id());
+        let output = Command::new("tmux")
+            .args(["new-session", "-d", "-P", "-F", "#{window_id}", "-s", &session, "cat"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let first_window = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let status = Command::new("tmux")
+            .args(["set-option", "-t", &session, "history-limit", "5"])
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let status = Command::new("tmux")
+            .args(["new-window", "-d", "-t", &format!("{}:", session), "seq 1 100; cat"])
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let status = Command::new("tmux").args(["kill-window", "-t", &first_window]).status().unwrap();
+        assert!(status.success());
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while !TmuxSpawner::capture_pane(&session, None).is_ok_and(|pane| pane.ends_with("100")) {
+            assert!(std::time::Instant::now() < deadline, "tmux pane {} never filled", session);
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+
+        let settle_timeout = std::time::Duration::from_secs(5);
+        let started = std::time::Instant::now();
+        let delta = TmuxSpawner::inject_and_capture_delta(&session, "second", &TmuxInjectOptions::default(), settle_timeout);
+        let elapsed = started.elapsed();
+        TmuxSpawner::kill_session(&session).unwrap();
+
+        assert_eq!(delta.unwrap(), "second\nsecond");
+        assert!(elapsed < settle_timeout, "waited the full settle timeout");
+    }
+
     #[test]
     fn test_list_sessions() {
         if let Ok(sessions) = TmuxSpawner::list_sessions() {
@@ -0,0 +1,401 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::session::{JsonlEntry, SessionDetector};
+
+/// A single conversation turn read from a session's JSONL transcript
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TranscriptEntry {
+    pub role: String,
+    pub text: String,
+    pub timestamp: Option<String>,
+}
+
+/// A window of transcript entries plus the total number of entries available
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptPage {
+    pub entries: Vec<TranscriptEntry>,
+    pub offset: usize,
+    pub total: usize,
+}
+
+/// Output shape for [`render`]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TranscriptFormat {
+    /// Headed sections per turn, for reading
+    #[default]
+    Markdown,
+    /// One JSON array of entries
+    Json,
+    /// One JSON entry per line
+    Jsonl,
+    /// Turn texts only, separated by blank lines
+    Plain,
+}
+
+impl TranscriptFormat {
+    /// Parse a format name (`markdown`, `json`, `jsonl` or `plain`)
+    pub fn parse(format: &str) -> Result<Self> {
+        match format.to_lowercase().as_str() {
+            "markdown" | "md" => Ok(Self::Markdown),
+            "json" => Ok(Self::Json),
+            "jsonl" => Ok(Self::Jsonl),
+            "plain" | "text" => Ok(Self::Plain),
+            _ => anyhow::bail!("Unknown transcript format '{}' (expected markdown, json, jsonl or plain)", format),
+        }
+    }
+}
+
+/// Render transcript entries in the given format
+pub fn render(entries: &[TranscriptEntry], format: TranscriptFormat) -> Result<String> {
+    let rendered = match format {
+        TranscriptFormat::Markdown => entries
+            .iter()
+            .map(|entry| {
+                let mut section = format!("## {}\n", capitalize(&entry.role));
+                if let Some(ref timestamp) = entry.timestamp {
+                    section.push_str(&format!("\n_{}_\n", timestamp));
+                }
+                section.push_str(&format!("\n{}\n", entry.text.trim_end()));
+                section
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        TranscriptFormat::Json => format!("{}\n", serde_json::to_string_pretty(entries)?),
+        TranscriptFormat::Jsonl => entries
+            .iter()
+            .map(|entry| serde_json::to_string(entry).map(|line| line + "\n"))
+            .collect::<Result<String, _>>()?,
+        TranscriptFormat::Plain => entries
+            .iter()
+            .map(|entry| format!("{}\n", entry.text.trim_end()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+
+    Ok(rendered)
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// Which transcript entries a replay re-sends
+///
+/// Applied in order: `range` selects entries by transcript position
+/// (0-based, end exclusive), then `roles` keeps matching roles, then
+/// `skip_first` drops the first entry that is left.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayFilter {
+    pub roles: Vec<String>,
+    pub skip_first: bool,
+    pub range: (Option<usize>, Option<usize>),
+}
+
+impl Default for ReplayFilter {
+    fn default() -> Self {
+        Self {
+            roles: vec!["user".to_string()],
+            skip_first: false,
+            range: (None, None),
+        }
+    }
+}
+
+impl ReplayFilter {
+    /// Parse a `N:M` range; either end may be left out (`3:`, `:10`)
+    pub fn parse_range(range: &str) -> Result<(Option<usize>, Option<usize>)> {
+        let (start, end) = range
+            .split_once(':')
+            .context(format!("Invalid range '{}': expected N:M", range))?;
+        let bound = |value: &str| -> Result<Option<usize>> {
+            let value = value.trim();
+            if value.is_empty() {
+                return Ok(None);
+            }
+            value
+                .parse()
+                .map(Some)
+                .context(format!("Invalid range '{}': '{}' is not a number", range, value))
+        };
+
+        let (start, end) = (bound(start)?, bound(end)?);
+        if let (Some(start), Some(end)) = (start, end) {
+            if start > end {
+                anyhow::bail!("Invalid range '{}': start is after end", range);
+            }
+        }
+        Ok((start, end))
+    }
+
+    /// The entries to replay, in transcript order
+    pub fn apply(&self, entries: Vec<TranscriptEntry>) -> Vec<TranscriptEntry> {
+        let start = self.range.0.unwrap_or(0);
+        let end = self.range.1.unwrap_or(usize::MAX);
+
+        entries
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| (start..end).contains(i))
+            .map(|(_, entry)| entry)
+            .filter(|entry| self.roles.contains(&entry.role))
+            .skip(usize::from(self.skip_first))
+            .collect()
+    }
+}
+
+/// Agent named by the last `call_agent("...")` in raw transcript text
+///
+/// Matches the prompt workers are sent to load their agent, including its
+/// JSON-escaped form (`call_agent(\"...\")`) inside transcript lines.
+fn last_agent_call(text: &str) -> Option<String> {
+    text.rmatch_indices("call_agent(").find_map(|(at, call)| {
+        let args = text[at + call.len()..].trim_start_matches('\\').strip_prefix('"')?;
+        let end = args.find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))?;
+        let closed = args[end..].trim_start_matches('\\').starts_with('"');
+        (end > 0 && closed).then(|| args[..end].to_string())
+    })
+}
+
+/// Reads conversation transcripts from Claude's JSONL session files
+pub struct TranscriptReader;
+
+impl TranscriptReader {
+    /// Load the full transcript of a session
+    pub fn load_transcript(session_id: &str) -> Result<Vec<TranscriptEntry>> {
+        let page = Self::load_transcript_range(session_id, 0, usize::MAX)?;
+        Ok(page.entries)
+    }
+
+    /// Load a window of `limit` entries starting at `offset`, along with the total count
+    ///
+    /// The file is streamed line by line, so only the requested window is held in memory.
+    pub fn load_transcript_range(session_id: &str, offset: usize, limit: usize) -> Result<TranscriptPage> {
+        let detector = SessionDetector::new()?;
+        let path = detector
+            .find_session_file(session_id)?
+            .ok_or_else(|| crate::InjectorError::SessionNotFound(session_id.to_string()))?;
+
+        Self::load_range_from_path(&path, offset, limit)
+    }
+
+    /// Agent a session loaded most recently with `call_agent("...")`, if any
+    pub fn infer_agent_type(session_id: &str) -> Result<Option<String>> {
+        let detector = SessionDetector::new()?;
+        let path = detector
+            .find_session_file(session_id)?
+            .ok_or_else(|| crate::InjectorError::SessionNotFound(session_id.to_string()))?;
+
+        let content = fs::read_to_string(&path).context(format!("Failed to read transcript {:?}", path))?;
+        Ok(last_agent_call(&content))
+    }
+
+    /// Most recent assistant message in a session, if it has produced one
+    pub fn last_assistant_message(session_id: &str) -> Result<Option<String>> {
+        let detector = SessionDetector::new()?;
+        let path = detector
+            .find_session_file(session_id)?
+            .ok_or_else(|| crate::InjectorError::SessionNotFound(session_id.to_string()))?;
+
+        Self::last_assistant_message_from_path(&path)
+    }
+
+    /// Most recent assistant message in a specific JSONL file
+    pub fn last_assistant_message_from_path(path: &Path) -> Result<Option<String>> {
+        let file = fs::File::open(path).context(format!("Failed to open transcript {:?}", path))?;
+        let reader = BufReader::new(file);
+
+        let last = reader
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| Self::parse_line(&line))
+            .filter(|entry| entry.role == "assistant")
+            .last();
+
+        Ok(last.map(|entry| entry.text))
+    }
+
+    /// Load a window of entries from a specific JSONL file
+    pub fn load_range_from_path(path: &Path, offset: usize, limit: usize) -> Result<TranscriptPage> {
+        let file = fs::File::open(path).context(format!("Failed to open transcript {:?}", path))?;
+        let reader = BufReader::new(file);
+
+        let mut entries = Vec::new();
+        let mut total = 0;
+
+        for line in reader.lines().map_while(Result::ok) {
+            let Some(entry) = Self::parse_line(&line) else {
+                continue;
+            };
+
+            if total >= offset && entries.len() < limit {
+                entries.push(entry);
+            }
+            total += 1;
+        }
+
+        Ok(TranscriptPage {
+            entries,
+            offset,
+            total,
+        })
+    }
+
+    /// Parse one JSONL line into a transcript entry, skipping non-message and empty lines
+    fn parse_line(line: &str) -> Option<TranscriptEntry> {
+        let entry: JsonlEntry = serde_json::from_str(line).ok()?;
+        let message = entry.message?;
+        let role = message.role?;
+
+        if role != "user" && role != "assistant" {
+            return None;
+        }
+
+        let text = flatten_content(&message.content?);
+        if text.trim().is_empty() {
+            return None;
+        }
+
+        Some(TranscriptEntry {
+            role,
+            text,
+            timestamp: entry.timestamp,
+        })
+    }
+}
+
+/// Flatten message content (plain string, array of text blocks or a single block) into text
+pub fn flatten_content(content: &serde_json::Value) -> String {
+    match content {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(arr) => arr
+            .iter()
+            .filter_map(|v| v.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        serde_json::Value::Object(obj) => obj
+            .get("text")
+            .and_then(|t| t.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_agent_call() {
+        let transcript = [
+            r#"{"message":{"role":"user","content":"mcp__agenthub_http__call_agent(\"coding-agent\")"}}"#,
+            r#"{"message":{"role":"user","content":"now call_agent(\"test-orchestrator-agent\") please"}}"#,
+            r#"{"message":{"role":"assistant","content":"I could call_agent(name) later"}}"#,
+        ]
+        .join("\n");
+        assert_eq!(last_agent_call(&transcript).as_deref(), Some("test-orchestrator-agent"));
+        assert_eq!(last_agent_call(r#"call_agent("debugger-agent")"#).as_deref(), Some("debugger-agent"));
+        assert_eq!(last_agent_call("no agent here"), None);
+    }
+
+    #[test]
+    fn test_load_range_from_path() {
+        let path = std::env::temp_dir().join(format!("transcript-test-{}.jsonl", std::process::id()));
+        let lines = [
+            r#"{"type":"summary","summary":"ignored"}"#,
+            r#"{"type":"user","message":{"role":"user","content":"first"}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"second"}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","content":"x"}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":"third"}}"#,
+        ];
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        let page = TranscriptReader::load_range_from_path(&path, 1, 1).unwrap();
+        assert_eq!(page.total, 3);
+        assert_eq!(page.entries.len(), 1);
+        assert_eq!(page.entries[0].role, "assistant");
+        assert_eq!(page.entries[0].text, "second");
+
+        let page = TranscriptReader::load_range_from_path(&path, 2, 10).unwrap();
+        assert_eq!(page.entries.len(), 1);
+        assert_eq!(page.entries[0].text, "third");
+
+        let last = TranscriptReader::last_assistant_message_from_path(&path).unwrap();
+        assert_eq!(last.as_deref(), Some("second"));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_render_formats() {
+        let entries = vec![
+            TranscriptEntry {
+                role: "user".to_string(),
+                text: "fix the build".to_string(),
+                timestamp: Some("2025-01-01T10:00:00Z".to_string()),
+            },
+            TranscriptEntry {
+                role: "assistant".to_string(),
+                text: "done\n".to_string(),
+                timestamp: None,
+            },
+        ];
+
+        assert_eq!(
+            render(&entries, TranscriptFormat::Markdown).unwrap(),
+            "## User\n\n_2025-01-01T10:00:00Z_\n\nfix the build\n\n## Assistant\n\ndone\n"
+        );
+        assert_eq!(render(&entries, TranscriptFormat::Plain).unwrap(), "fix the build\n\ndone\n");
+
+        let jsonl = render(&entries, TranscriptFormat::Jsonl).unwrap();
+        let parsed: Vec<TranscriptEntry> = jsonl.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(parsed, entries);
+
+        let json = render(&entries, TranscriptFormat::Json).unwrap();
+        assert_eq!(serde_json::from_str::<Vec<TranscriptEntry>>(&json).unwrap(), entries);
+
+        assert_eq!(TranscriptFormat::parse("JSONL").unwrap(), TranscriptFormat::Jsonl);
+        assert!(TranscriptFormat::parse("html").is_err());
+    }
+
+    #[test]
+    fn test_replay_filter() {
+        let entry = |role: &str, text: &str| TranscriptEntry {
+            role: role.to_string(),
+            text: text.to_string(),
+            timestamp: None,
+        };
+        let transcript = || {
+            vec![
+                entry("user", "paste"),
+                entry("assistant", "a1"),
+                entry("user", "u2"),
+                entry("assistant", "a2"),
+                entry("user", "u3"),
+            ]
+        };
+        let texts = |entries: Vec<TranscriptEntry>| entries.into_iter().map(|e| e.text).collect::<Vec<_>>();
+
+        assert_eq!(texts(ReplayFilter::default().apply(transcript())), ["paste", "u2", "u3"]);
+
+        let filter = ReplayFilter {
+            roles: vec!["user".to_string(), "assistant".to_string()],
+            skip_first: true,
+            range: ReplayFilter::parse_range(":4").unwrap(),
+        };
+        assert_eq!(texts(filter.apply(transcript())), ["a1", "u2", "a2"]);
+
+        assert_eq!(ReplayFilter::parse_range("2:").unwrap(), (Some(2), None));
+        assert!(ReplayFilter::parse_range("4:2").is_err());
+        assert!(ReplayFilter::parse_range("x:2").is_err());
+        assert!(ReplayFilter::parse_range("3").is_err());
+    }
+}
@@ -0,0 +1,130 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::state_file::StateFile;
+use crate::worker_registry::WorkerInfo;
+
+/// File name of the worker history, in the home directory or beside a project-local registry
+pub const HISTORY_FILE: &str = ".claude-worker-history.json";
+
+/// A worker that has left the active registry, with its final state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedWorker {
+    #[serde(flatten)]
+    pub info: WorkerInfo,
+    pub stopped_at: u64,
+}
+
+/// Archive of stopped workers kept in `~/.claude-worker-history.json`, or beside a project-local registry
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct WorkerHistory {
+    workers: Vec<ArchivedWorker>,
+}
+
+impl WorkerHistory {
+    /// Load history from the default file
+    pub fn load() -> Result<Self> {
+        match Self::state_file().read()? {
+            Some(content) => Ok(serde_json::from_str(&content)?),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Load history from a specific file
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Save history to the default file
+    pub fn save(&self) -> Result<()> {
+        Self::state_file().write(&serde_json::to_string_pretty(&self)?)
+    }
+
+    /// Save history to a specific file
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(&self)?)?;
+        Ok(())
+    }
+
+    /// History file in the home directory, with fallbacks for read-only homes
+    fn state_file() -> StateFile {
+        StateFile::in_home(HISTORY_FILE)
+    }
+
+    /// Append workers to the default history file
+    pub fn archive(workers: Vec<WorkerInfo>) -> Result<()> {
+        if workers.is_empty() {
+            return Ok(());
+        }
+
+        let mut history = Self::load()?;
+        history.append(workers);
+        history.save()
+    }
+
+    /// Append workers to a history file
+    pub fn archive_to(path: &Path, workers: Vec<WorkerInfo>) -> Result<()> {
+        if workers.is_empty() {
+            return Ok(());
+        }
+
+        let mut history = Self::load_from(path)?;
+        history.append(workers);
+        history.save_to(path)
+    }
+
+    /// Add workers stamped with the current time
+    fn append(&mut self, workers: Vec<WorkerInfo>) {
+        let stopped_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        self.workers
+            .extend(workers.into_iter().map(|info| ArchivedWorker { info, stopped_at }));
+    }
+
+    /// Archived workers, most recently stopped first
+    pub fn list(&self) -> Vec<&ArchivedWorker> {
+        let mut workers: Vec<&ArchivedWorker> = self.workers.iter().collect();
+        workers.sort_by_key(|w| std::cmp::Reverse(w.stopped_at));
+        workers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::worker_registry::{test_worker, WorkerStatus};
+
+    #[test]
+    fn test_archive_appends_to_history() {
+        let path = std::env::temp_dir().join(format!("claude-worker-history-test-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let worker = |name: &str, status: WorkerStatus| WorkerInfo {
+            spawned_at: 100,
+            messages_sent: 4,
+            ..test_worker(name, status)
+        };
+
+        WorkerHistory::archive_to(&path, vec![worker("first", WorkerStatus::Stopped)]).unwrap();
+        WorkerHistory::archive_to(&path, vec![worker("second", WorkerStatus::Error)]).unwrap();
+
+        let history = WorkerHistory::load_from(&path).unwrap();
+        let names: Vec<&str> = history.workers.iter().map(|w| w.info.name.as_str()).collect();
+        assert_eq!(names, ["first", "second"]);
+        assert_eq!(history.workers[1].info.status, WorkerStatus::Error);
+        assert_eq!(history.workers[0].info.messages_sent, 4);
+        assert!(history.workers[0].stopped_at > 0);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,453 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::config::InjectorConfig;
+use crate::error::InjectorError;
+use crate::payload::PayloadType;
+use crate::state_file::FileLock;
+use crate::tmux_spawner::{PaneState, SelfTestOutcome, TmuxInjectOptions, TmuxSpawner};
+use crate::worker_registry::{WorkerInfo, WorkerRegistry, WorkerStatus};
+
+/// Time between pane checks while waiting for a worker to become ready
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Pause after feeding a prompt so Claude has started on it before idle checks resume
+const FEED_PICKUP_DELAY: Duration = Duration::from_secs(2);
+
+/// MCP tool that makes a worker load an agent
+const CALL_AGENT_TOOL: &str = "mcp__agenthub_http__call_agent";
+
+/// Wait after the first agent-load command; doubled for each retry
+const AGENT_LOAD_WAIT: Duration = Duration::from_secs(3);
+
+/// Pane lines searched for the agent-ready marker
+const AGENT_MARKER_LINES: usize = 200;
+
+/// Placeholder in an agent-ready marker that stands for the agent type
+pub const AGENT_MARKER_PLACEHOLDER: &str = "{agent}";
+
+/// Pane text showing a worker has loaded its agent, as on Claude's status line
+pub const DEFAULT_AGENT_READY_MARKER: &str = "Agent: {agent}";
+
+/// Times the agent-load command is re-sent before a worker is marked `Error`
+pub const DEFAULT_AGENT_LOAD_RETRIES: u32 = 2;
+
+/// Agent type recorded for adopted workers whose transcript never loaded one
+pub const UNKNOWN_AGENT: &str = "unknown";
+
+/// Longest to wait for Claude to start in a new worker, and again for its agent
+pub const DEFAULT_READY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Longest to wait for Claude to answer the injection self-test
+pub const SELF_TEST_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// How [`WorkerManager::spawn_with_prompt`] starts a worker
+#[derive(Debug, Clone)]
+pub struct WorkerSpawnOptions {
+    pub task_id: Option<String>,
+    pub ttl_secs: Option<u64>,
+    /// Claude session to resume instead of starting a new one
+    pub resume: Option<String>,
+    /// Project root loaded as context, see [`TmuxSpawner::project_root_args`]
+    pub project_root: Option<String>,
+    /// Run [`WorkerManager::run_self_test`] before loading the agent
+    pub self_test: bool,
+    /// Longest to wait for Claude to start, and again for the agent to settle
+    pub ready_timeout: Duration,
+}
+
+impl Default for WorkerSpawnOptions {
+    fn default() -> Self {
+        Self {
+            task_id: None,
+            ttl_secs: None,
+            resume: None,
+            project_root: None,
+            self_test: false,
+            ready_timeout: DEFAULT_READY_TIMEOUT,
+        }
+    }
+}
+
+/// High-level worker lifecycle on top of [`TmuxSpawner`] and [`WorkerRegistry`]
+pub struct WorkerManager;
+
+impl WorkerManager {
+    /// Message that makes a worker load its agent
+    pub fn load_agent_command(agent_type: &str) -> String {
+        crate::payload::mcp_invocation(CALL_AGENT_TOOL, &serde_json::Value::from(agent_type))
+            .expect("agent tool name is valid")
+    }
+
+    /// Spawn a worker, load its agent and inject its first prompt
+    ///
+    /// Waits for Claude to start (bounded by `options.ready_timeout`), runs the
+    /// injection self-test when asked, then loads the agent with
+    /// [`WorkerManager::load_agent`] using the config's marker and retries.
+    /// `prompt`, or else the agent's entry in the config's `agent_prompts`, is
+    /// injected and the worker marked `Working`; with neither it is marked
+    /// `Ready`. On failure the worker is marked `Error` and left running for
+    /// inspection.
+    pub async fn spawn_with_prompt(
+        name: &str,
+        agent_type: &str,
+        working_dir: &str,
+        prompt: Option<&str>,
+        options: &WorkerSpawnOptions,
+        config: &InjectorConfig,
+    ) -> Result<WorkerInfo> {
+        let mut worker = TmuxSpawner::spawn_worker(
+            name,
+            agent_type,
+            working_dir,
+            options.task_id.clone(),
+            options.ttl_secs,
+            options.resume.clone(),
+            options.project_root.as_deref(),
+        )?;
+
+        let prompt = prompt.map(str::to_string).or_else(|| {
+            let default = config.agent_prompts.get(agent_type).cloned();
+            if default.is_some() {
+                log::info!("Using the default prompt for agent {}", agent_type);
+            }
+            default
+        });
+
+        if let Err(e) = Self::load_agent_and_prompt(name, agent_type, prompt.as_deref(), options, config).await {
+            let mut registry = WorkerRegistry::load()?;
+            registry.update_status(name, WorkerStatus::Error)?;
+            return Err(e.context(format!("Worker '{}' was spawned but could not be prompted", name)));
+        }
+
+        let mut registry = WorkerRegistry::load()?;
+        worker.status = if prompt.is_some() { WorkerStatus::Working } else { WorkerStatus::Ready };
+        registry.update_status(name, worker.status.clone())?;
+        if let Some(ref prompt) = prompt {
+            registry.record_message(name, prompt, PayloadType::UserPrompt)?;
+            worker.messages_sent += 1;
+        }
+
+        Ok(worker)
+    }
+
+    /// Make a worker load its agent, re-sending the command until it shows up
+    ///
+    /// After each attempt the pane is searched for `marker`, in which
+    /// [`AGENT_MARKER_PLACEHOLDER`] stands for the agent type; an empty marker
+    /// skips the check. The wait before checking starts at 3s and doubles with
+    /// every retry, without blocking the runtime. When the marker has not
+    /// appeared after `retries` retries, the worker is marked `Error`.
+    pub async fn load_agent(name: &str, agent_type: &str, marker: &str, retries: u32) -> Result<()> {
+        let marker = marker.replace(AGENT_MARKER_PLACEHOLDER, agent_type);
+
+        for attempt in 0..=retries {
+            if attempt > 0 {
+                log::warn!("Agent {} not loaded in worker {}, retrying ({}/{})", agent_type, name, attempt, retries);
+            }
+            TmuxSpawner::inject_message(name, &Self::load_agent_command(agent_type))?;
+            tokio::time::sleep(Self::agent_load_wait(attempt)).await;
+
+            if TmuxSpawner::capture_pane(name, Some(AGENT_MARKER_LINES))?.contains(&marker) {
+                return Ok(());
+            }
+        }
+
+        let mut registry = WorkerRegistry::load()?;
+        registry.update_status(name, WorkerStatus::Error)?;
+        anyhow::bail!(
+            "Agent {} did not load in worker '{}': '{}' never appeared after {} attempt(s)",
+            agent_type,
+            name,
+            marker,
+            retries + 1
+        )
+    }
+
+    /// Time to wait for the agent after load attempt `attempt` (0 for the first)
+    fn agent_load_wait(attempt: u32) -> Duration {
+        AGENT_LOAD_WAIT.saturating_mul(2u32.saturating_pow(attempt))
+    }
+
+    async fn load_agent_and_prompt(
+        name: &str,
+        agent_type: &str,
+        prompt: Option<&str>,
+        options: &WorkerSpawnOptions,
+        config: &InjectorConfig,
+    ) -> Result<()> {
+        let started = Instant::now();
+
+        Self::wait_until_ready(name, options.ready_timeout)?;
+        if options.self_test {
+            Self::run_self_test(name)?;
+        }
+
+        log::info!("Loading agent {} in worker {}", agent_type, name);
+        Self::load_agent(name, agent_type, &config.agent_ready_marker, config.agent_load_retries).await?;
+
+        if let Some(prompt) = prompt {
+            Self::wait_until_ready(name, options.ready_timeout.saturating_sub(started.elapsed()))
+                .context(format!("Agent {} did not finish loading", agent_type))?;
+            TmuxSpawner::inject_message(name, prompt)?;
+        }
+        Ok(())
+    }
+
+    /// Inject the self-test prompt into a fresh session and fail unless Claude answers it
+    ///
+    /// See [`TmuxSpawner::self_test`]; Claude gets [`SELF_TEST_TIMEOUT`] to answer.
+    pub fn run_self_test(name: &str) -> Result<()> {
+        log::info!("Running injection self-test in {}", name);
+
+        match TmuxSpawner::self_test(name, SELF_TEST_TIMEOUT)? {
+            SelfTestOutcome::Passed => {
+                log::info!("Injection self-test passed in {}", name);
+                Ok(())
+            }
+            SelfTestOutcome::NoResponse => anyhow::bail!(
+                "Injection self-test failed: the prompt reached '{}' but Claude did not answer within {:?}",
+                name,
+                SELF_TEST_TIMEOUT
+            ),
+            SelfTestOutcome::NotDelivered => anyhow::bail!(
+                "Injection self-test failed: the prompt never appeared in '{}' (check that tmux send-keys works)",
+                name
+            ),
+        }
+    }
+
+    /// Tmux sessions running Claude that are not registered as workers
+    pub fn discover_unregistered() -> Result<Vec<String>> {
+        let registry = WorkerRegistry::load()?;
+        let registered: Vec<&str> = registry.list_all().iter().map(|w| w.tmux_session.as_str()).collect();
+
+        let mut sessions: Vec<String> = TmuxSpawner::list_sessions()?
+            .into_iter()
+            .filter(|session| !registered.contains(&session.as_str()))
+            .filter(|session| TmuxSpawner::is_claude_running(session))
+            .collect();
+        sessions.sort();
+        Ok(sessions)
+    }
+
+    /// Register a tmux session already running Claude as a worker of the same name
+    ///
+    /// The agent type is read from the `call_agent("...")` that loaded it in
+    /// the session's transcript, or [`UNKNOWN_AGENT`] when there is none.
+    pub fn adopt(tmux_session: &str) -> Result<WorkerInfo> {
+        let mut registry = WorkerRegistry::load()?;
+        if registry.exists(tmux_session) {
+            anyhow::bail!("A worker named '{}' already exists", tmux_session);
+        }
+
+        let pane = TmuxSpawner::pane_info(&TmuxSpawner::resolve_target(tmux_session)?)?;
+        let session_id = TmuxSpawner::resolve_session_id(tmux_session)?;
+        let agent_type = session_id
+            .as_deref()
+            .and_then(|id| crate::TranscriptReader::infer_agent_type(id).ok().flatten())
+            .unwrap_or_else(|| UNKNOWN_AGENT.to_string());
+
+        let worker = WorkerInfo {
+            session_id,
+            ..WorkerInfo::new(tmux_session, &agent_type, &pane.current_path, WorkerStatus::Ready)
+        };
+
+        registry.register(worker.clone())?;
+        Ok(worker)
+    }
+
+    /// Wait until Claude runs in the session and its pane has settled
+    pub fn wait_until_ready(session_name: &str, timeout: Duration) -> Result<()> {
+        let started = Instant::now();
+        let mut previous: Option<String> = None;
+
+        loop {
+            if TmuxSpawner::is_claude_running(session_name) {
+                let pane = TmuxSpawner::capture_pane(session_name, None)?;
+                if Self::pane_settled(previous.as_deref(), &pane) {
+                    return Ok(());
+                }
+                previous = Some(pane);
+            } else if !TmuxSpawner::session_exists(session_name) {
+                anyhow::bail!("Tmux session '{}' exited before Claude was ready", session_name);
+            }
+
+            if started.elapsed() >= timeout {
+                return Err(anyhow::Error::new(InjectorError::Timeout {
+                    limit: timeout,
+                    partial_output: previous.unwrap_or_default(),
+                })
+                .context(format!("Claude in '{}' was not ready", session_name)));
+            }
+            std::thread::sleep(READY_POLL_INTERVAL);
+        }
+    }
+
+    /// Poll the registry until a worker has `status`
+    ///
+    /// Each poll re-reads the registry and reconciles it with live tmux
+    /// sessions in memory, so a worker whose session died shows up as
+    /// `Stopped`; the reconciled registry is saved once, when waiting ends.
+    /// Fails early when the worker is unknown or stopped, and with
+    /// [`InjectorError::Timeout`] once `timeout` has passed.
+    pub fn wait_for_status(name: &str, status: &WorkerStatus, timeout: Duration) -> Result<WorkerInfo> {
+        let started = Instant::now();
+
+        loop {
+            let mut registry = WorkerRegistry::load()?;
+            let (_, changed) = registry.reconcile_unsaved();
+            let finish = |registry: &WorkerRegistry| if changed { registry.save() } else { Ok(()) };
+
+            let worker = registry
+                .get(name)
+                .context(format!("Worker '{}' not found in registry", name))?;
+            if worker.status == *status {
+                finish(&registry)?;
+                return Ok(worker.clone());
+            }
+            if worker.status == WorkerStatus::Stopped {
+                finish(&registry)?;
+                anyhow::bail!("Worker '{}' stopped before becoming {}", name, status);
+            }
+
+            if started.elapsed() >= timeout {
+                finish(&registry)?;
+                return Err(anyhow::Error::new(InjectorError::Timeout {
+                    limit: timeout,
+                    partial_output: String::new(),
+                })
+                .context(format!("Worker '{}' is still {}, not {}", name, worker.status, status)));
+            }
+            std::thread::sleep(READY_POLL_INTERVAL);
+        }
+    }
+
+    /// Send a worker the prompts in `queue`, one line at a time, each once it is idle
+    ///
+    /// A line is removed from the top of the file once it has been sent, so
+    /// the file always holds what is left and an interrupted feed resumes with
+    /// the unsent line; blank lines are skipped. Lines can be appended while
+    /// feeding by writers that hold the same lock, e.g.
+    /// `flock <queue>.lock sh -c 'echo "next task" >> <queue>'`. Waiting for the
+    /// worker to go idle is bounded by `idle_timeout` per prompt, and prompts
+    /// are sent with `options`. Returns how many prompts were sent.
+    pub fn feed_from_file(
+        name: &str,
+        queue: &Path,
+        idle_timeout: Duration,
+        options: &TmuxInjectOptions,
+    ) -> Result<usize> {
+        let mut registry = WorkerRegistry::load()?;
+        let tmux_session = registry
+            .get(name)
+            .map(|w| w.tmux_session.clone())
+            .unwrap_or_else(|| name.to_string());
+
+        let mut sent = 0;
+        loop {
+            Self::wait_until_ready(&tmux_session, idle_timeout)
+                .context(format!("Worker '{}' did not go idle after {} prompt(s)", name, sent))?;
+
+            let Some(prompt) = Self::peek_line(queue)? else {
+                return Ok(sent);
+            };
+
+            log::info!("Feeding prompt {} to worker {}", sent + 1, name);
+            let text = crate::wrap_injection(&prompt);
+            TmuxSpawner::inject_message_with_options(&tmux_session, &text, options)?;
+            Self::remove_line(queue, &prompt)?;
+            registry.reload()?;
+            registry.record_message(name, &text, PayloadType::UserPrompt)?;
+            sent += 1;
+
+            std::thread::sleep(FEED_PICKUP_DELAY);
+        }
+    }
+
+    /// First non-blank line of a file, trimmed, left in place
+    fn peek_line(path: &Path) -> Result<Option<String>> {
+        let _lock = FileLock::acquire(path).context(format!("Failed to lock queue {}", path.display()))?;
+        let content = std::fs::read_to_string(path).context(format!("Failed to read queue {}", path.display()))?;
+
+        Ok(content
+            .lines()
+            .find(|line| !line.trim().is_empty())
+            .map(|line| line.trim().to_string()))
+    }
+
+    /// Remove the first non-blank line of a file if it is still `sent`
+    ///
+    /// The file is re-read under the lock, so lines appended since
+    /// [`WorkerManager::peek_line`] are kept.
+    fn remove_line(path: &Path, sent: &str) -> Result<()> {
+        let _lock = FileLock::acquire(path).context(format!("Failed to lock queue {}", path.display()))?;
+        let content = std::fs::read_to_string(path).context(format!("Failed to read queue {}", path.display()))?;
+        let mut lines = content.lines().skip_while(|line| line.trim().is_empty());
+
+        if lines.next().map(str::trim) != Some(sent) {
+            return Ok(());
+        }
+
+        let rest: String = lines.map(|line| format!("{}\n", line)).collect();
+        std::fs::write(path, rest).context(format!("Failed to update queue {}", path.display()))?;
+        Ok(())
+    }
+
+    /// A pane is settled when it shows something, stopped changing and is not generating
+    fn pane_settled(previous: Option<&str>, current: &str) -> bool {
+        !current.trim().is_empty() && previous == Some(current) && PaneState::detect(current) == PaneState::Idle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_peek_and_remove_line() {
+        let path = std::env::temp_dir().join(format!("claude-feed-test-{}.txt", std::process::id()));
+        std::fs::write(&path, "\n  first task  \n\nsecond task\n").unwrap();
+
+        let first = WorkerManager::peek_line(&path).unwrap().unwrap();
+        assert_eq!(first, "first task");
+        assert_eq!(WorkerManager::peek_line(&path).unwrap().as_deref(), Some("first task"));
+
+        // Appended while the first line was being sent
+        std::fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(b"third task\n").unwrap();
+        WorkerManager::remove_line(&path, &first).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "\nsecond task\nthird task\n");
+
+        // Already gone, e.g. edited out by hand: nothing else is removed
+        WorkerManager::remove_line(&path, &first).unwrap();
+        assert_eq!(WorkerManager::peek_line(&path).unwrap().as_deref(), Some("second task"));
+
+        std::fs::write(&path, "\n \n").unwrap();
+        assert_eq!(WorkerManager::peek_line(&path).unwrap(), None);
+
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(format!("{}.lock", path.display())).unwrap();
+    }
+
+    #[test]
+    fn test_agent_load_wait_doubles() {
+        assert_eq!(WorkerManager::agent_load_wait(0), Duration::from_secs(3));
+        assert_eq!(WorkerManager::agent_load_wait(1), Duration::from_secs(6));
+        assert_eq!(WorkerManager::agent_load_wait(2), Duration::from_secs(12));
+        assert_eq!(WorkerManager::agent_load_wait(40), AGENT_LOAD_WAIT.saturating_mul(u32::MAX));
+    }
+
+    #[test]
+    fn test_pane_settled() {
+        let idle = "╭───╮\n│ > │\n╰───╯\n  ? for shortcuts";
+        let generating = "✻ Thinking… (3s · esc to interrupt)";
+
+        assert!(WorkerManager::pane_settled(Some(idle), idle));
+        assert!(!WorkerManager::pane_settled(None, idle));
+        assert!(!WorkerManager::pane_settled(Some("loading"), idle));
+        assert!(!WorkerManager::pane_settled(Some(generating), generating));
+        assert!(!WorkerManager::pane_settled(Some(""), ""));
+    }
+}
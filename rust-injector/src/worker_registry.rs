@@ -1,8 +1,12 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::payload::PayloadType;
+use crate::state_file::StateFile;
 
 /// Worker metadata for orchestration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,6 +19,84 @@ pub struct WorkerInfo {
     pub spawned_at: u64,
     pub status: WorkerStatus,
     pub messages_sent: u32,
+    /// Stop the worker this many seconds after it was spawned
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+    /// Claude conversation running in the worker's pane, once resolved
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Claude session the worker was spawned to continue (`--resume`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resumed_from: Option<String>,
+    /// When the worker was last sent a message (unix seconds)
+    #[serde(default)]
+    pub last_message_at: Option<u64>,
+    /// Most recent messages sent to the worker, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<MessageRecord>,
+}
+
+/// Characters of a message kept in its [`MessageRecord`]
+pub const MESSAGE_PREVIEW_CHARS: usize = 200;
+
+/// A message sent to a worker, as kept in [`WorkerInfo::history`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageRecord {
+    /// When it was sent (unix seconds)
+    pub timestamp: u64,
+    /// The first [`MESSAGE_PREVIEW_CHARS`] characters of the message
+    #[serde(alias = "content")]
+    pub preview: String,
+    /// Length of the whole message in characters
+    #[serde(default)]
+    pub length: usize,
+    pub kind: PayloadType,
+}
+
+impl MessageRecord {
+    /// Whether the preview is shorter than the message it came from
+    pub fn is_truncated(&self) -> bool {
+        self.length > self.preview.chars().count()
+    }
+}
+
+impl WorkerInfo {
+    /// A worker running in a tmux session of its own name, spawned now and not yet messaged
+    pub fn new(name: &str, agent_type: &str, working_dir: &str, status: WorkerStatus) -> Self {
+        Self {
+            name: name.to_string(),
+            agent_type: agent_type.to_string(),
+            task_id: None,
+            tmux_session: name.to_string(),
+            working_dir: working_dir.to_string(),
+            spawned_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            status,
+            messages_sent: 0,
+            ttl_secs: None,
+            session_id: None,
+            resumed_from: None,
+            last_message_at: None,
+            history: Vec::new(),
+        }
+    }
+
+    /// Check whether the worker has outlived its TTL at `now` (unix seconds)
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.ttl_secs
+            .is_some_and(|ttl| now >= self.spawned_at.saturating_add(ttl))
+    }
+}
+
+/// A `coding-agent` worker in `/tmp` spawned at time 0, for tests to adjust
+#[cfg(test)]
+pub(crate) fn test_worker(name: &str, status: WorkerStatus) -> WorkerInfo {
+    WorkerInfo {
+        spawned_at: 0,
+        ..WorkerInfo::new(name, "coding-agent", "/tmp", status)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -40,10 +122,114 @@ impl std::fmt::Display for WorkerStatus {
     }
 }
 
+impl WorkerStatus {
+    /// Parse a status name as printed by `Display` (e.g. `idle`)
+    pub fn parse(status: &str) -> anyhow::Result<Self> {
+        match status.to_lowercase().as_str() {
+            "starting" => Ok(WorkerStatus::Starting),
+            "ready" => Ok(WorkerStatus::Ready),
+            "working" => Ok(WorkerStatus::Working),
+            "idle" => Ok(WorkerStatus::Idle),
+            "error" => Ok(WorkerStatus::Error),
+            "stopped" => Ok(WorkerStatus::Stopped),
+            _ => anyhow::bail!("Invalid status: {}", status),
+        }
+    }
+}
+
+/// A damaged worker entry found by [`WorkerRegistry::load_checked`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegistryIssue {
+    /// Registry key of the entry
+    pub worker: String,
+    pub problem: String,
+    /// The entry could not be repaired and was left out of the registry; it
+    /// stays in the file as it was until `registry-check --repair`
+    pub dropped: bool,
+}
+
+impl RegistryIssue {
+    fn repaired(worker: &str, problem: String) -> Self {
+        Self { worker: worker.to_string(), problem, dropped: false }
+    }
+
+    fn dropped(worker: &str, problem: String) -> Self {
+        Self { worker: worker.to_string(), problem, dropped: true }
+    }
+}
+
+impl std::fmt::Display for RegistryIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let outcome = if self.dropped { "dropped" } else { "repaired" };
+        write!(f, "worker '{}': {} ({})", self.worker, self.problem, outcome)
+    }
+}
+
+fn warn_issues(issues: &[RegistryIssue]) {
+    for issue in issues {
+        log::warn!("Worker registry: {} - run `claude-inject registry-check` for details", issue);
+    }
+}
+
+/// Replace a missing or unknown `status` in a raw worker entry, describing the change
+///
+/// Lower-case names (e.g. `idle`) are mapped to their status; anything else
+/// becomes `Error` so the worker shows up as needing attention.
+fn repair_status(entry: &mut serde_json::Value) -> Option<String> {
+    let current = entry.get("status").cloned();
+    if current.clone().is_some_and(|status| serde_json::from_value::<WorkerStatus>(status).is_ok()) {
+        return None;
+    }
+
+    let repaired = current
+        .as_ref()
+        .and_then(|status| status.as_str())
+        .and_then(|status| WorkerStatus::parse(status).ok())
+        .unwrap_or(WorkerStatus::Error);
+    let problem = match current {
+        Some(status) => format!("invalid status {}, set to {}", status, repaired),
+        None => format!("missing status, set to {}", repaired),
+    };
+
+    entry.as_object_mut()?.insert("status".to_string(), serde_json::to_value(&repaired).ok()?);
+    Some(problem)
+}
+
+/// Name of a project-local worker registry, found by walking up from the working directory
+pub const PROJECT_REGISTRY_FILE: &str = ".claude-workers.json";
+
+/// Name of the home-level registry file
+pub const GLOBAL_REGISTRY_FILE: &str = ".claude-worker-registry.json";
+
+/// Set by `--global` to ignore project-local registries
+static FORCE_GLOBAL: AtomicBool = AtomicBool::new(false);
+
+/// Always use the home-level registry for the rest of the process
+pub fn force_global_registry() {
+    FORCE_GLOBAL.store(true, Ordering::SeqCst);
+}
+
+/// Messages kept per worker unless configured otherwise
+pub const DEFAULT_MESSAGE_HISTORY_LEN: usize = 50;
+
+/// Set from `message_history_len` in the config
+static MESSAGE_HISTORY_LEN: AtomicUsize = AtomicUsize::new(DEFAULT_MESSAGE_HISTORY_LEN);
+
+/// Keep at most `len` messages in each worker's history for the rest of the process
+pub fn set_message_history_len(len: usize) {
+    MESSAGE_HISTORY_LEN.store(len, Ordering::SeqCst);
+}
+
 /// Worker registry for tracking active sessions
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WorkerRegistry {
     workers: HashMap<String, WorkerInfo>,
+    /// Project-local registry file this was loaded from; `None` for the global one
+    #[serde(skip)]
+    path: Option<PathBuf>,
+    /// Entries that could not be loaded, written back unchanged by `save`
+    #[serde(skip)]
+    unreadable: BTreeMap<String, serde_json::Value>,
 }
 
 impl WorkerRegistry {
@@ -51,37 +237,247 @@ impl WorkerRegistry {
     pub fn new() -> Self {
         Self {
             workers: HashMap::new(),
+            path: None,
+            unreadable: BTreeMap::new(),
         }
     }
 
     /// Load registry from file
+    ///
+    /// A `.claude-workers.json` in the working directory or one of its parents
+    /// is used in preference to the global registry, unless `--global` was given.
+    /// Damaged worker entries are repaired or dropped with a warning, see
+    /// [`WorkerRegistry::load_checked`].
     pub fn load() -> Result<Self> {
-        let path = Self::get_registry_path();
-        if !path.exists() {
+        let (registry, issues) = Self::load_checked()?;
+        warn_issues(&issues);
+        Ok(registry)
+    }
+
+    /// Load the registry and report the worker entries that had to be repaired or dropped
+    ///
+    /// Only a file that is not JSON, or has no `workers` object, fails to load;
+    /// problems with single workers come back as [`RegistryIssue`]s alongside
+    /// the repaired registry, which is not saved. Dropped entries are kept
+    /// aside and saved back as they were, until
+    /// [`WorkerRegistry::discard_unreadable`] is called.
+    pub fn load_checked() -> Result<(Self, Vec<RegistryIssue>)> {
+        if !FORCE_GLOBAL.load(Ordering::SeqCst) {
+            if let Some(path) = std::env::current_dir().ok().and_then(|dir| Self::find_project_registry(&dir)) {
+                return Self::read_project(&path);
+            }
+        }
+
+        Self::read_global()
+    }
+
+    fn read_global() -> Result<(Self, Vec<RegistryIssue>)> {
+        match Self::state_file().read()? {
+            Some(content) => Self::parse(&content),
+            None => Ok((Self::new(), Vec::new())),
+        }
+    }
+
+    /// Parse registry JSON read from somewhere else, such as another host
+    ///
+    /// Empty content is an empty registry. The result is a read-only copy:
+    /// saving it would write the local home-level registry.
+    pub fn from_json(content: &str) -> Result<Self> {
+        if content.trim().is_empty() {
             return Ok(Self::new());
         }
 
-        let content = fs::read_to_string(&path)?;
-        let registry: WorkerRegistry = serde_json::from_str(&content)?;
+        let (registry, issues) = Self::parse(content)?;
+        warn_issues(&issues);
+        Ok(registry)
+    }
+
+    /// Re-read the registry file this registry came from, replacing the in-memory workers
+    ///
+    /// Lets a long-lived process see workers that other invocations spawned or
+    /// removed. Returns the names of workers that were not known before.
+    ///
+    /// There is no locking: every `save` writes the whole registry, so the last
+    /// writer wins and a save from a stale copy undoes changes made since it was
+    /// loaded. Reload right before changing the registry to keep that window short.
+    pub fn reload(&mut self) -> Result<Vec<String>> {
+        let (fresh, issues) = match self.path {
+            Some(ref path) => Self::read_project(path)?,
+            None => Self::read_global()?,
+        };
+        warn_issues(&issues);
+
+        let mut added: Vec<String> = fresh
+            .workers
+            .keys()
+            .filter(|name| !self.workers.contains_key(*name))
+            .cloned()
+            .collect();
+        added.sort();
+
+        self.workers = fresh.workers;
+        self.unreadable = fresh.unreadable;
+        Ok(added)
+    }
+
+    /// Load a project-local registry; an empty file is an empty registry
+    pub fn load_project(path: &Path) -> Result<Self> {
+        let (registry, issues) = Self::read_project(path)?;
+        warn_issues(&issues);
         Ok(registry)
     }
 
+    fn read_project(path: &Path) -> Result<(Self, Vec<RegistryIssue>)> {
+        let content = fs::read_to_string(path).context(format!("Failed to read {}", path.display()))?;
+
+        let (mut registry, issues) = if content.trim().is_empty() {
+            (Self::new(), Vec::new())
+        } else {
+            Self::parse(&content).context(format!("Invalid worker registry: {}", path.display()))?
+        };
+        registry.path = Some(path.to_path_buf());
+
+        Ok((registry, issues))
+    }
+
+    /// Read registry JSON one worker at a time, so one bad entry does not lose the rest
+    fn parse(content: &str) -> Result<(Self, Vec<RegistryIssue>)> {
+        let value: serde_json::Value = serde_json::from_str(content)?;
+        let Some(serde_json::Value::Object(entries)) = value.get("workers") else {
+            anyhow::bail!("Worker registry has no \"workers\" object");
+        };
+
+        let mut registry = Self::new();
+        let mut issues = Vec::new();
+        for (key, entry) in entries {
+            let mut entry = entry.clone();
+            if let Some(problem) = repair_status(&mut entry) {
+                issues.push(RegistryIssue::repaired(key, problem));
+            }
+
+            match serde_json::from_value::<WorkerInfo>(entry) {
+                Ok(worker) => {
+                    registry.workers.insert(key.clone(), worker);
+                }
+                Err(e) => {
+                    issues.push(RegistryIssue::dropped(key, format!("unreadable entry: {}", e)));
+                    registry.unreadable.insert(key.clone(), entries[key].clone());
+                }
+            }
+        }
+
+        issues.extend(registry.validate());
+        issues.sort_by(|a, b| a.worker.cmp(&b.worker));
+        Ok((registry, issues))
+    }
+
+    /// Check every worker has a name matching its key and a tmux session, repairing those that do not
+    ///
+    /// A worker whose registry key is blank cannot be addressed and is
+    /// removed; otherwise the name is reset to the key and a blank tmux
+    /// session to the worker's name, which is what `spawn-worker` uses.
+    /// Statuses are checked while parsing, see [`WorkerRegistry::load_checked`].
+    pub fn validate(&mut self) -> Vec<RegistryIssue> {
+        let mut issues = Vec::new();
+
+        let unreadable = &mut self.unreadable;
+        self.workers.retain(|key, worker| {
+            if key.trim().is_empty() {
+                issues.push(RegistryIssue::dropped(key, "blank worker name".to_string()));
+                if let Ok(entry) = serde_json::to_value(&*worker) {
+                    unreadable.insert(key.clone(), entry);
+                }
+                return false;
+            }
+            if worker.name != *key {
+                issues.push(RegistryIssue::repaired(
+                    key,
+                    format!("name {:?} did not match its registry key", worker.name),
+                ));
+                worker.name = key.clone();
+            }
+            if worker.tmux_session.trim().is_empty() {
+                issues.push(RegistryIssue::repaired(key, "blank tmux session, set to the worker name".to_string()));
+                worker.tmux_session = key.clone();
+            }
+            true
+        });
+
+        issues.sort_by(|a, b| a.worker.cmp(&b.worker));
+        issues
+    }
+
+    /// Find the nearest project-local registry at or above `dir`
+    pub fn find_project_registry(dir: &Path) -> Option<PathBuf> {
+        dir.ancestors()
+            .map(|ancestor| ancestor.join(PROJECT_REGISTRY_FILE))
+            .find(|candidate| candidate.is_file())
+    }
+
+    /// Project-local registry file in use, or `None` for the global registry
+    pub fn project_path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Forget the entries that could not be loaded, so the next save leaves them out
+    ///
+    /// Returns how many there were.
+    pub fn discard_unreadable(&mut self) -> usize {
+        std::mem::take(&mut self.unreadable).len()
+    }
+
     /// Save registry to file
+    ///
+    /// Entries that could not be loaded are written back unchanged.
     pub fn save(&self) -> Result<()> {
-        let path = Self::get_registry_path();
-        let content = serde_json::to_string_pretty(&self)?;
-        fs::write(&path, content)?;
-        Ok(())
+        let mut value = serde_json::to_value(self)?;
+        if let Some(serde_json::Value::Object(workers)) = value.get_mut("workers") {
+            for (key, entry) in &self.unreadable {
+                if !workers.contains_key(key) {
+                    workers.insert(key.clone(), entry.clone());
+                }
+            }
+        }
+        let content = serde_json::to_string_pretty(&value)?;
+
+        match self.path {
+            Some(ref path) => fs::write(path, content).context(format!("Failed to write {}", path.display())),
+            None => Self::state_file().write(&content),
+        }
+    }
+
+    /// Registry file in the home directory, with fallbacks for read-only homes
+    fn state_file() -> StateFile {
+        StateFile::in_home(GLOBAL_REGISTRY_FILE)
+    }
+
+    /// History file for workers leaving this registry: beside a project-local
+    /// registry, or `None` for the home-level history
+    pub fn history_path(&self) -> Option<PathBuf> {
+        self.path
+            .as_deref()
+            .and_then(Path::parent)
+            .map(|dir| dir.join(crate::worker_history::HISTORY_FILE))
     }
 
-    /// Get registry file path
-    fn get_registry_path() -> PathBuf {
-        let home = dirs::home_dir().expect("Cannot find home directory");
-        home.join(".claude-worker-registry.json")
+    /// The history that workers leaving this registry are archived into
+    pub fn load_history(&self) -> Result<crate::WorkerHistory> {
+        match self.history_path() {
+            Some(path) => crate::WorkerHistory::load_from(&path),
+            None => crate::WorkerHistory::load(),
+        }
+    }
+
+    fn archive_workers(&self, workers: Vec<WorkerInfo>) -> Result<()> {
+        match self.history_path() {
+            Some(path) => crate::WorkerHistory::archive_to(&path, workers),
+            None => crate::WorkerHistory::archive(workers),
+        }
     }
 
     /// Register a new worker
     pub fn register(&mut self, worker: WorkerInfo) -> Result<()> {
+        self.unreadable.remove(&worker.name);
         self.workers.insert(worker.name.clone(), worker);
         self.save()?;
         Ok(())
@@ -94,6 +490,19 @@ impl WorkerRegistry {
         Ok(())
     }
 
+    /// Remove a worker and keep its record, with `final_status`, in the worker history
+    pub fn archive(&mut self, name: &str, final_status: WorkerStatus) -> Result<Option<WorkerInfo>> {
+        let Some(mut worker) = self.workers.remove(name) else {
+            return Ok(None);
+        };
+
+        worker.status = final_status;
+        self.archive_workers(vec![worker.clone()])?;
+        self.save()?;
+
+        Ok(Some(worker))
+    }
+
     /// Get worker info
     pub fn get(&self, name: &str) -> Option<&WorkerInfo> {
         self.workers.get(name)
@@ -113,10 +522,55 @@ impl WorkerRegistry {
         Ok(())
     }
 
-    /// Increment message counter
-    pub fn increment_messages(&mut self, name: &str) -> Result<()> {
+    /// Record the Claude conversation a worker is running
+    pub fn set_session_id(&mut self, name: &str, session_id: Option<String>) -> Result<()> {
         if let Some(worker) = self.workers.get_mut(name) {
+            worker.session_id = session_id;
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// Mark workers running the Claude conversation `session_id` as `Stopped`
+    ///
+    /// Returns the names of the workers that were changed.
+    pub fn mark_session_stopped(&mut self, session_id: &str) -> Result<Vec<String>> {
+        let mut stopped = Vec::new();
+        for (name, worker) in self.workers.iter_mut() {
+            if worker.session_id.as_deref() == Some(session_id) && worker.status != WorkerStatus::Stopped {
+                worker.status = WorkerStatus::Stopped;
+                stopped.push(name.clone());
+            }
+        }
+
+        if !stopped.is_empty() {
+            self.save()?;
+        }
+        Ok(stopped)
+    }
+
+    /// Count a message sent to a worker and add it to the worker's history
+    ///
+    /// The history keeps the latest messages only, as many as
+    /// [`set_message_history_len`] allows. Unknown workers are ignored.
+    pub fn record_message(&mut self, name: &str, content: &str, kind: PayloadType) -> Result<()> {
+        if let Some(worker) = self.workers.get_mut(name) {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_secs();
+
             worker.messages_sent += 1;
+            worker.last_message_at = Some(now);
+            worker.history.push(MessageRecord {
+                timestamp: now,
+                preview: content.chars().take(MESSAGE_PREVIEW_CHARS).collect(),
+                length: content.chars().count(),
+                kind,
+            });
+
+            let limit = MESSAGE_HISTORY_LEN.load(Ordering::SeqCst);
+            let excess = worker.history.len().saturating_sub(limit);
+            worker.history.drain(..excess);
             self.save()?;
         }
         Ok(())
@@ -165,24 +619,218 @@ impl WorkerRegistry {
         self.workers.len()
     }
 
+    /// Workers as they are now: one whose tmux session is gone shows as `Stopped`
+    ///
+    /// Unlike [`WorkerRegistry::reconcile`], nothing is changed or saved.
+    pub fn live_view(&self) -> Vec<WorkerInfo> {
+        self.workers
+            .values()
+            .map(|w| {
+                let mut worker = w.clone();
+                if worker.status != WorkerStatus::Stopped && !crate::TmuxSpawner::session_exists(&worker.tmux_session) {
+                    worker.status = WorkerStatus::Stopped;
+                }
+                worker
+            })
+            .collect()
+    }
+
+    /// Mark workers whose tmux session has disappeared as `Stopped`
+    ///
+    /// Returns the names of the workers that were changed.
+    pub fn reconcile(&mut self) -> Result<Vec<String>> {
+        let (gone, changed) = self.reconcile_unsaved();
+        if changed {
+            self.save()?;
+        }
+
+        Ok(gone)
+    }
+
+    /// [`WorkerRegistry::reconcile`] without saving, for callers that poll and save once at the end
+    ///
+    /// Returns the workers marked `Stopped`, and whether anything changed,
+    /// including repairs from [`WorkerRegistry::repair_mismatches`].
+    pub fn reconcile_unsaved(&mut self) -> (Vec<String>, bool) {
+        let repaired = self.repair_with(crate::TmuxSpawner::session_exists);
+
+        let gone: Vec<String> = self.workers
+            .values()
+            .filter(|w| w.status != WorkerStatus::Stopped)
+            .filter(|w| !crate::TmuxSpawner::session_exists(&w.tmux_session))
+            .map(|w| w.name.clone())
+            .collect();
+
+        for name in &gone {
+            log::info!("Worker {} has no live tmux session, marking as stopped", name);
+            if let Some(worker) = self.workers.get_mut(name) {
+                worker.status = WorkerStatus::Stopped;
+            }
+        }
+
+        let changed = !repaired.is_empty() || !gone.is_empty();
+        (gone, changed)
+    }
+
+    /// Repair entries whose name or tmux session disagree with their registry key
+    ///
+    /// A worker's `name` is reset to its key, and a `tmux_session` that no longer
+    /// exists is pointed back at the session named after the worker when that one
+    /// is live. Other mismatches, and tmux sessions shared by several workers, are
+    /// only logged. Returns the names of the workers that were repaired.
+    pub fn repair_mismatches(&mut self) -> Result<Vec<String>> {
+        let repaired = self.repair_with(crate::TmuxSpawner::session_exists);
+
+        if !repaired.is_empty() {
+            self.save()?;
+        }
+
+        Ok(repaired)
+    }
+
+    fn repair_with(&mut self, session_exists: impl Fn(&str) -> bool) -> Vec<String> {
+        let mut repaired = Vec::new();
+        let mut owners: HashMap<String, Vec<String>> = HashMap::new();
+
+        for (key, worker) in self.workers.iter_mut() {
+            if worker.name != *key {
+                log::warn!("Worker {} was registered under the name {}, repairing", key, worker.name);
+                worker.name = key.clone();
+                repaired.push(key.clone());
+            }
+
+            if worker.tmux_session != *key {
+                if !session_exists(&worker.tmux_session) && session_exists(key) {
+                    log::warn!(
+                        "Worker {} pointed at missing tmux session {}, repairing",
+                        key,
+                        worker.tmux_session
+                    );
+                    worker.tmux_session = key.clone();
+                    if !repaired.contains(key) {
+                        repaired.push(key.clone());
+                    }
+                } else {
+                    log::warn!("Worker {} runs in tmux session {}", key, worker.tmux_session);
+                }
+            }
+
+            owners.entry(worker.tmux_session.clone()).or_default().push(key.clone());
+        }
+
+        for (tmux_session, mut names) in owners {
+            if names.len() > 1 {
+                names.sort();
+                log::warn!("Workers {} share tmux session {}", names.join(", "), tmux_session);
+            }
+        }
+
+        repaired.sort();
+        repaired
+    }
+
+    /// Mark workers stuck in `Starting` as `Error`
+    ///
+    /// A worker is stuck when it has been `Starting` for longer than
+    /// `threshold_secs` and its tmux session shows no running Claude.
+    /// Returns the names of the workers that were changed.
+    pub fn sweep_stuck_starting(&mut self, threshold_secs: u64) -> Result<Vec<String>> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let stuck: Vec<String> = self.workers
+            .values()
+            .filter(|w| w.status == WorkerStatus::Starting)
+            .filter(|w| now.saturating_sub(w.spawned_at) > threshold_secs)
+            .filter(|w| !crate::TmuxSpawner::is_claude_running(&w.tmux_session))
+            .map(|w| w.name.clone())
+            .collect();
+
+        for name in &stuck {
+            log::warn!("Worker {} stuck in starting state, marking as error", name);
+            if let Some(worker) = self.workers.get_mut(name) {
+                worker.status = WorkerStatus::Error;
+            }
+        }
+
+        if !stuck.is_empty() {
+            self.save()?;
+        }
+
+        Ok(stuck)
+    }
+
+    /// Stop and unregister workers whose TTL has passed
+    ///
+    /// Workers without a TTL are left alone. Returns the names of the removed workers.
+    pub fn sweep_expired(&mut self) -> Result<Vec<String>> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let expired: Vec<String> = self.workers
+            .values()
+            .filter(|w| w.is_expired(now))
+            .map(|w| w.name.clone())
+            .collect();
+
+        let mut archived = Vec::new();
+        for name in &expired {
+            if let Some(mut worker) = self.workers.remove(name) {
+                log::info!(
+                    "Worker {} exceeded its TTL of {}s, stopping",
+                    name,
+                    worker.ttl_secs.unwrap_or_default()
+                );
+                if crate::TmuxSpawner::session_exists(&worker.tmux_session) {
+                    crate::TmuxSpawner::kill_session(&worker.tmux_session)?;
+                }
+                worker.status = WorkerStatus::Stopped;
+                archived.push(worker);
+            }
+        }
+
+        if !expired.is_empty() {
+            self.archive_workers(archived)?;
+            self.save()?;
+        }
+
+        Ok(expired)
+    }
+
     /// Cleanup stopped workers
     pub fn cleanup_stopped(&mut self) -> Result<usize> {
+        Ok(self.reap_stopped()?.len())
+    }
+
+    /// Move stopped workers into the worker history, returning their names
+    pub fn reap_stopped(&mut self) -> Result<Vec<String>> {
         let stopped: Vec<String> = self.workers
             .iter()
             .filter(|(_, w)| w.status == WorkerStatus::Stopped)
             .map(|(name, _)| name.clone())
             .collect();
 
-        let count = stopped.len();
-        for name in stopped {
-            self.workers.remove(&name);
-        }
+        let archived: Vec<WorkerInfo> = stopped
+            .iter()
+            .filter_map(|name| self.workers.remove(name))
+            .collect();
 
-        if count > 0 {
+        if !stopped.is_empty() {
+            self.archive_workers(archived)?;
             self.save()?;
         }
 
-        Ok(count)
+        Ok(stopped)
+    }
+}
+
+impl Default for WorkerRegistry {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -192,17 +840,14 @@ mod tests {
 
     #[test]
     fn test_registry_operations() {
-        let mut registry = WorkerRegistry::new();
+        let path = std::env::temp_dir().join(format!("claude-operations-registry-{}.json", std::process::id()));
+        fs::write(&path, "").unwrap();
+        let mut registry = WorkerRegistry::load_project(&path).unwrap();
 
         let worker = WorkerInfo {
-            name: "test-worker".to_string(),
-            agent_type: "coding-agent".to_string(),
             task_id: Some("task-123".to_string()),
-            tmux_session: "test-worker".to_string(),
-            working_dir: "/tmp".to_string(),
             spawned_at: 12345,
-            status: WorkerStatus::Ready,
-            messages_sent: 0,
+            ..test_worker("test-worker", WorkerStatus::Ready)
         };
 
         registry.register(worker).unwrap();
@@ -212,5 +857,278 @@ mod tests {
 
         registry.update_status("test-worker", WorkerStatus::Working).unwrap();
         assert_eq!(registry.get("test-worker").unwrap().status, WorkerStatus::Working);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_repairs_and_drops_damaged_workers() {
+        let content = r#"{"workers": {
+            "ok": {"name": "ok", "agent_type": "a", "task_id": null, "tmux_session": "ok", "working_dir": "/tmp",
+                   "spawned_at": 1, "status": "Idle", "messages_sent": 0},
+            "renamed": {"name": "", "agent_type": "a", "task_id": null, "tmux_session": "", "working_dir": "/tmp",
+                        "spawned_at": 1, "status": "working", "messages_sent": 0},
+            "odd": {"name": "odd", "agent_type": "a", "task_id": null, "tmux_session": "odd", "working_dir": "/tmp",
+                    "spawned_at": 1, "status": "Busy", "messages_sent": 0},
+            "broken": {"name": "broken"}
+        }}"#;
+
+        let (registry, issues) = WorkerRegistry::parse(content).unwrap();
+        assert_eq!(registry.count(), 3);
+        assert_eq!(registry.get("renamed").unwrap().name, "renamed");
+        assert_eq!(registry.get("renamed").unwrap().tmux_session, "renamed");
+        assert_eq!(registry.get("renamed").unwrap().status, WorkerStatus::Working);
+        assert_eq!(registry.get("odd").unwrap().status, WorkerStatus::Error);
+
+        let dropped: Vec<&str> = issues.iter().filter(|i| i.dropped).map(|i| i.worker.as_str()).collect();
+        assert_eq!(dropped, ["broken"]);
+        assert!(!issues.iter().any(|i| i.worker == "ok"));
+        assert_eq!(issues.iter().filter(|i| i.worker == "renamed").count(), 3);
+
+        assert!(WorkerRegistry::parse(r#"{"other": 1}"#).is_err());
+    }
+
+    #[test]
+    fn test_unreadable_entries_survive_save_until_discarded() {
+        let path = std::env::temp_dir().join(format!("claude-unreadable-registry-{}.json", std::process::id()));
+        fs::write(
+            &path,
+            r#"{"workers": {
+                "ok": {"name": "ok", "agent_type": "a", "task_id": null, "tmux_session": "ok", "working_dir": "/tmp",
+                       "spawned_at": 1, "status": "Idle", "messages_sent": 0},
+                "broken": {"name": "broken", "from_a_newer_version": true}
+            }}"#,
+        )
+        .unwrap();
+
+        let mut registry = WorkerRegistry::load_project(&path).unwrap();
+        assert!(!registry.exists("broken"));
+        registry.update_status("ok", WorkerStatus::Working).unwrap();
+
+        let saved: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["workers"]["broken"], serde_json::json!({"name": "broken", "from_a_newer_version": true}));
+        assert_eq!(saved["workers"]["ok"]["status"], "Working");
+
+        let mut registry = WorkerRegistry::load_project(&path).unwrap();
+        assert_eq!(registry.discard_unreadable(), 1);
+        registry.save().unwrap();
+        let saved: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert!(saved["workers"].get("broken").is_none());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_project_registry_is_found_and_saved_in_place() {
+        let root = std::env::temp_dir().join(format!("claude-project-registry-{}", std::process::id()));
+        let nested = root.join("services").join("api");
+        fs::create_dir_all(&nested).unwrap();
+        assert!(WorkerRegistry::find_project_registry(&nested).is_none_or(|path| !path.starts_with(&root)));
+
+        let path = root.join(PROJECT_REGISTRY_FILE);
+        fs::write(&path, "").unwrap();
+        assert_eq!(WorkerRegistry::find_project_registry(&nested), Some(path.clone()));
+
+        let mut registry = WorkerRegistry::load_project(&path).unwrap();
+        assert_eq!(registry.count(), 0);
+        registry.workers.insert(
+            "api-worker".to_string(),
+            WorkerInfo {
+                working_dir: nested.to_string_lossy().to_string(),
+                ..test_worker("api-worker", WorkerStatus::Ready)
+            },
+        );
+        registry.save().unwrap();
+
+        assert!(WorkerRegistry::load_project(&path).unwrap().exists("api-worker"));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_record_message_keeps_latest_history() {
+        let path = std::env::temp_dir().join(format!("claude-history-registry-{}.json", std::process::id()));
+        fs::write(&path, r#"{"workers": {"w1": {"name": "w1", "agent_type": "coding-agent", "task_id": null,
+            "tmux_session": "w1", "working_dir": "/tmp", "spawned_at": 0, "status": "Working", "messages_sent": 3}}}"#)
+            .unwrap();
+
+        // Registries written before history was kept still load
+        let mut registry = WorkerRegistry::load_project(&path).unwrap();
+        assert!(registry.get("w1").unwrap().history.is_empty());
+
+        for i in 0..DEFAULT_MESSAGE_HISTORY_LEN + 2 {
+            registry.record_message("w1", &format!("message {}", i), PayloadType::UserPrompt).unwrap();
+        }
+        registry.record_message("unknown", "ignored", PayloadType::Context).unwrap();
+        registry.record_message("w1", &"x".repeat(MESSAGE_PREVIEW_CHARS * 10), PayloadType::Context).unwrap();
+
+        let worker = WorkerRegistry::load_project(&path).unwrap().get("w1").unwrap().clone();
+        assert_eq!(worker.messages_sent, 3 + DEFAULT_MESSAGE_HISTORY_LEN as u32 + 3);
+        assert_eq!(worker.history.len(), DEFAULT_MESSAGE_HISTORY_LEN);
+        assert_eq!(worker.history[0].preview, "message 3");
+        assert!(!worker.history[0].is_truncated());
+        assert_eq!(worker.last_message_at, Some(worker.history.last().unwrap().timestamp));
+
+        // Only a bounded preview of long messages is kept
+        let long = worker.history.last().unwrap();
+        assert_eq!(long.kind, PayloadType::Context);
+        assert_eq!(long.preview.len(), MESSAGE_PREVIEW_CHARS);
+        assert_eq!(long.length, MESSAGE_PREVIEW_CHARS * 10);
+        assert!(long.is_truncated());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_reconcile_stops_workers_without_a_session() {
+        let path = std::env::temp_dir().join(format!("claude-reconcile-registry-{}.json", std::process::id()));
+        fs::write(&path, "").unwrap();
+
+        let mut registry = WorkerRegistry::load_project(&path).unwrap();
+        let name = format!("reconcile-test-{}", std::process::id());
+        registry.workers.insert(
+            name.clone(),
+            WorkerInfo {
+                tmux_session: format!("no-such-session-{}", std::process::id()),
+                ..test_worker(&name, WorkerStatus::Working)
+            },
+        );
+
+        assert_eq!(registry.live_view()[0].status, WorkerStatus::Stopped);
+        assert_eq!(registry.get(&name).unwrap().status, WorkerStatus::Working);
+
+        assert_eq!(registry.reconcile().unwrap(), vec![name.clone()]);
+        assert_eq!(registry.get(&name).unwrap().status, WorkerStatus::Stopped);
+        assert_eq!(WorkerRegistry::load_project(&path).unwrap().get(&name).unwrap().status, WorkerStatus::Stopped);
+        assert!(registry.reconcile().unwrap().is_empty());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_reload_picks_up_external_changes() {
+        let path = std::env::temp_dir().join(format!("claude-reload-registry-{}.json", std::process::id()));
+        fs::write(&path, "").unwrap();
+
+        let worker = |name: &str| test_worker(name, WorkerStatus::Ready);
+
+        let mut supervisor = WorkerRegistry::load_project(&path).unwrap();
+        supervisor.register(worker("old")).unwrap();
+
+        // Another invocation adds one worker and removes the other
+        let mut cli = WorkerRegistry::load_project(&path).unwrap();
+        cli.register(worker("spawned")).unwrap();
+        cli.unregister("old").unwrap();
+
+        assert_eq!(supervisor.reload().unwrap(), ["spawned"]);
+        assert!(supervisor.exists("spawned"));
+        assert!(!supervisor.exists("old"));
+        assert_eq!(supervisor.project_path(), Some(path.as_path()));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_status_parse_round_trips() {
+        for status in [
+            WorkerStatus::Starting,
+            WorkerStatus::Ready,
+            WorkerStatus::Working,
+            WorkerStatus::Idle,
+            WorkerStatus::Error,
+            WorkerStatus::Stopped,
+        ] {
+            assert_eq!(WorkerStatus::parse(&status.to_string()).unwrap(), status);
+        }
+        assert_eq!(WorkerStatus::parse("Idle").unwrap(), WorkerStatus::Idle);
+        assert!(WorkerStatus::parse("busy").is_err());
+    }
+
+    #[test]
+    fn test_sweep_stuck_starting() {
+        let dir = std::env::temp_dir().join(format!("claude-sweep-stuck-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(PROJECT_REGISTRY_FILE);
+        fs::write(&path, "").unwrap();
+        let mut registry = WorkerRegistry::load_project(&path).unwrap();
+
+        let worker = WorkerInfo {
+            tmux_session: "claude-injector-test-no-such-session".to_string(),
+            ..test_worker("stuck-worker", WorkerStatus::Starting)
+        };
+        registry.workers.insert(worker.name.clone(), worker);
+
+        let changed = registry.sweep_stuck_starting(60).unwrap();
+
+        assert_eq!(changed, vec!["stuck-worker".to_string()]);
+        assert_eq!(registry.get("stuck-worker").unwrap().status, WorkerStatus::Error);
+        assert_eq!(
+            WorkerRegistry::load_project(&path).unwrap().get("stuck-worker").unwrap().status,
+            WorkerStatus::Error
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_repair_mismatches() {
+        let mut registry = WorkerRegistry::new();
+
+        for (key, name, tmux_session) in [
+            ("renamed", "old-name", "renamed"),
+            ("moved", "moved", "gone-session"),
+            ("elsewhere", "elsewhere", "live-session"),
+        ] {
+            let worker = WorkerInfo {
+                tmux_session: tmux_session.to_string(),
+                ..test_worker(name, WorkerStatus::Working)
+            };
+            registry.workers.insert(key.to_string(), worker);
+        }
+
+        let live = ["renamed", "moved", "elsewhere", "live-session"];
+        let repaired = registry.repair_with(|session| live.contains(&session));
+
+        assert_eq!(repaired, ["moved", "renamed"]);
+        assert_eq!(registry.get("renamed").unwrap().name, "renamed");
+        assert_eq!(registry.get("moved").unwrap().tmux_session, "moved");
+        // Both sessions are live, so the mismatch is only reported
+        assert_eq!(registry.get("elsewhere").unwrap().tmux_session, "live-session");
+    }
+
+    #[test]
+    fn test_sweep_expired() {
+        let dir = std::env::temp_dir().join(format!("claude-sweep-expired-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(PROJECT_REGISTRY_FILE);
+        fs::write(&path, "").unwrap();
+        let mut registry = WorkerRegistry::load_project(&path).unwrap();
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        for (name, ttl_secs) in [("ttl-expired", Some(60)), ("ttl-fresh", Some(3600)), ("ttl-none", None)] {
+            let worker = WorkerInfo {
+                tmux_session: "claude-injector-test-no-such-session".to_string(),
+                spawned_at: now - 120,
+                ttl_secs,
+                ..test_worker(name, WorkerStatus::Working)
+            };
+            registry.workers.insert(worker.name.clone(), worker);
+        }
+
+        assert_eq!(registry.sweep_expired().unwrap(), vec!["ttl-expired".to_string()]);
+        assert!(registry.exists("ttl-fresh"));
+        assert!(registry.exists("ttl-none"));
+        assert!(!WorkerRegistry::load_project(&path).unwrap().exists("ttl-expired"));
+
+        // Archived beside the project registry, not in the home-level history
+        let history = registry.load_history().unwrap();
+        let archived: Vec<&str> = history.list().iter().map(|w| w.info.name.as_str()).collect();
+        assert_eq!(archived, ["ttl-expired"]);
+        assert_eq!(registry.history_path(), Some(dir.join(crate::worker_history::HISTORY_FILE)));
+
+        fs::remove_dir_all(&dir).unwrap();
     }
 }